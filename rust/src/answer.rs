@@ -0,0 +1,166 @@
+use std::{borrow::Cow, fmt};
+
+/// The answer to a puzzle part, normalized so that equivalent answers compare equal regardless of
+/// how they were produced.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Answer {
+    Integer(i128),
+    String(String),
+    /// Multi-line answers, e.g. ASCII art that spells out letters.
+    Lines(Vec<String>),
+}
+
+impl Answer {
+    /// Normalize `answer`: trailing whitespace is removed from each line, as are any leading and
+    /// trailing blank lines, and numbers are parsed so `007` and `7` are the same answer.
+    pub(crate) fn parse(answer: &str) -> Self {
+        let lines = answer
+            .lines()
+            .map(str::trim_end)
+            .skip_while(|line| line.is_empty())
+            .collect::<Vec<_>>();
+        let n_lines = lines.len() - lines.iter().rev().take_while(|l| l.is_empty()).count();
+        let lines = &lines[..n_lines];
+
+        match lines {
+            [] => Self::String(String::new()),
+            [line] => match line.trim().parse() {
+                Ok(n) => Self::Integer(n),
+                Err(_) => Self::String(line.trim().to_owned()),
+            },
+            lines => Self::Lines(lines.iter().map(|&l| l.to_owned()).collect()),
+        }
+    }
+
+    pub(crate) fn is_multi_line(&self) -> bool {
+        matches!(self, Self::Lines(_))
+    }
+
+    /// Compare `self` to `other` line by line.
+    pub(crate) fn diff<'a>(&'a self, other: &'a Self) -> Vec<LineDiff<'a>> {
+        let lines = |answer: &'a Self| -> Vec<Cow<'a, str>> {
+            match answer {
+                Self::Integer(n) => vec![Cow::Owned(n.to_string())],
+                Self::String(s) => vec![Cow::Borrowed(s)],
+                Self::Lines(lines) => lines.iter().map(|l| Cow::Borrowed(l.as_str())).collect(),
+            }
+        };
+
+        let mut old = lines(self).into_iter();
+        let mut new = lines(other).into_iter();
+        let mut diff = Vec::new();
+        loop {
+            diff.push(match (old.next(), new.next()) {
+                (Some(o), Some(n)) if o == n => LineDiff::Same(o),
+                (Some(o), Some(n)) => LineDiff::Changed(o, n),
+                (Some(o), None) => LineDiff::Removed(o),
+                (None, Some(n)) => LineDiff::Added(n),
+                (None, None) => break,
+            });
+        }
+        diff
+    }
+}
+
+impl fmt::Display for Answer {
+    /// The alternate flag starts multi-line answers on a new line.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Integer(n) => write!(f, "{n}"),
+            Self::String(s) => write!(f, "{s}"),
+            Self::Lines(lines) => {
+                for (i, line) in lines.iter().enumerate() {
+                    if f.alternate() || i > 0 {
+                        writeln!(f)?;
+                    }
+                    write!(f, "{line}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+macro_rules! impl_from_int {
+    ( $( $int:ty ),+ ) => {
+        $(
+            impl From<$int> for Answer {
+                fn from(n: $int) -> Self {
+                    Self::Integer(n as i128)
+                }
+            }
+        )+
+    };
+}
+
+impl_from_int!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize, i128);
+
+impl From<u128> for Answer {
+    fn from(n: u128) -> Self {
+        match i128::try_from(n) {
+            Ok(n) => Self::Integer(n),
+            Err(_) => Self::String(n.to_string()),
+        }
+    }
+}
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Self::parse(&s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Self::parse(s)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum LineDiff<'a> {
+    Same(Cow<'a, str>),
+    Changed(Cow<'a, str>, Cow<'a, str>),
+    Removed(Cow<'a, str>),
+    Added(Cow<'a, str>),
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use super::{Answer, LineDiff};
+
+    #[test]
+    fn parse() {
+        assert_eq!(Answer::Integer(7), Answer::parse("007\n"));
+        assert_eq!(Answer::Integer(-12), Answer::parse("  -12  "));
+        assert_eq!(Answer::String("6,0,3".into()), Answer::parse("6,0,3 \n"));
+        assert_eq!(
+            Answer::Lines(vec!["#..#".into(), " ##".into()]),
+            Answer::parse("\n#..#  \n ##\n\n")
+        );
+        assert_eq!(Answer::from(7_u8), Answer::from("7".to_owned()));
+    }
+
+    #[test]
+    fn display() {
+        let art = Answer::Lines(vec!["#..#".into(), "####".into()]);
+        assert_eq!("#..#\n####", art.to_string());
+        assert_eq!("\n#..#\n####", format!("{art:#}"));
+        assert_eq!("42", format!("{:#}", Answer::Integer(42)));
+    }
+
+    #[test]
+    fn diff() {
+        let old = Answer::Lines(vec!["#..#".into(), "####".into()]);
+        let new = Answer::Lines(vec!["#..#".into(), "#..#".into(), "#..#".into()]);
+        assert_eq!(
+            vec![
+                LineDiff::Same(Cow::Borrowed("#..#")),
+                LineDiff::Changed(Cow::Borrowed("####"), Cow::Borrowed("#..#")),
+                LineDiff::Added(Cow::Borrowed("#..#")),
+            ],
+            old.diff(&new)
+        );
+    }
+}
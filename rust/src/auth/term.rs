@@ -3,10 +3,9 @@ use std::{
     iter,
 };
 
-use crossterm::{
-    cursor, execute, style,
-    terminal::{self, disable_raw_mode, enable_raw_mode},
-};
+use crossterm::{cursor, execute, style, terminal};
+
+use crate::terminal::TerminalGuard;
 
 pub(super) fn read_token() -> io::Result<Option<String>> {
     let mut gh_pat_reader = TokenReader::new()?;
@@ -14,14 +13,15 @@ pub(super) fn read_token() -> io::Result<Option<String>> {
 }
 
 struct TokenReader {
+    _guard: TerminalGuard,
     stdout: io::Stdout,
     buf: Vec<u8>,
 }
 
 impl TokenReader {
     fn new() -> io::Result<Self> {
-        enable_raw_mode()?;
         Ok(Self {
+            _guard: TerminalGuard::raw()?,
             stdout: io::stdout(),
             buf: Vec::with_capacity(128),
         })
@@ -84,9 +84,3 @@ impl TokenReader {
         Ok(Some(gh_pat))
     }
 }
-
-impl Drop for TokenReader {
-    fn drop(&mut self) {
-        disable_raw_mode().unwrap();
-    }
-}
@@ -2,9 +2,12 @@ use std::{
     fmt, fs, io,
     path::{Path, PathBuf},
     sync::LazyLock,
+    time::SystemTime,
 };
 
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, bail};
+
+use crate::answer::Answer;
 
 pub(crate) static PUZZLE_ANSWERS_DIR: LazyLock<PathBuf> =
     LazyLock::new(|| Path::new(crate::PUZZLE_DIR).join("answers"));
@@ -25,26 +28,26 @@ fn create_answers_dir(year: u32) -> Result<PathBuf> {
     Ok(dir)
 }
 
-#[derive(PartialEq, Debug)]
+#[derive(Clone, PartialEq, Debug)]
 pub(crate) struct DayPartCommit {
-    pub(crate) answer: String,
+    pub(crate) answer: Answer,
 }
 
 impl fmt::Display for DayPartCommit {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        writeln!(f, "{}", self.answer.trim())?;
+        writeln!(f, "{}", self.answer)?;
         Ok(())
     }
 }
 
 impl DayPartCommit {
-    pub(crate) fn new(answer: &dyn fmt::Display) -> Self {
-        let answer = answer.to_string();
+    pub(crate) fn new(answer: &Answer) -> Self {
+        let answer = answer.clone();
         Self { answer }
     }
 
     fn parse_from_file(path: impl AsRef<Path>) -> Result<Option<Self>> {
-        match fs::read_to_string(&path) {
+        match crate::store::read_to_string(&path) {
             Ok(commit) => Ok(Some(Self::parse(&commit).with_context(|| {
                 format!("failed to parse commit file: {}", path.as_ref().display())
             })?)),
@@ -57,21 +60,199 @@ impl DayPartCommit {
 
     fn parse(commit_str: &str) -> Result<Self> {
         Ok(Self {
-            answer: commit_str.trim().to_owned(),
+            answer: Answer::parse(commit_str),
         })
     }
 
-    pub(crate) fn write(&self, year: u32, day_i: u32, part: crate::Part) -> Result<()> {
+    /// Write the commit and add it to the part's history, recording the executor that produced it.
+    pub(crate) fn write(
+        &self,
+        year: u32,
+        day_i: u32,
+        part: crate::Part,
+        executor: Option<&str>,
+    ) -> Result<()> {
         let dir = create_answers_dir(year)?;
 
         let path = dir.join(format!("day{day_i}.{}", part.number()));
+        let history_path = history_path(&dir, day_i, part);
+
+        // Keep a commit made before there was a history, dated by when its file was written.
+        if !history_path.exists()
+            && let Some(existing) = Self::parse_from_file(&path)?
+        {
+            let timestamp = fs::metadata(&path)
+                .and_then(|m| m.modified())
+                .map(unix_timestamp)
+                .unwrap_or_default();
+            append_history(
+                &history_path,
+                &CommitHistoryEntry {
+                    timestamp,
+                    executor: None,
+                    commit: existing,
+                },
+            )?;
+        }
+
         let commit = self.to_string();
 
-        fs::write(&path, commit)
-            .with_context(|| format!("failed to write puzzle commit to file: {}", path.display()))
+        crate::store::write(&path, commit).with_context(|| {
+            format!("failed to write puzzle commit to file: {}", path.display())
+        })?;
+
+        append_history(
+            &history_path,
+            &CommitHistoryEntry {
+                timestamp: unix_timestamp(SystemTime::now()),
+                executor: executor.map(str::to_owned),
+                commit: self.clone(),
+            },
+        )
+    }
+}
+
+pub(crate) struct CommitHistoryEntry {
+    pub(crate) timestamp: u64,
+    pub(crate) executor: Option<String>,
+    pub(crate) commit: DayPartCommit,
+}
+
+impl CommitHistoryEntry {
+    fn parse(line: &str) -> Result<Self> {
+        let mut fields = line.splitn(3, '\t');
+        let (Some(timestamp), Some(executor), Some(answer)) =
+            (fields.next(), fields.next(), fields.next())
+        else {
+            bail!("expected 3 fields");
+        };
+        Ok(Self {
+            timestamp: timestamp.parse().context("invalid timestamp")?,
+            executor: (executor != "-").then(|| executor.to_owned()),
+            commit: DayPartCommit {
+                answer: Answer::parse(&unescape(answer)),
+            },
+        })
+    }
+}
+
+impl fmt::Display for CommitHistoryEntry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{}\t{}\t{}",
+            self.timestamp,
+            self.executor.as_deref().unwrap_or("-"),
+            escape(&self.commit.answer.to_string())
+        )
     }
 }
 
+fn history_path(dir: &Path, day_i: u32, part: crate::Part) -> PathBuf {
+    dir.join(format!("day{day_i}.{}.log", part.number()))
+}
+
+fn unix_timestamp(time: SystemTime) -> u64 {
+    time.duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// History entries are one per line, so multi-line answers have their newlines escaped.
+fn escape(answer: &str) -> String {
+    answer.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+fn unescape(answer: &str) -> String {
+    let mut unescaped = String::with_capacity(answer.len());
+    let mut chars = answer.chars();
+    while let Some(c) = chars.next() {
+        match (c, chars.clone().next()) {
+            ('\\', Some('n')) => {
+                unescaped.push('\n');
+                chars.next();
+            }
+            ('\\', Some('\\')) => {
+                unescaped.push('\\');
+                chars.next();
+            }
+            _ => unescaped.push(c),
+        }
+    }
+    unescaped
+}
+
+fn append_history(path: &Path, entry: &CommitHistoryEntry) -> Result<()> {
+    crate::store::append(path, entry.to_string())
+        .with_context(|| format!("failed to append to commit history: {}", path.display()))
+}
+
+/// Read the history of a part's commits, oldest first.
+pub(crate) fn get_commit_history(
+    year: u32,
+    day_i: u32,
+    part: crate::Part,
+) -> Result<Vec<CommitHistoryEntry>> {
+    let path = history_path(&create_answers_dir(year)?, day_i, part);
+    let history = match crate::store::read_to_string(&path) {
+        Ok(history) => history,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => {
+            return Err(err)
+                .with_context(|| format!("failed to read commit history: {}", path.display()));
+        }
+    };
+
+    history
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(i, line)| {
+            CommitHistoryEntry::parse(line).with_context(|| {
+                format!(
+                    "failed to parse commit history line {}: {}",
+                    i + 1,
+                    path.display()
+                )
+            })
+        })
+        .collect()
+}
+
+/// The entry (1-based) before the one where the current answer was first committed, which is what
+/// `revert` restores by default. Reverting appends the restored answer to the history, so going by
+/// the first time it was committed lets repeated reverts walk back instead of undoing each other.
+pub(crate) fn previous_entry(history: &[CommitHistoryEntry]) -> Option<usize> {
+    let current = &history.last()?.commit;
+    let first_i = history.iter().position(|entry| entry.commit == *current)?;
+    (first_i > 0).then_some(first_i)
+}
+
+/// Restore the answer of history entry `entry_i` (1-based), returning the restored entry.
+pub(crate) fn revert(
+    year: u32,
+    day_i: u32,
+    part: crate::Part,
+    entry_i: usize,
+) -> Result<CommitHistoryEntry> {
+    let mut history = get_commit_history(year, day_i, part)?;
+    if history.is_empty() {
+        bail!("day {day_i} {part} has no commit history");
+    }
+    if !(1..=history.len()).contains(&entry_i) {
+        bail!(
+            "day {day_i} {part} has no history entry {entry_i}, expected 1 to {}",
+            history.len()
+        );
+    }
+
+    let entry = history.swap_remove(entry_i - 1);
+    entry
+        .commit
+        .write(year, day_i, part, entry.executor.as_deref())?;
+    Ok(entry)
+}
+
 pub(crate) fn get_existing_commits(year: u32, day_i: u32) -> Result<DayCommits> {
     let dir = create_answers_dir(year)?;
     let commit1_path = dir.join(format!("day{day_i}.1"));
@@ -81,3 +262,53 @@ pub(crate) fn get_existing_commits(year: u32, day_i: u32) -> Result<DayCommits>
         DayPartCommit::parse_from_file(&commit2_path)?,
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::answer::Answer;
+
+    #[test]
+    fn history_entry_roundtrip() {
+        let entry = super::CommitHistoryEntry {
+            timestamp: 1_734_307_200,
+            executor: Some("part2_fast".into()),
+            commit: super::DayPartCommit {
+                answer: Answer::Lines(vec!["#..#".into(), "\\n.#".into()]),
+            },
+        };
+        let line = entry.to_string();
+        assert_eq!("1734307200\tpart2_fast\t#..#\\n\\\\n.#\n", line);
+
+        let parsed = super::CommitHistoryEntry::parse(line.trim_end()).unwrap();
+        assert_eq!(entry.timestamp, parsed.timestamp);
+        assert_eq!(entry.executor, parsed.executor);
+        assert_eq!(entry.commit, parsed.commit);
+
+        let parsed = super::CommitHistoryEntry::parse("0\t-\t42").unwrap();
+        assert_eq!(None, parsed.executor);
+        assert_eq!(Answer::Integer(42), parsed.commit.answer);
+    }
+
+    #[test]
+    fn previous_entry() {
+        let history = |answers: &[u64]| {
+            answers
+                .iter()
+                .map(|&answer| super::CommitHistoryEntry {
+                    timestamp: 0,
+                    executor: None,
+                    commit: super::DayPartCommit {
+                        answer: answer.into(),
+                    },
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(None, super::previous_entry(&history(&[])));
+        assert_eq!(None, super::previous_entry(&history(&[1])));
+        assert_eq!(Some(2), super::previous_entry(&history(&[1, 2, 3])));
+        // After reverting from 3 to 2, then from 2 to 1.
+        assert_eq!(Some(1), super::previous_entry(&history(&[1, 2, 3, 2])));
+        assert_eq!(None, super::previous_entry(&history(&[1, 2, 3, 2, 1])));
+    }
+}
@@ -0,0 +1,479 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    sync::LazyLock,
+};
+
+use anyhow::{Context, Result, bail};
+
+use crate::{answer::Answer, auth, commit, input, store};
+
+/// The directories of the puzzle data store that are bundled into archives, as they're named in
+/// archives.
+static DATA_DIRS: LazyLock<[String; 2]> = LazyLock::new(|| {
+    [
+        store_path(&input::PUZZLE_INPUTS_DIR),
+        store_path(&commit::PUZZLE_ANSWERS_DIR),
+    ]
+});
+
+static TOKEN_PATH: LazyLock<String> = LazyLock::new(|| store_path(&auth::TOKEN_PATH));
+
+/// The name in archives of a path in the puzzle data store.
+fn store_path(path: &Path) -> String {
+    let rel_path = path
+        .strip_prefix(crate::PUZZLE_DIR)
+        .expect("the path is in the puzzle data store");
+    archive_path(rel_path)
+}
+
+/// Join the components of a path relative to the puzzle data store with `/`, whatever the platform.
+fn archive_path(rel_path: &Path) -> String {
+    rel_path
+        .iter()
+        .map(|c| c.to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Bundle the inputs, test inputs and committed answers into a tar archive at `archive_path`,
+/// returning the number of files written.
+pub(crate) fn export(archive_path: &Path, include_token: bool) -> Result<usize> {
+    let root = Path::new(crate::PUZZLE_DIR);
+
+    let mut files = Vec::new();
+    for dir in &*DATA_DIRS {
+        collect_files(root, Path::new(dir), &mut files)?;
+    }
+    files.retain(|(path, _)| include_token || *path != *TOKEN_PATH);
+    files.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+
+    let mut archive = Vec::new();
+    tar::write(&mut archive, &files)?;
+    fs::write(archive_path, archive)
+        .with_context(|| format!("failed to write archive: {}", archive_path.display()))?;
+
+    Ok(files.len())
+}
+
+fn collect_files(root: &Path, rel_dir: &Path, files: &mut Vec<(String, Vec<u8>)>) -> Result<()> {
+    let dir = root.join(rel_dir);
+    let entries = match fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => {
+            return Err(err)
+                .with_context(|| format!("failed to read directory: {}", dir.display()));
+        }
+    };
+
+    for entry in entries {
+        let entry =
+            entry.with_context(|| format!("failed to read directory: {}", dir.display()))?;
+        let rel_path = rel_dir.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            collect_files(root, &rel_path, files)?;
+        } else {
+            let contents = fs::read(entry.path())
+                .with_context(|| format!("failed to read file: {}", entry.path().display()))?;
+            files.push((archive_path(&rel_path), contents));
+        }
+    }
+
+    Ok(())
+}
+
+/// Rewrite the inputs, test inputs and committed answers in place, encrypted with the configured
+/// key or decrypted, returning the number of files changed. The session token is never encrypted.
+pub(crate) fn set_encrypted(encrypt: bool) -> Result<usize> {
+    if encrypt && !store::is_encryption_enabled()? {
+        bail!("no encryption key is configured, set AOC_STORE_KEY");
+    }
+
+    let root = Path::new(crate::PUZZLE_DIR);
+
+    let mut files = Vec::new();
+    for dir in &*DATA_DIRS {
+        collect_files(root, Path::new(dir), &mut files)?;
+    }
+
+    let mut n_changed = 0;
+    for (path, contents) in files {
+        if path == *TOKEN_PATH || store::is_encrypted(&contents) == encrypt {
+            continue;
+        }
+        let contents =
+            store::decode(contents).with_context(|| format!("failed to decrypt file: {path}"))?;
+        let contents = if encrypt {
+            store::encode(&contents).with_context(|| format!("failed to encrypt file: {path}"))?
+        } else {
+            contents
+        };
+        let target = root.join(&path);
+        fs::write(&target, contents)
+            .with_context(|| format!("failed to write file: {}", target.display()))?;
+        n_changed += 1;
+    }
+
+    Ok(n_changed)
+}
+
+#[derive(Default)]
+pub(crate) struct ImportSummary {
+    pub(crate) created: usize,
+    pub(crate) unchanged: usize,
+    pub(crate) merged: usize,
+    pub(crate) overwritten: usize,
+}
+
+enum ImportAction {
+    Create,
+    Unchanged,
+    Merge(Vec<u8>),
+    Conflict,
+}
+
+/// Unpack an archive made by [`export`] into the puzzle data store.
+///
+/// Commit histories are merged. Any other file that already exists with different contents is a
+/// conflict, and nothing is written unless `force` is set, in which case they're overwritten.
+pub(crate) fn import(
+    archive_path: &Path,
+    include_token: bool,
+    force: bool,
+) -> Result<ImportSummary> {
+    let archive = fs::read(archive_path)
+        .with_context(|| format!("failed to read archive: {}", archive_path.display()))?;
+    let files = tar::read(&archive)
+        .with_context(|| format!("failed to read archive: {}", archive_path.display()))?;
+
+    import_files(Path::new(crate::PUZZLE_DIR), files, include_token, force)
+}
+
+fn import_files(
+    root: &Path,
+    files: Vec<(String, Vec<u8>)>,
+    include_token: bool,
+    force: bool,
+) -> Result<ImportSummary> {
+    let mut actions = Vec::new();
+    for (path, contents) in files {
+        if path == *TOKEN_PATH && !include_token {
+            continue;
+        }
+        let target = root.join(validate_path(&path)?);
+        let action = import_action(&path, &target, &contents)?;
+        actions.push((path, target, contents, action));
+    }
+
+    let conflicts = actions
+        .iter()
+        .filter(|(.., action)| matches!(action, ImportAction::Conflict))
+        .map(|(path, ..)| path.as_str())
+        .collect::<Vec<_>>();
+    if !conflicts.is_empty() && !force {
+        bail!(
+            "{} files differ from the existing data, use `--force` to overwrite them:\n{}",
+            conflicts.len(),
+            conflicts
+                .iter()
+                .map(|p| format!("  {p}"))
+                .collect::<Vec<_>>()
+                .join("\n")
+        );
+    }
+
+    let mut summary = ImportSummary::default();
+    for (path, target, contents, action) in actions {
+        let contents = match action {
+            ImportAction::Unchanged => {
+                summary.unchanged += 1;
+                continue;
+            }
+            ImportAction::Create => {
+                summary.created += 1;
+                store_contents(&path, contents)?
+            }
+            ImportAction::Merge(merged) => {
+                summary.merged += 1;
+                merged
+            }
+            ImportAction::Conflict => {
+                summary.overwritten += 1;
+                store_contents(&path, contents)?
+            }
+        };
+
+        if let Some(dir) = target.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("failed to create directory: {}", dir.display()))?;
+        }
+        fs::write(&target, contents)
+            .with_context(|| format!("failed to write file: {}", target.display()))?;
+    }
+
+    Ok(summary)
+}
+
+/// Encrypt or decrypt an archive entry to match the store, as [`store::write`] would have written
+/// it. The session token is never encrypted.
+fn store_contents(path: &str, contents: Vec<u8>) -> Result<Vec<u8>> {
+    if path == *TOKEN_PATH || store::is_encrypted(&contents) == store::is_encryption_enabled()? {
+        return Ok(contents);
+    }
+    let contents = store::decode(contents)
+        .with_context(|| format!("failed to decrypt archive entry: {path}"))?;
+    store::encode(&contents).with_context(|| format!("failed to encrypt archive entry: {path}"))
+}
+
+/// Only allow archive paths inside the data directories so an archive can't write elsewhere.
+fn validate_path(path: &str) -> Result<PathBuf> {
+    let components = path.split('/').collect::<Vec<_>>();
+    let valid = components
+        .iter()
+        .all(|c| !c.is_empty() && *c != "." && *c != "..")
+        && DATA_DIRS.iter().any(|dir| {
+            Path::new(path)
+                .strip_prefix(dir)
+                .is_ok_and(|rest| !rest.as_os_str().is_empty())
+        });
+    if !valid {
+        bail!("invalid path in archive: {path}");
+    }
+    Ok(components.iter().collect())
+}
+
+fn import_action(path: &str, target: &Path, contents: &[u8]) -> Result<ImportAction> {
+    let existing = match fs::read(target) {
+        Ok(existing) => existing,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(ImportAction::Create),
+        Err(err) => {
+            return Err(err).with_context(|| format!("failed to read file: {}", target.display()));
+        }
+    };
+
+    if existing == contents {
+        return Ok(ImportAction::Unchanged);
+    }
+
+    // Either side may be encrypted, or encrypted with a different nonce, so compare plaintexts.
+    let existing = store::decode(existing)
+        .with_context(|| format!("failed to decrypt file: {}", target.display()))?;
+    let contents = store::decode(contents.to_vec())
+        .with_context(|| format!("failed to decrypt archive entry: {path}"))?;
+    if existing == contents {
+        return Ok(ImportAction::Unchanged);
+    }
+
+    let is_answers = Path::new(path).starts_with(&DATA_DIRS[1]);
+    let existing_str = String::from_utf8_lossy(&existing);
+    let contents_str = String::from_utf8_lossy(&contents);
+
+    Ok(if is_answers && path.ends_with(".log") {
+        match merge_lines(&existing_str, &contents_str) {
+            Some(merged) => ImportAction::Merge(store::encode(merged.as_bytes())?),
+            None => ImportAction::Unchanged,
+        }
+    } else if is_answers && Answer::parse(&existing_str) == Answer::parse(&contents_str) {
+        ImportAction::Unchanged
+    } else {
+        ImportAction::Conflict
+    })
+}
+
+/// Append the lines of `other` that `existing` doesn't have, or `None` if there are none.
+fn merge_lines(existing: &str, other: &str) -> Option<String> {
+    let existing_lines = existing.lines().collect::<Vec<_>>();
+    let new_lines = other
+        .lines()
+        .filter(|line| !line.is_empty() && !existing_lines.contains(line))
+        .collect::<Vec<_>>();
+    if new_lines.is_empty() {
+        return None;
+    }
+
+    let mut merged = existing.to_owned();
+    if !merged.is_empty() && !merged.ends_with('\n') {
+        merged.push('\n');
+    }
+    for line in new_lines {
+        merged.push_str(line);
+        merged.push('\n');
+    }
+    Some(merged)
+}
+
+/// A minimal reader and writer for ustar archives of regular files, so exports can be inspected
+/// with `tar`.
+mod tar {
+    use std::io::{self, Write};
+
+    use anyhow::{Context, Result, bail};
+
+    const BLOCK_SIZE: usize = 512;
+    const NAME_LEN: usize = 100;
+
+    pub(super) fn write(out: &mut impl Write, files: &[(String, Vec<u8>)]) -> Result<()> {
+        for (path, contents) in files {
+            if path.len() >= NAME_LEN {
+                bail!("path is too long for the archive: {path}");
+            }
+
+            let mut header = [0_u8; BLOCK_SIZE];
+            header[..path.len()].copy_from_slice(path.as_bytes());
+            write_octal(&mut header[100..108], 0o644);
+            write_octal(&mut header[108..116], 0);
+            write_octal(&mut header[116..124], 0);
+            write_octal(&mut header[124..136], contents.len() as u64);
+            write_octal(&mut header[136..148], 0);
+            header[156] = b'0';
+            header[257..263].copy_from_slice(b"ustar\0");
+            header[263..265].copy_from_slice(b"00");
+
+            header[148..156].fill(b' ');
+            let checksum = header.iter().map(|&b| b as u64).sum();
+            write_octal(&mut header[148..155], checksum);
+
+            out.write_all(&header)?;
+            out.write_all(contents)?;
+            out.write_all(&[0; BLOCK_SIZE][..padding(contents.len())])?;
+        }
+
+        out.write_all(&[0; 2 * BLOCK_SIZE])?;
+        Ok(())
+    }
+
+    pub(super) fn read(archive: &[u8]) -> Result<Vec<(String, Vec<u8>)>> {
+        let mut files = Vec::new();
+        let mut offset = 0;
+
+        while let Some(header) = archive.get(offset..offset + BLOCK_SIZE) {
+            if header.iter().all(|&b| b == 0) {
+                break;
+            }
+            if &header[257..262] != b"ustar" {
+                bail!("not a tar archive, or it is corrupted at byte {offset}");
+            }
+
+            let name = read_str(&header[..NAME_LEN]);
+            let prefix = read_str(&header[345..500]);
+            let path = if prefix.is_empty() {
+                name.to_owned()
+            } else {
+                format!("{prefix}/{name}")
+            };
+            let size = read_octal(&header[124..136])
+                .with_context(|| format!("invalid size for archive entry: {path}"))?;
+
+            offset += BLOCK_SIZE;
+            let contents = archive
+                .get(offset..offset + size)
+                .with_context(|| format!("archive is truncated in entry: {path}"))?;
+            offset += size + padding(size);
+
+            // Skip directories and anything else that isn't a regular file.
+            if matches!(header[156], b'0' | b'\0') {
+                files.push((path, contents.to_vec()));
+            }
+        }
+
+        Ok(files)
+    }
+
+    fn padding(len: usize) -> usize {
+        (BLOCK_SIZE - len % BLOCK_SIZE) % BLOCK_SIZE
+    }
+
+    /// Write `value` as zero-padded octal digits followed by a NUL, filling `field`.
+    fn write_octal(field: &mut [u8], value: u64) {
+        let digits = format!("{value:0width$o}", width = field.len() - 1);
+        field[..digits.len()].copy_from_slice(digits.as_bytes());
+        field[digits.len()] = 0;
+    }
+
+    fn read_octal(field: &[u8]) -> io::Result<usize> {
+        let digits = read_str(field).trim();
+        usize::from_str_radix(digits, 8).map_err(io::Error::other)
+    }
+
+    fn read_str(field: &[u8]) -> &str {
+        let end = field.iter().position(|&b| b == 0).unwrap_or(field.len());
+        std::str::from_utf8(&field[..end]).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn tar_roundtrip() {
+        let files = vec![
+            ("inputs/2024/day1".to_owned(), b"3   4\n4   3\n".to_vec()),
+            ("answers/2024/day1.1".to_owned(), vec![b'7'; 1024]),
+            ("answers/2024/day1.1.log".to_owned(), Vec::new()),
+        ];
+
+        let mut archive = Vec::new();
+        super::tar::write(&mut archive, &files).unwrap();
+        assert_eq!(0, archive.len() % 512);
+        assert_eq!(files, super::tar::read(&archive).unwrap());
+
+        assert!(super::tar::read(&[1; 512]).is_err());
+    }
+
+    #[test]
+    fn validate_path() {
+        assert!(super::validate_path("inputs/2024/day1").is_ok());
+        assert!(super::validate_path("answers/2024/day1.1").is_ok());
+        assert!(super::validate_path("timings/2024.log").is_err());
+        assert!(super::validate_path("inputs/../../Cargo.toml").is_err());
+        assert!(super::validate_path("/inputs/2024/day1").is_err());
+        assert!(super::validate_path("inputs").is_err());
+    }
+
+    #[test]
+    fn import_plaintext_into_encrypted_store() {
+        let root = std::env::temp_dir().join(format!("aoc-import-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("inputs/2024")).unwrap();
+        std::fs::write(root.join("inputs/2024/day2"), b"7 6 4 2 1\n").unwrap();
+
+        let files = vec![
+            ("inputs/2024/day1".to_owned(), b"3   4\n4   3\n".to_vec()),
+            ("inputs/2024/day2".to_owned(), b"1 2 7 8 9\n".to_vec()),
+            ("inputs/.token".to_owned(), b"session".to_vec()),
+        ];
+        let (summary, written) = crate::store::tests::with_key("0123456789abcdef", || {
+            let summary = super::import_files(&root, files.clone(), true, true).unwrap();
+            let written = files
+                .iter()
+                .map(|(path, _)| {
+                    let contents = std::fs::read(root.join(path)).unwrap();
+                    let is_encrypted = crate::store::is_encrypted(&contents);
+                    (is_encrypted, crate::store::decode(contents).unwrap())
+                })
+                .collect::<Vec<_>>();
+            (summary, written)
+        });
+        std::fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!((2, 1), (summary.created, summary.overwritten));
+        assert_eq!(
+            files
+                .into_iter()
+                .map(|(path, contents)| (path != "inputs/.token", contents))
+                .collect::<Vec<_>>(),
+            written
+        );
+    }
+
+    #[test]
+    fn merge_lines() {
+        assert_eq!(
+            Some("1\t-\t12\n2\tpart1\t42\n3\tpart1\t7\n".to_owned()),
+            super::merge_lines("1\t-\t12\n2\tpart1\t42\n", "1\t-\t12\n3\tpart1\t7\n")
+        );
+        assert_eq!(
+            None,
+            super::merge_lines("1\t-\t12\n2\tpart1\t42\n", "2\tpart1\t42\n")
+        );
+    }
+}
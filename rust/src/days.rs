@@ -5,9 +5,10 @@ use std::{
 };
 
 use adventofcode as aoc;
-use anyhow::{Result, bail};
+use anyhow::{Context, Result, bail};
 use criterion::{BenchmarkId, Criterion};
 use crossterm::style;
+use itertools::Itertools;
 
 type YearAndDay = (u32, u32);
 
@@ -57,6 +58,14 @@ inventory::collect!(DayModule);
 
 static DAY_EXECUTORS: std::sync::LazyLock<HashMap<YearAndDay, DayExecutors>> =
     std::sync::LazyLock::new(|| {
+        let problems = validate_modules(inventory::iter::<DayModule>());
+        if !problems.is_empty() {
+            eprintln!(
+                "warning: invalid day module registrations:\n{}",
+                problems.iter().map(|p| format!("  - {p}")).join("\n")
+            );
+        }
+
         inventory::iter::<DayModule>()
             .map(|m| (m.key, m.executors))
             .collect()
@@ -69,6 +78,78 @@ static DAY_VISUALIZERS: std::sync::LazyLock<HashMap<YearAndDay, DayVisualizers>>
             .collect()
     });
 
+/// Mistakes in module registrations that would otherwise silently shadow or hide days.
+fn validate_modules<'m>(modules: impl IntoIterator<Item = &'m DayModule>) -> Vec<String> {
+    let mut problems = Vec::new();
+    let mut seen = HashMap::<YearAndDay, usize>::new();
+
+    for module in modules {
+        let (year, day_i) = module.key;
+
+        *seen.entry(module.key).or_default() += 1;
+
+        if !(1..=25).contains(&day_i) {
+            problems.push(format!(
+                "year {year} day {day_i}: day is out of range 1..=25"
+            ));
+        }
+
+        for (part, executors) in [
+            (crate::Part::Part1, module.executors.0),
+            (crate::Part::Part2, module.executors.1),
+        ] {
+            if executors.is_empty() {
+                problems.push(format!("year {year} day {day_i}: {part} has no executors"));
+            }
+
+            for name in executors.iter().map(|e| e.name).duplicates() {
+                problems.push(format!(
+                    "year {year} day {day_i}: {part} has multiple executors named `{name}`"
+                ));
+            }
+        }
+    }
+
+    for ((year, day_i), n) in seen.into_iter().sorted() {
+        if n > 1 {
+            problems.push(format!("year {year} day {day_i}: registered {n} times"));
+        }
+    }
+
+    problems
+}
+
+pub(crate) fn registered_years() -> Vec<u32> {
+    let mut years = DAY_EXECUTORS
+        .keys()
+        .map(|&(year, _)| year)
+        .collect::<Vec<_>>();
+    years.sort_unstable();
+    years.dedup();
+    years
+}
+
+pub(crate) fn registered_days(year: u32) -> Vec<u32> {
+    let mut days = DAY_EXECUTORS
+        .keys()
+        .filter(|&&(y, _)| y == year)
+        .map(|&(_, day)| day)
+        .collect::<Vec<_>>();
+    days.sort_unstable();
+    days
+}
+
+/// The days registered in at least one year.
+pub(crate) fn registered_days_of_any_year() -> Vec<u32> {
+    let mut days = DAY_EXECUTORS
+        .keys()
+        .map(|&(_, day)| day)
+        .collect::<Vec<_>>();
+    days.sort_unstable();
+    days.dedup();
+    days
+}
+
 type DayExecutors = (&'static [DayPartExecutor], &'static [DayPartExecutor]);
 type DayPartExecutorFn = for<'input> fn(&'input str) -> Option<DayPartAnswer>;
 
@@ -81,9 +162,17 @@ impl DayPartExecutor {
     pub const fn new(name: &'static str, executor: DayPartExecutorFn) -> Self {
         Self { name, executor }
     }
+
+    pub(crate) fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub(crate) fn execute(&self, input: &str) -> Option<DayPartAnswer> {
+        (self.executor)(input)
+    }
 }
 
-pub(crate) type DayPartAnswer = Box<dyn ::std::fmt::Display>;
+pub(crate) type DayPartAnswer = crate::answer::Answer;
 
 #[derive(Default)]
 pub(crate) struct DayResult(
@@ -92,11 +181,13 @@ pub(crate) struct DayResult(
 );
 
 pub(crate) struct DayPartResult {
+    pub(crate) name: &'static str,
     pub(crate) answer: DayPartAnswer,
     pub(crate) duration: DayPartDuration,
+    pub(crate) mem: Option<crate::mem::MemUsage>,
 }
 
-pub(crate) struct DayPartDuration(Duration);
+pub(crate) struct DayPartDuration(pub(crate) Duration);
 
 impl DayPartDuration {
     pub(crate) fn speed_color(&self) -> style::Color {
@@ -203,7 +294,8 @@ impl fmt::Display for DayPartDuration {
 }
 
 type DayVisualizers = (Option<DayPartVisualizerFn>, Option<DayPartVisualizerFn>);
-type DayPartVisualizerFn = DayPartExecutorFn;
+type DayPartVisualizerFn =
+    fn(&str, &crate::viz::VizContext) -> anyhow::Result<Option<crate::answer::Answer>>;
 
 pub(crate) fn execute_day(
     year: u32,
@@ -218,12 +310,16 @@ pub(crate) fn execute_day(
         if should_run {
             day.iter()
                 .map(|d| {
-                    let t = Instant::now();
-                    let answer = (d.executor)(&input);
-                    let duration = t.elapsed();
+                    let ((answer, duration), mem) = crate::mem::measure(|| {
+                        let t = Instant::now();
+                        let answer = (d.executor)(&input);
+                        (answer, t.elapsed())
+                    });
                     answer.map(|answer| DayPartResult {
+                        name: d.name,
                         answer,
                         duration: DayPartDuration(duration),
+                        mem,
                     })
                 })
                 .collect()
@@ -302,6 +398,84 @@ fn bench_day_inner(
     Ok(())
 }
 
+pub(crate) fn get_day_part_executor(
+    year: u32,
+    day_i: u32,
+    part: crate::Part,
+    name: Option<&str>,
+) -> Option<Result<&'static DayPartExecutor>> {
+    let executors = DAY_EXECUTORS.get(&(year, day_i))?;
+    let part_executors = match part {
+        crate::Part::Part1 => executors.0,
+        crate::Part::Part2 => executors.1,
+    };
+
+    Some(match name {
+        None => part_executors
+            .first()
+            .with_context(|| format!("{part} has no implementations")),
+        Some(name) => part_executors
+            .iter()
+            .find(|e| e.name == name)
+            .with_context(|| {
+                format!(
+                    "{part} has no implementation named `{name}`, expected one of: {}",
+                    part_executors.iter().map(|e| e.name).join(", ")
+                )
+            }),
+    })
+}
+
+pub(crate) fn get_day_executors(year: u32, day_i: u32) -> Option<&'static DayExecutors> {
+    DAY_EXECUTORS.get(&(year, day_i))
+}
+
 pub(crate) fn get_day_visualizers(year: u32, day_i: u32) -> Option<&'static DayVisualizers> {
     DAY_VISUALIZERS.get(&(year, day_i))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{DayModule, DayPartAnswer, DayPartExecutor};
+
+    fn part(_input: &str) -> Option<DayPartAnswer> {
+        None
+    }
+
+    const PART: &[DayPartExecutor] = &[DayPartExecutor::new("part", part)];
+    const PARTS: &[DayPartExecutor] = &[
+        DayPartExecutor::new("part", part),
+        DayPartExecutor::new("part", part),
+    ];
+
+    #[test]
+    fn validate_modules() {
+        let modules = [
+            DayModule::new(2024, 1).with_executors(PART, PART),
+            DayModule::new(2024, 2).with_executors(PART, PARTS),
+            DayModule::new(2024, 1).with_executors(PART, PART),
+            DayModule::new(2025, 0).with_executors(PART, &[]),
+        ];
+
+        assert_eq!(
+            vec![
+                "year 2024 day 2: Part 2 has multiple executors named `part`",
+                "year 2025 day 0: day is out of range 1..=25",
+                "year 2025 day 0: Part 2 has no executors",
+                "year 2024 day 1: registered 2 times",
+            ],
+            super::validate_modules(&modules)
+        );
+        assert!(super::validate_modules(&modules[..1]).is_empty());
+    }
+
+    #[test]
+    fn registered_modules_are_valid() {
+        let problems = super::validate_modules(inventory::iter::<DayModule>());
+        assert!(
+            problems.is_empty(),
+            "invalid day module registrations:\n{}",
+            problems.join("\n")
+        );
+    }
+}
@@ -34,24 +34,14 @@ pub(crate) fn get_input(year: u32, day_i: u32) -> Result<String> {
     let input_path = dir.join(format!("day{day_i}"));
 
     if input_path.exists() {
-        fs::read_to_string(&input_path)
+        crate::store::read_to_string(&input_path)
             .with_context(|| format!("failed to read puzzle input file: {}", input_path.display()))
     } else {
-        let token = crate::auth::get_token()?;
-        let token = token.trim();
-
         let url = format!("https://adventofcode.com/{year}/day/{day_i}/input");
+        let input =
+            fetch(&url).with_context(|| format!("failed to fetch puzzle input for day {day_i}"))?;
 
-        let input = Client::new()
-            .get(&url)
-            .header(H::COOKIE, format!("session={token}"))
-            .send()
-            .and_then(Response::error_for_status)
-            .with_context(|| format!("failed to fetch puzzle input for day {day_i}"))?
-            .text()
-            .with_context(|| format!("failed to decode puzzle input: {url}"))?;
-
-        fs::write(&input_path, &input).with_context(|| {
+        crate::store::write(&input_path, &input).with_context(|| {
             format!(
                 "failed to write puzzle input to file: {}",
                 input_path.display()
@@ -62,6 +52,24 @@ pub(crate) fn get_input(year: u32, day_i: u32) -> Result<String> {
     }
 }
 
+pub(crate) fn has_input(year: u32, day_i: u32) -> bool {
+    inputs_dir(year).join(format!("day{day_i}")).exists()
+}
+
+fn fetch(url: &str) -> Result<String> {
+    let token = crate::auth::get_token()?;
+    let token = token.trim();
+
+    Client::new()
+        .get(url)
+        .header(H::COOKIE, format!("session={token}"))
+        .send()
+        .and_then(Response::error_for_status)
+        .with_context(|| format!("request failed: {url}"))?
+        .text()
+        .with_context(|| format!("failed to decode response: {url}"))
+}
+
 fn test_input_path(dir: &Path, day_i: u32, input_i: u32) -> PathBuf {
     dir.join(format!("day{day_i}-test{input_i}"))
 }
@@ -70,7 +78,7 @@ pub(crate) fn get_test_input(year: u32, day_i: u32, input_i: u32) -> Result<Stri
     let dir = inputs_dir(year);
     let test_input_path = test_input_path(&dir, day_i, input_i);
 
-    fs::read_to_string(&test_input_path).with_context(|| {
+    crate::store::read_to_string(&test_input_path).with_context(|| {
         format!(
             "failed to read puzzle test input file: {}",
             test_input_path.display()
@@ -78,6 +86,14 @@ pub(crate) fn get_test_input(year: u32, day_i: u32, input_i: u32) -> Result<Stri
     })
 }
 
+/// Count the test inputs of a day, stopping at the first missing index.
+pub(crate) fn count_test_inputs(year: u32, day_i: u32) -> u32 {
+    let dir = inputs_dir(year);
+    (1..)
+        .take_while(|&i| test_input_path(&dir, day_i, i).exists())
+        .count() as u32
+}
+
 pub(crate) fn set_test_input(year: u32, day_i: u32, input_i: u32) -> Result<()> {
     let dir = create_inputs_dir(year)?;
 
@@ -89,7 +105,7 @@ pub(crate) fn set_test_input(year: u32, day_i: u32, input_i: u32) -> Result<()>
         .with_context(|| "failed to read test input")?;
 
     let test_input_path = test_input_path(&dir, day_i, input_i);
-    fs::write(&test_input_path, test_input).with_context(|| {
+    crate::store::write(&test_input_path, test_input).with_context(|| {
         format!(
             "failed to write puzzle test input to file: {}",
             test_input_path.display()
@@ -98,3 +114,71 @@ pub(crate) fn set_test_input(year: u32, day_i: u32, input_i: u32) -> Result<()>
 
     Ok(())
 }
+
+/// Fetch the puzzle description and save its first code block as test input 1, which is usually
+/// the example.
+///
+/// Returns `false` if the test input already exists.
+pub(crate) fn fetch_test_input(year: u32, day_i: u32) -> Result<bool> {
+    let dir = create_inputs_dir(year)?;
+    let test_input_path = test_input_path(&dir, day_i, 1);
+
+    if test_input_path.exists() {
+        return Ok(false);
+    }
+
+    let url = format!("https://adventofcode.com/{year}/day/{day_i}");
+    let page = fetch(&url)
+        .with_context(|| format!("failed to fetch puzzle description for day {day_i}"))?;
+    let test_input = extract_example(&page)
+        .with_context(|| format!("failed to find an example in puzzle description: {url}"))?;
+
+    crate::store::write(&test_input_path, test_input).with_context(|| {
+        format!(
+            "failed to write puzzle test input to file: {}",
+            test_input_path.display()
+        )
+    })?;
+
+    Ok(true)
+}
+
+fn extract_example(page: &str) -> Option<String> {
+    const START: &str = "<pre><code>";
+    const END: &str = "</code></pre>";
+
+    let start = page.find(START)? + START.len();
+    let len = page[start..].find(END)?;
+    let html = &page[start..start + len];
+
+    let mut text = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(i) = rest.find('<') {
+        text.push_str(&rest[..i]);
+        rest = &rest[i + rest[i..].find('>')? + 1..];
+    }
+    text.push_str(rest);
+
+    Some(
+        text.replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&quot;", "\"")
+            .replace("&#39;", "'")
+            .replace("&amp;", "&"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn extract_example() {
+        let page = "<article><p>For example:</p>\
+            <pre><code>3   4\n<em>4</em>   3\n-&gt; &amp;\n</code></pre>\
+            <pre><code>ignored</code></pre></article>";
+        assert_eq!(
+            Some("3   4\n4   3\n-> &\n"),
+            super::extract_example(page).as_deref()
+        );
+        assert_eq!(None, super::extract_example("<p>no example</p>"));
+    }
+}
@@ -1,5 +1,7 @@
 use std::{collections, fmt, hash, ops};
 
+pub mod ocr;
+
 #[inline(always)]
 pub fn count_digits(x: u64) -> u64 {
     _count_digits_fast(x)
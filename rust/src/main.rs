@@ -1,16 +1,26 @@
 #![feature(iter_map_windows)]
 
-use std::{collections, fmt, iter, sync};
+use std::{cmp, collections, fmt, iter, net::SocketAddr, path::PathBuf, time::Duration};
 
 use anyhow::{Result, bail};
 use clap::{Args, Parser, Subcommand};
 use crossterm::style::{self, StyledContent, Stylize};
+use itertools::Itertools;
 
+mod answer;
 mod auth;
 mod commit;
+mod data;
 mod days;
 mod input;
+mod mem;
+mod profile;
 mod puzzles;
+mod scaffold;
+mod stats;
+mod store;
+mod terminal;
+mod viz;
 
 pub(crate) static PUZZLE_DIR: &str = env!("PUZZLE_DIR");
 
@@ -31,11 +41,19 @@ enum Cli {
     },
     Bench(CliBenchCommand),
     Commit(CliCommitCommand),
+    Data {
+        #[command(subcommand)]
+        command: CliDataCommand,
+    },
     Input {
         #[command(subcommand)]
         command: CliInputCommand,
     },
+    List(CliListCommand),
+    New(CliNewCommand),
+    Profile(CliProfileCommand),
     Run(CliRunCommand),
+    Stats(CliStatsCommand),
     #[command(alias = "viz")]
     Visualize(CliVisualizeCommand),
 }
@@ -46,8 +64,13 @@ impl Cli {
             Self::Auth { command } => command.run(),
             Self::Bench(command) => command.run(),
             Self::Commit(command) => command.run(),
+            Self::Data { command } => command.run(),
             Self::Input { command } => command.run(),
+            Self::List(command) => command.run(),
+            Self::New(command) => command.run(),
+            Self::Profile(command) => command.run(),
             Self::Run(command) => command.run(),
+            Self::Stats(command) => command.run(),
             Self::Visualize(command) => command.run(),
         }
     }
@@ -99,7 +122,7 @@ struct CliBenchCommand {
     #[arg(value_parser = YearParser::new())]
     year: Year,
 
-    #[arg(value_parser = DayParser)]
+    #[arg(value_parser = DayParser::registered())]
     day: Day,
 }
 
@@ -134,39 +157,217 @@ impl CliBenchCommand {
 // ###################################################################
 
 #[derive(Args, Clone, Debug)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 struct CliCommitCommand {
+    #[command(subcommand)]
+    command: Option<CliCommitSubcommand>,
+
     #[arg(long)]
     force: bool,
 
-    #[arg(value_parser = YearParser::new())]
-    year: Year,
+    #[arg(value_parser = YearParser::new(), required = true)]
+    year: Option<Year>,
 
-    #[arg(value_parser = DayParser)]
-    day: Day,
+    /// `all`, a day (`day3`), an inclusive range (`day1..day5`) or a comma-separated list of these
+    #[arg(name = "day", value_parser = CliDaySpecParser::new(), required = true)]
+    day_spec: Option<CliDaySpec>,
+}
+
+#[derive(Subcommand, Clone, Debug)]
+enum CliCommitSubcommand {
+    /// Show the history of committed answers
+    Log {
+        #[command(flatten)]
+        parts: CliDefaultedPartsGroup,
+
+        #[arg(value_parser = YearParser::new())]
+        year: Year,
+
+        #[arg(value_parser = DayParser::registered())]
+        day: Day,
+    },
+    /// Restore a previously committed answer
+    Revert {
+        #[command(flatten)]
+        parts: CliSinglePartGroup,
+
+        #[arg(value_parser = YearParser::new())]
+        year: Year,
+
+        #[arg(value_parser = DayParser::registered())]
+        day: Day,
+
+        /// The history entry to restore, defaults to the one before the current answer was first
+        /// committed, so repeated reverts walk back through the history
+        entry: Option<usize>,
+    },
 }
 
 impl CliCommitCommand {
     fn run(self) -> Result<()> {
-        let input = input::get_input(self.year.0, self.day.0)?;
+        match self.command {
+            Some(CliCommitSubcommand::Log { parts, year, day }) => {
+                if parts.part1() {
+                    print_commit_log(year, day, Part::Part1)?;
+                }
+                if parts.part2() {
+                    print_commit_log(year, day, Part::Part2)?;
+                }
+                Ok(())
+            }
+            Some(CliCommitSubcommand::Revert {
+                parts,
+                year,
+                day,
+                entry,
+            }) => {
+                let part = parts.part();
+                let entry_i = match entry {
+                    Some(entry_i) => entry_i,
+                    None => {
+                        let history = commit::get_commit_history(year.0, day.0, part)?;
+                        if history.len() == 1 {
+                            bail!("nothing to revert to: only one history entry");
+                        }
+                        match commit::previous_entry(&history) {
+                            Some(entry_i) => entry_i,
+                            // Without any history, `revert` reports that instead.
+                            None if history.is_empty() => 0,
+                            None => bail!(
+                                "nothing to revert to: the current answer is the earliest one \
+                                in the history"
+                            ),
+                        }
+                    }
+                };
+                let entry = commit::revert(year.0, day.0, part, entry_i)?;
+                println!(
+                    "{part}: {:#}  {}    {}",
+                    entry.commit.answer,
+                    "✓".bold().green(),
+                    format!("(reverted to entry {entry_i})").dark_grey()
+                );
+                Ok(())
+            }
+            None => {
+                let (Some(year), Some(day_spec)) = (self.year, &self.day_spec) else {
+                    unreachable!("year and day are required without a subcommand");
+                };
+                let days = day_spec.days(year)?;
+                match day_spec {
+                    CliDaySpec::Days(_) if days.len() == 1 => self.commit_day(year, Day(days[0])),
+                    _ => self.commit_days(year, &days),
+                }
+            }
+        }
+    }
+
+    /// Commit the answers of each of `days` that aren't committed yet, reporting conflicts instead
+    /// of stopping at them.
+    fn commit_days(&self, year: Year, days: &[u32]) -> Result<()> {
+        let mut n_new = 0_u32;
+        let mut n_unchanged = 0_u32;
+        let mut n_conflicts = 0_u32;
+        let mut n_overwritten = 0_u32;
+
+        for &day_i in days {
+            let input = input::get_input(year.0, day_i)?;
+            let Some(result) = days::execute_day(year.0, day_i, true, true, input) else {
+                continue;
+            };
+            let existing_commits = commit::get_existing_commits(year.0, day_i)?;
 
-        let Some(result) = days::execute_day(self.year.0, self.day.0, true, true, input) else {
+            for (part, part_results, existing_commit) in [
+                (Part::Part1, &result.0, existing_commits.0),
+                (Part::Part2, &result.1, existing_commits.1),
+            ] {
+                let Some(Some(r)) = part_results.first() else {
+                    continue;
+                };
+                let result_commit = commit::DayPartCommit::new(&r.answer);
+                let label = format!("Day {day_i:2} {part}:");
+                match existing_commit {
+                    Some(existing) if result_commit == existing => n_unchanged += 1,
+                    Some(existing) if !self.force => {
+                        n_conflicts += 1;
+                        println!(
+                            "{label} {}  {:#} {} {:#}",
+                            "✗".bold().red(),
+                            existing.answer,
+                            "→".dark_grey(),
+                            result_commit.answer,
+                        );
+                    }
+                    existing => {
+                        result_commit.write(year.0, day_i, part, Some(r.name))?;
+                        let status = if existing.is_some() {
+                            n_overwritten += 1;
+                            "(overwritten)"
+                        } else {
+                            n_new += 1;
+                            "(committed)"
+                        };
+                        println!(
+                            "{label} {}  {:#}    {}",
+                            "✓".bold().green(),
+                            result_commit.answer,
+                            status.dark_grey(),
+                        );
+                    }
+                }
+            }
+        }
+
+        if n_new + n_overwritten + n_conflicts > 0 {
+            println!();
+        }
+        print!("{n_new} new, {n_unchanged} unchanged, {n_conflicts} conflicting");
+        if n_overwritten > 0 {
+            print!(", {n_overwritten} overwritten");
+        }
+        println!();
+        if n_conflicts > 0 {
             println!(
-                "No implementation for year {} day {}.",
-                &self.year.0, self.day.0
+                "{}",
+                "Use `--force` to overwrite conflicting parts".dark_grey()
             );
+        }
+
+        Ok(())
+    }
+
+    fn commit_day(&self, year: Year, day: Day) -> Result<()> {
+        let input = input::get_input(year.0, day.0)?;
+
+        let Some(result) = days::execute_day(year.0, day.0, true, true, input) else {
+            println!("No implementation for year {} day {}.", year.0, day.0);
             return Ok(());
         };
 
-        let existing_commits = commit::get_existing_commits(self.year.0, self.day.0)?;
-
-        self.print_part(Part::Part1, &result.0, existing_commits.0.as_ref())?;
-        self.print_part(Part::Part2, &result.1, existing_commits.1.as_ref())?;
+        let existing_commits = commit::get_existing_commits(year.0, day.0)?;
+
+        self.print_part(
+            year,
+            day,
+            Part::Part1,
+            &result.0,
+            existing_commits.0.as_ref(),
+        )?;
+        self.print_part(
+            year,
+            day,
+            Part::Part2,
+            &result.1,
+            existing_commits.1.as_ref(),
+        )?;
 
         Ok(())
     }
 
     fn print_part(
         &self,
+        year: Year,
+        day: Day,
         part: Part,
         day_results: &[Option<days::DayPartResult>],
         existing_commit: Option<&commit::DayPartCommit>,
@@ -187,7 +388,7 @@ impl CliCommitCommand {
                     print_incorrect_answer_diff(part, &existing1.answer, &result_commit.answer);
                 }
                 _ => {
-                    result_commit.write(self.year.0, self.day.0, part)?;
+                    result_commit.write(year.0, day.0, part, Some(r.name))?;
                     print_committed(part, &result_commit.answer);
                 }
             }
@@ -197,43 +398,167 @@ impl CliCommitCommand {
     }
 }
 
-fn print_committed(part: Part, answer: &str) {
+fn print_commit_log(year: Year, day: Day, part: Part) -> Result<()> {
+    let history = commit::get_commit_history(year.0, day.0, part)?;
+
+    println!("{}", part.to_string().bold());
+    if history.is_empty() {
+        println!("  {}", "No commits".dark_grey());
+        return Ok(());
+    }
+
+    let executor_width = history
+        .iter()
+        .map(|e| e.executor.as_deref().map_or(1, str::len))
+        .max()
+        .unwrap_or_default();
+
+    for (i, entry) in history.iter().enumerate() {
+        let marker = if i + 1 == history.len() {
+            "*".bold().green()
+        } else {
+            " ".stylize()
+        };
+        println!(
+            "{marker} {i:3}  {date}  {executor:executor_width$}  {answer:#}",
+            i = i + 1,
+            date = if entry.timestamp > 0 {
+                stats::format_date(entry.timestamp)
+            } else {
+                "?".repeat(10)
+            }
+            .dark_grey(),
+            executor = entry.executor.as_deref().unwrap_or("-"),
+            answer = entry.commit.answer,
+        );
+    }
+
+    Ok(())
+}
+
+fn print_committed(part: Part, answer: &answer::Answer) {
     println!(
-        "{}: {}  {}    {}",
+        "{}: {:#}  {}    {}",
         part,
-        answer.trim(),
+        answer,
         "✓".bold().green(),
         "(committed)".dark_grey()
     );
 }
 
-fn print_already_committed(part: Part, answer: &str) {
+fn print_already_committed(part: Part, answer: &answer::Answer) {
     println!(
-        "{}: {}  {}    {}",
+        "{}: {:#}  {}    {}",
         part,
-        answer.trim(),
+        answer,
         "✓".bold().green(),
         "(already committed)".dark_grey()
     );
 }
 
-fn print_incorrect_answer_diff(part: Part, commit_answer: &str, current_answer: &str) {
+fn print_incorrect_answer_diff(
+    part: Part,
+    commit_answer: &answer::Answer,
+    current_answer: &answer::Answer,
+) {
     eprintln!(
         "{}: {} answer does not match existing commit",
         part,
         "error".bold().red(),
     );
     eprintln!();
-    eprintln!("<<<<<<< commited answer");
-    eprintln!("{}", commit_answer.trim());
-    eprintln!("=======");
-    eprintln!("{}", current_answer.trim());
-    eprintln!(">>>>>>> current run answer");
+    if commit_answer.is_multi_line() || current_answer.is_multi_line() {
+        eprintln!("--- commited answer");
+        eprintln!("+++ current run answer");
+        for line in commit_answer.diff(current_answer) {
+            match line {
+                answer::LineDiff::Same(line) => eprintln!(" {line}"),
+                answer::LineDiff::Changed(old, new) => {
+                    eprintln!("{}", format!("-{old}").red());
+                    eprintln!("{}", format!("+{new}").green());
+                }
+                answer::LineDiff::Removed(old) => eprintln!("{}", format!("-{old}").red()),
+                answer::LineDiff::Added(new) => eprintln!("{}", format!("+{new}").green()),
+            }
+        }
+    } else {
+        eprintln!("<<<<<<< commited answer");
+        eprintln!("{commit_answer}");
+        eprintln!("=======");
+        eprintln!("{current_answer}");
+        eprintln!(">>>>>>> current run answer");
+    }
     eprintln!();
     eprintln!("Use `--force` to overwrite");
     eprintln!();
 }
 
+// ###################################################################
+// # CLI - Data
+// ###################################################################
+
+#[derive(Subcommand, Clone, Debug)]
+enum CliDataCommand {
+    /// Bundle the inputs, test inputs and committed answers into a tar archive
+    Export {
+        /// Also include the session token
+        #[arg(long)]
+        include_token: bool,
+
+        archive: std::path::PathBuf,
+    },
+    /// Unpack an archive made by `data export`, merging commit histories
+    Import {
+        /// Also import the session token, if the archive has one
+        #[arg(long)]
+        include_token: bool,
+
+        /// Overwrite existing files that differ from the archive
+        #[arg(long)]
+        force: bool,
+
+        archive: std::path::PathBuf,
+    },
+    /// Encrypt the inputs, test inputs and committed answers in place with the configured key
+    Encrypt,
+    /// Decrypt the inputs, test inputs and committed answers in place
+    Decrypt,
+}
+
+impl CliDataCommand {
+    fn run(self) -> Result<()> {
+        match self {
+            Self::Export {
+                include_token,
+                archive,
+            } => {
+                let n_files = data::export(&archive, include_token)?;
+                println!("Exported {n_files} files to {}", archive.display());
+            }
+            Self::Import {
+                include_token,
+                force,
+                archive,
+            } => {
+                let summary = data::import(&archive, include_token, force)?;
+                println!(
+                    "Imported {} new, {} merged, {} overwritten, {} unchanged",
+                    summary.created, summary.merged, summary.overwritten, summary.unchanged
+                );
+            }
+            Self::Encrypt => {
+                let n_files = data::set_encrypted(true)?;
+                println!("Encrypted {n_files} files");
+            }
+            Self::Decrypt => {
+                let n_files = data::set_encrypted(false)?;
+                println!("Decrypted {n_files} files");
+            }
+        }
+        Ok(())
+    }
+}
+
 // ###################################################################
 // # CLI - Input
 // ###################################################################
@@ -247,14 +572,14 @@ enum CliInputCommand {
         #[arg(value_parser = YearParser::new())]
         year: Year,
 
-        #[arg(value_parser = DayParser)]
+        #[arg(value_parser = DayParser::any())]
         day: Day,
     },
     SetTest {
         #[arg(value_parser = YearParser::new())]
         year: Year,
 
-        #[arg(value_parser = DayParser)]
+        #[arg(value_parser = DayParser::any())]
         day: Day,
 
         #[arg(default_value_t = 1)]
@@ -282,6 +607,225 @@ impl CliInputCommand {
     }
 }
 
+// ###################################################################
+// # CLI - List
+// ###################################################################
+
+#[derive(Args, Clone, Debug)]
+struct CliListCommand {
+    #[arg(value_parser = YearParser::new())]
+    year: Option<Year>,
+}
+
+impl CliListCommand {
+    fn run(self) -> Result<()> {
+        let years = match self.year {
+            Some(year) => vec![year.0],
+            None => days::registered_years(),
+        };
+
+        for (i, year) in years.into_iter().enumerate() {
+            if i > 0 {
+                println!();
+            }
+            println!("{}", format!("Year {year}").bold());
+
+            let rows = days::registered_days(year)
+                .into_iter()
+                .map(|day_i| -> Result<_> {
+                    let executors =
+                        days::get_day_executors(year, day_i).expect("registered day has executors");
+                    let visualizers = days::get_day_visualizers(year, day_i)
+                        .expect("registered day has visualizers");
+                    let names = |executors: &[days::DayPartExecutor]| {
+                        executors.iter().map(|e| e.name()).join(", ")
+                    };
+                    Ok((
+                        day_i,
+                        names(executors.0),
+                        names(executors.1),
+                        (visualizers.0.is_some(), visualizers.1.is_some()),
+                        input::has_input(year, day_i),
+                        input::count_test_inputs(year, day_i),
+                        commit::get_existing_commits(year, day_i)?,
+                    ))
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            let pt1_width = rows.iter().map(|r| r.1.len()).chain([5]).max().unwrap();
+            let pt2_width = rows.iter().map(|r| r.2.len()).chain([5]).max().unwrap();
+
+            println!(
+                "{}",
+                format!(
+                    "  Day  {:pt1_width$}  {:pt2_width$}  Viz  Input  Tests  Answers",
+                    "Pt. 1", "Pt. 2",
+                )
+                .grey()
+            );
+
+            for (day_i, pt1_names, pt2_names, visualizers, has_input, n_tests, commits) in rows {
+                let flag = |set: bool, symbol: &'static str| {
+                    if set {
+                        symbol.stylize()
+                    } else {
+                        "-".dark_grey()
+                    }
+                };
+                let star = |commit: Option<commit::DayPartCommit>| {
+                    if commit.is_some() {
+                        "★".bold().yellow()
+                    } else {
+                        "★".dark_grey()
+                    }
+                };
+                println!(
+                    "  {day_i:3}  {pt1_names:pt1_width$}  {pt2_names:pt2_width$}  {viz1}{viz2}   {input}      {tests}  {star1}{star2}",
+                    viz1 = flag(visualizers.0, "1"),
+                    viz2 = flag(visualizers.1, "2"),
+                    input = if has_input {
+                        "✔".green()
+                    } else {
+                        "-".dark_grey()
+                    },
+                    // Styled strings ignore the width, so pad them first.
+                    tests = if n_tests > 0 {
+                        format!("{n_tests:<5}").stylize()
+                    } else {
+                        format!("{:<5}", "-").dark_grey()
+                    },
+                    star1 = star(commits.0),
+                    star2 = star(commits.1),
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// ###################################################################
+// # CLI - New
+// ###################################################################
+
+#[derive(Args, Clone, Debug)]
+struct CliNewCommand {
+    /// Also fetch the puzzle input and save the first example as test input 1
+    #[arg(long)]
+    fetch: bool,
+
+    #[arg(value_parser = clap::value_parser!(u32).range(2015..))]
+    year: u32,
+
+    #[arg(value_parser = DayParser::any())]
+    day: Day,
+}
+
+impl CliNewCommand {
+    fn run(self) -> Result<()> {
+        let day_path = scaffold::new_day(self.year, self.day.0)?;
+        println!("Created {}", day_path.display());
+
+        if self.fetch {
+            input::get_input(self.year, self.day.0)?;
+            println!("Fetched input");
+
+            if input::fetch_test_input(self.year, self.day.0)? {
+                println!("Fetched example as test input 1");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// ###################################################################
+// # CLI - Profile
+// ###################################################################
+
+#[derive(Args, Clone, Debug)]
+struct CliProfileCommand {
+    #[command(flatten)]
+    parts: CliSinglePartGroup,
+
+    /// The implementation to profile, defaults to the first one registered for the part
+    #[arg(long)]
+    executor: Option<String>,
+
+    /// How long to run the implementation for (e.g. `5s`, `500ms`)
+    #[arg(long, default_value = "5s", value_parser = parse_duration)]
+    duration: Duration,
+
+    /// The sampling frequency in Hz
+    #[arg(long, default_value_t = 2991)]
+    frequency: u32,
+
+    #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "1")]
+    test: Option<u32>,
+
+    #[arg(value_parser = YearParser::new())]
+    year: Year,
+
+    #[arg(value_parser = DayParser::registered())]
+    day: Day,
+}
+
+impl CliProfileCommand {
+    fn run(self) -> Result<()> {
+        let part = self.parts.part();
+
+        let Some(executor) =
+            days::get_day_part_executor(self.year.0, self.day.0, part, self.executor.as_deref())
+        else {
+            println!(
+                "No implementation for year {} day {}.",
+                self.year.0, self.day.0
+            );
+            return Ok(());
+        };
+        let executor = executor?;
+
+        let input = if let Some(test_i) = self.test {
+            input::get_test_input(self.year.0, self.day.0, test_i)?
+        } else {
+            input::get_input(self.year.0, self.day.0)?
+        };
+
+        println!(
+            "Profiling {part} `{}` for {}...",
+            executor.name(),
+            days::DayPartDuration(self.duration)
+        );
+
+        let profile = profile::profile(
+            self.year.0,
+            self.day.0,
+            part,
+            executor,
+            &input,
+            self.duration,
+            self.frequency,
+        )?;
+
+        println!(
+            "Collected {} samples over {} iterations: {}",
+            profile.n_samples,
+            profile.iterations,
+            profile.folded_path.display()
+        );
+        println!(
+            "{}",
+            format!(
+                "Render with `inferno-flamegraph < {} > flamegraph.svg`",
+                profile.folded_path.display()
+            )
+            .dark_grey()
+        );
+
+        Ok(())
+    }
+}
+
 // ###################################################################
 // # CLI - Run
 // ###################################################################
@@ -294,49 +838,146 @@ struct CliRunCommand {
     #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "1")]
     test: Option<u32>,
 
-    #[arg(name = "year", value_parser = YearParser::new())]
-    year: Year,
+    /// Fail if the total time of a year exceeds this duration (e.g. `1s`, `500ms`)
+    #[arg(long, value_parser = parse_duration)]
+    budget: Option<Duration>,
+
+    /// Show every implementation of each part instead of only the first when running multiple days
+    #[arg(long)]
+    all_executors: bool,
 
-    #[arg(name = "day", value_parser = CliRunDaySpecParser::new())]
-    day_spec: CliRunDaySpec,
+    /// Report the peak heap usage and number of allocations of each implementation
+    #[cfg(feature = "mem-stats")]
+    #[arg(long)]
+    mem: bool,
+
+    #[arg(name = "year", value_parser = CliRunYearSpecParser::new())]
+    year_spec: CliRunYearSpec,
+
+    /// `all`, a day (`day3`), an inclusive range (`day1..day5`) or a comma-separated list of these
+    #[arg(name = "day", value_parser = CliDaySpecParser::new())]
+    day_spec: CliDaySpec,
 }
 
 impl CliRunCommand {
+    /// Whether to report memory usage, which needs the `mem-stats` feature.
+    fn mem(&self) -> bool {
+        #[cfg(feature = "mem-stats")]
+        let mem = self.mem;
+        #[cfg(not(feature = "mem-stats"))]
+        let mem = false;
+        mem
+    }
+
     fn run(self) -> Result<()> {
-        match self.day_spec {
-            CliRunDaySpec::All => self.run_all(),
-            CliRunDaySpec::Day(day) => self.run_one(day),
+        #[cfg(feature = "mem-stats")]
+        if self.mem {
+            mem::enable();
+        }
+
+        let years = match self.year_spec {
+            CliRunYearSpec::All => days::registered_years(),
+            CliRunYearSpec::Year(year) => vec![year.0],
+        };
+
+        if let [year] = years.as_slice()
+            && let CliDaySpec::Days(_) = self.day_spec
+            && let [day] = self.day_spec.days(Year(*year))?.as_slice()
+        {
+            return self.run_one(Year(*year), Day(*day));
         }
+
+        let mut over_budget_years = Vec::new();
+
+        for (i, &year) in years.iter().enumerate() {
+            // Across years, each only runs the days it has.
+            let days = match years.as_slice() {
+                [_] => self.day_spec.days(Year(year))?,
+                _ => {
+                    let registered = days::registered_days(year);
+                    match &self.day_spec {
+                        CliDaySpec::All => registered,
+                        CliDaySpec::Days(days) => days
+                            .iter()
+                            .map(|d| d.0)
+                            .filter(|day| registered.contains(day))
+                            .collect(),
+                    }
+                }
+            };
+
+            if years.len() > 1 {
+                if i > 0 {
+                    println!();
+                }
+                println!("{}", format!("Year {year}").bold());
+            }
+
+            if !self.run_many(Year(year), &days)? {
+                over_budget_years.push(year);
+            }
+        }
+
+        if let (Some(budget), false) = (self.budget, over_budget_years.is_empty()) {
+            bail!(
+                "total time exceeds the budget of {} in year {}",
+                days::DayPartDuration(budget),
+                over_budget_years.iter().join(", ")
+            );
+        }
+
+        Ok(())
     }
 
-    fn run_one(self, day: Day) -> Result<()> {
+    fn run_one(&self, year: Year, day: Day) -> Result<()> {
+        if self.budget.is_some() {
+            eprintln!("Warning: ignoring `--budget` option, it only applies to multiple days");
+        }
+        if self.all_executors {
+            eprintln!(
+                "Warning: ignoring `--all-executors` option, it only applies to multiple days"
+            );
+        }
+
         let input = if let Some(test_i) = self.test {
-            input::get_test_input(self.year.0, day.0, test_i)?
+            input::get_test_input(year.0, day.0, test_i)?
         } else {
-            input::get_input(self.year.0, day.0)?
+            input::get_input(year.0, day.0)?
         };
 
-        let Some(result) = days::execute_day(
-            self.year.0,
-            day.0,
-            self.parts.part1(),
-            self.parts.part2(),
-            input,
-        ) else {
-            println!("No implementation for year {} day {}.", &self.year.0, day.0);
+        let Some(result) =
+            days::execute_day(year.0, day.0, self.parts.part1(), self.parts.part2(), input)
+        else {
+            println!("No implementation for year {} day {}.", year.0, day.0);
             return Ok(());
         };
 
-        let existing_commits = commit::get_existing_commits(self.year.0, day.0)?;
+        let existing_commits = commit::get_existing_commits(year.0, day.0)?;
 
-        self.print_day_results(Part::Part1, &result.0, existing_commits.0.as_ref());
-        self.print_day_results(Part::Part2, &result.1, existing_commits.1.as_ref());
+        let mut timing_log = stats::TimingLog::new(year.0);
 
-        Ok(())
+        self.print_day_results(
+            &mut timing_log,
+            day,
+            Part::Part1,
+            &result.0,
+            existing_commits.0.as_ref(),
+        );
+        self.print_day_results(
+            &mut timing_log,
+            day,
+            Part::Part2,
+            &result.1,
+            existing_commits.1.as_ref(),
+        );
+
+        timing_log.save()
     }
 
     fn print_day_results(
         &self,
+        timing_log: &mut stats::TimingLog,
+        day: Day,
         part: Part,
         day_results: &[Option<days::DayPartResult>],
         existing_commit: Option<&commit::DayPartCommit>,
@@ -351,12 +992,29 @@ impl CliRunCommand {
                 };
                 let commit = commit::DayPartCommit::new(&r.answer);
                 let commit_status = self.get_single_day_commit_status(existing_commit, &commit);
+                let answer_status = if self.test.is_some() {
+                    stats::AnswerStatus::Test
+                } else {
+                    stats::AnswerStatus::new(existing_commit, &commit)
+                };
+                timing_log.record(day.0, part, r, answer_status);
+                let mem = match &r.mem {
+                    Some(mem) => format!("  {mem}").dark_grey(),
+                    None => String::new().stylize(),
+                };
+                // Multi-line answers go below the status so they stay aligned.
+                let (inline_answer, answer_lines) = match &r.answer {
+                    answer::Answer::Lines(lines) => (String::new(), lines.as_slice()),
+                    answer => (answer.to_string(), [].as_slice()),
+                };
                 println!(
-                    "{part}{sub_part}: {answer}{status}    {duration:#}",
-                    answer = r.answer,
+                    "{part}{sub_part}: {inline_answer}{status}    {duration:#}{mem}",
                     status = commit_status,
                     duration = r.duration,
                 );
+                for line in answer_lines {
+                    println!("  {line}");
+                }
             }
         }
     }
@@ -376,54 +1034,117 @@ impl CliRunCommand {
         }
     }
 
-    fn run_all(self) -> Result<()> {
+    /// Run each of `days` and print a summary table, returning whether the total time is within
+    /// the budget.
+    fn run_many(&self, year: Year, days: &[u32]) -> Result<bool> {
         if self.test.is_some() {
-            eprintln!("Warning: ignoring `--test` option, it does nothing with `run all`");
+            eprintln!(
+                "Warning: ignoring `--test` option, it does nothing when running multiple days"
+            );
         }
 
         const GUTTER_WIDTH: usize = 4;
         const PADDING_WIDTH: usize = 4;
         const STATUS_WIDTH: usize = 3;
         const DURATION_WIDTH: usize = 8;
-        const PART_WIDTH: usize = STATUS_WIDTH + DURATION_WIDTH;
-
-        static DAYS_IN_YEAR: sync::LazyLock<collections::HashMap<Year, u32>> =
-            sync::LazyLock::new(|| collections::HashMap::from_iter([(Year(2025), 12)]));
-
-        let n_days = DAYS_IN_YEAR.get(&self.year).copied().unwrap_or(25);
+        const MEM_WIDTH: usize = 18;
 
-        let results = (1_u32..=n_days)
+        let results = days
+            .iter()
             .map(
-                |day_i| -> Result<(u32, commit::DayCommits, days::DayResult)> {
-                    let commits = commit::get_existing_commits(self.year.0, day_i)?;
-                    let input = input::get_input(self.year.0, day_i)?;
-                    let result = days::execute_day(self.year.0, day_i, true, true, input)
-                        .unwrap_or_default();
+                |&day_i| -> Result<(u32, commit::DayCommits, days::DayResult)> {
+                    let commits = commit::get_existing_commits(year.0, day_i)?;
+                    let input = input::get_input(year.0, day_i)?;
+                    let result =
+                        days::execute_day(year.0, day_i, true, true, input).unwrap_or_default();
                     Ok((day_i, commits, result))
                 },
             )
             .collect::<Result<Vec<_>>>()?;
 
-        for (day_i, _, day_results) in &results {
-            for (part, results) in [
-                (Part::Part1, day_results.0.as_slice()),
-                (Part::Part2, day_results.1.as_slice()),
+        let mut timing_log = stats::TimingLog::new(year.0);
+
+        for (day_i, day_commits, day_results) in &results {
+            for (part, existing_commit, results) in [
+                (
+                    Part::Part1,
+                    day_commits.0.as_ref(),
+                    day_results.0.as_slice(),
+                ),
+                (
+                    Part::Part2,
+                    day_commits.1.as_ref(),
+                    day_results.1.as_slice(),
+                ),
             ] {
-                if results.len() > 1 {
+                if results.len() > 1 && !self.all_executors {
                     eprintln!(
                         "Warning: Day {day_i} {part} has multiple implementations, only the first will be shown"
                     );
                 }
+                for r in results.iter().flatten() {
+                    let commit = commit::DayPartCommit::new(&r.answer);
+                    let answer_status = stats::AnswerStatus::new(existing_commit, &commit);
+                    timing_log.record(*day_i, part, r, answer_status);
+                }
             }
         }
 
+        timing_log.save()?;
+
+        let day_durations = results
+            .iter()
+            .map(|(_, _, day_result)| {
+                [&day_result.0, &day_result.1]
+                    .into_iter()
+                    .filter_map(|part_results| part_results.first()?.as_ref())
+                    .map(|r| r.duration.0)
+                    .sum::<Duration>()
+            })
+            .collect::<Vec<_>>();
+        let total_duration = day_durations.iter().sum::<Duration>();
+
+        const N_SLOWEST_DAYS: usize = 3;
+        let slowest_days = if self.budget.is_some() {
+            let mut by_duration = results
+                .iter()
+                .zip(&day_durations)
+                .filter(|(_, duration)| !duration.is_zero())
+                .map(|((day_i, _, _), duration)| (*day_i, *duration))
+                .collect::<Vec<_>>();
+            by_duration.sort_unstable_by_key(|&(_, duration)| cmp::Reverse(duration));
+            by_duration.truncate(N_SLOWEST_DAYS);
+            by_duration.into_iter().map(|(day_i, _)| day_i).collect()
+        } else {
+            Vec::new()
+        };
+
+        let name_width = if self.all_executors {
+            results
+                .iter()
+                .flat_map(|(_, _, day_result)| day_result.0.iter().chain(&day_result.1))
+                .flatten()
+                .map(|r| r.name.len() + 2)
+                .max()
+                .unwrap_or_default()
+        } else {
+            0
+        };
+        let mem_width = if self.mem() { MEM_WIDTH } else { 0 };
+        let part_width = STATUS_WIDTH + name_width + DURATION_WIDTH + mem_width;
+
+        let mut n_stars = 0_u32;
+        let mut n_mismatches = 0_u32;
+        let mut n_uncommitted = 0_u32;
+        let mut n_unimplemented = 0_u32;
+
         println!(
             "{spacer:gutter_w$} {spacer:padding_w$}{p1}{spacer:padding_w$}{p2}",
             spacer = "",
             gutter_w = GUTTER_WIDTH,
             padding_w = PADDING_WIDTH,
-            p1 = format!("{:w$}", "Pt. 1", w = PART_WIDTH).grey(),
-            p2 = format!("{:w$}", "Pt. 2", w = PART_WIDTH).grey(),
+            p1 = format!("{:w$}", "Pt. 1", w = part_width).grey(),
+            p2 = format!("{:w$}", "Pt. 2", w = part_width).grey(),
         );
 
         println!(
@@ -431,56 +1152,193 @@ impl CliRunCommand {
             gutter = "─".repeat(GUTTER_WIDTH).dark_grey(),
             divider = "┬".dark_grey(),
             padding = "─".repeat(PADDING_WIDTH).dark_grey(),
-            part = "─".repeat(PART_WIDTH).dark_grey(),
+            part = "─".repeat(part_width).dark_grey(),
             tail = "─".repeat(PADDING_WIDTH).dark_grey(),
         );
 
-        for (day_i, day_commits, day_result) in results {
+        for ((day_i, day_commits, day_result), day_duration) in
+            results.into_iter().zip(day_durations)
+        {
+            let is_slow = slowest_days.contains(&day_i);
+
             print!(
                 " {day_label} {divider}",
-                day_label = format!("{day_i:2}").grey(),
+                day_label = if is_slow {
+                    format!("{day_i:2}").bold().red()
+                } else {
+                    format!("{day_i:2}").grey()
+                },
                 divider = "│".dark_grey(),
             );
 
             if day_result.0.is_empty() || day_result.1.is_empty() {
+                n_unimplemented += 2;
                 println!();
                 continue;
             }
 
-            for (existing_commit, result) in [
-                (day_commits.0.as_ref(), day_result.0[0].as_ref()),
-                (day_commits.1.as_ref(), day_result.1[0].as_ref()),
-            ] {
-                if let Some(r) = result {
-                    let commit = commit::DayPartCommit::new(&r.answer);
-                    let commit_status =
-                        self.get_many_day_commit_status(existing_commit, Some(&commit));
+            let parts = [
+                (day_commits.0.as_ref(), day_result.0.as_slice()),
+                (day_commits.1.as_ref(), day_result.1.as_slice()),
+            ];
+
+            for (existing_commit, part_results) in parts {
+                match &part_results[0] {
+                    Some(r) => match existing_commit {
+                        Some(existing) if commit::DayPartCommit::new(&r.answer) == *existing => {
+                            n_stars += 1
+                        }
+                        Some(_) => n_mismatches += 1,
+                        None => n_uncommitted += 1,
+                    },
+                    None => n_unimplemented += 1,
+                }
+            }
+
+            let n_rows = if self.all_executors {
+                cmp::max(day_result.0.len(), day_result.1.len())
+            } else {
+                1
+            };
+
+            let fastest = parts.map(|(existing_commit, part_results)| {
+                if !self.all_executors || part_results.len() < 2 {
+                    return None;
+                }
+                part_results
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, r)| Some((i, r.as_ref()?)))
+                    .filter(|(_, r)| {
+                        existing_commit.is_some_and(|existing| {
+                            commit::DayPartCommit::new(&r.answer) == *existing
+                        })
+                    })
+                    .min_by_key(|(_, r)| r.duration.0)
+                    .map(|(i, _)| i)
+            });
+
+            for row_i in 0..n_rows {
+                if row_i > 0 {
                     print!(
-                        "{padding:padding_w$}{status}  {duration_fg}{duration:duration_w$}{reset}",
-                        padding = "",
-                        padding_w = PADDING_WIDTH,
-                        status = commit_status,
-                        duration = r.duration,
-                        duration_fg = style::SetForegroundColor(r.duration.speed_color()),
-                        duration_w = DURATION_WIDTH,
-                        reset = style::SetForegroundColor(style::Color::Reset),
+                        " {spacer:2} {divider}",
+                        spacer = "",
+                        divider = "│".dark_grey(),
                     );
-                } else {
-                    let commit_status = self.get_many_day_commit_status(existing_commit, None);
+                }
+
+                for ((existing_commit, part_results), fastest_i) in parts.iter().zip(fastest) {
+                    let Some(result) = part_results.get(row_i) else {
+                        print!("{spacer:w$}", spacer = "", w = PADDING_WIDTH + part_width,);
+                        continue;
+                    };
+
+                    if let Some(r) = result {
+                        let commit = commit::DayPartCommit::new(&r.answer);
+                        let commit_status =
+                            self.get_many_day_commit_status(*existing_commit, Some(&commit));
+                        let name = if self.all_executors {
+                            format!("{:w$}", r.name, w = name_width)
+                        } else {
+                            String::new()
+                        };
+                        let mem = match &r.mem {
+                            Some(usage) => format!(
+                                "{:>9} {:>6}",
+                                mem::format_bytes(usage.peak_bytes),
+                                mem::format_count(usage.n_allocs)
+                            ),
+                            None => String::new(),
+                        };
+                        print!(
+                            "{padding:padding_w$}{status}  {name}{duration_fg}{duration:duration_w$}{reset}{mem}",
+                            padding = "",
+                            padding_w = PADDING_WIDTH,
+                            status = commit_status,
+                            name = if fastest_i == Some(row_i) {
+                                name.bold().green()
+                            } else {
+                                name.grey()
+                            },
+                            duration = r.duration,
+                            duration_fg = style::SetForegroundColor(r.duration.speed_color()),
+                            duration_w = DURATION_WIDTH,
+                            reset = style::SetForegroundColor(style::Color::Reset),
+                            mem = format!("{mem:>mem_width$}").dark_grey(),
+                        );
+                    } else {
+                        let commit_status = self.get_many_day_commit_status(*existing_commit, None);
+                        print!(
+                            "{spacer:padding_w$}{status}  {spacer:duration_width$}",
+                            padding_w = PADDING_WIDTH,
+                            status = commit_status,
+                            spacer = "",
+                            duration_width = name_width + DURATION_WIDTH + mem_width
+                        );
+                    }
+                }
+
+                if is_slow && row_i == 0 {
+                    let share = day_duration.as_secs_f64() / total_duration.as_secs_f64() * 100.0;
                     print!(
-                        "{spacer:padding_w$}{status}  {spacer:duration_width$}",
-                        padding_w = PADDING_WIDTH,
-                        status = commit_status,
+                        "{spacer:padding_w$}{share}",
                         spacer = "",
-                        duration_width = DURATION_WIDTH
+                        padding_w = PADDING_WIDTH,
+                        share = format!("{share:3.0}%").red(),
                     );
                 }
-            }
 
-            println!();
+                println!();
+            }
         }
 
-        Ok(())
+        println!(
+            "{gutter}{divider}{tail}",
+            gutter = "─".repeat(GUTTER_WIDTH).dark_grey(),
+            divider = "┴".dark_grey(),
+            tail = "─".repeat(3 * PADDING_WIDTH + 2 * part_width).dark_grey(),
+        );
+
+        println!(
+            " {label} {total}    {star} {n_stars}  {mismatch} {n_mismatches}  {uncommitted} {n_uncommitted}  {unimplemented} {n_unimplemented}",
+            label = "Total".grey(),
+            total = days::DayPartDuration(total_duration),
+            star = "★".bold().yellow(),
+            mismatch = "✗".bold().red(),
+            uncommitted = "?".bold().dark_magenta(),
+            unimplemented = "★".dark_grey(),
+        );
+
+        match self.budget {
+            Some(budget) if total_duration > budget => {
+                println!(
+                    " {label} {budget}    {status}",
+                    label = "Budget".grey(),
+                    budget = days::DayPartDuration(budget),
+                    status = format!(
+                        "exceeded by {}",
+                        days::DayPartDuration(total_duration - budget)
+                    )
+                    .bold()
+                    .red(),
+                );
+                Ok(false)
+            }
+            Some(budget) => {
+                println!(
+                    " {label} {budget}    {status}",
+                    label = "Budget".grey(),
+                    budget = days::DayPartDuration(budget),
+                    status = format!(
+                        "{} remaining",
+                        days::DayPartDuration(budget - total_duration)
+                    )
+                    .green(),
+                );
+                Ok(true)
+            }
+            None => Ok(true),
+        }
     }
 
     fn get_many_day_commit_status(
@@ -497,6 +1355,114 @@ impl CliRunCommand {
     }
 }
 
+// ###################################################################
+// # CLI - Stats
+// ###################################################################
+
+#[derive(Args, Clone, Debug)]
+struct CliStatsCommand {
+    #[arg(value_parser = YearParser::new())]
+    year: Year,
+
+    #[arg(value_parser = DayParser::registered())]
+    day: Option<Day>,
+}
+
+impl CliStatsCommand {
+    const TREND_LEN: usize = 24;
+
+    fn run(self) -> Result<()> {
+        let records = stats::read_timings(self.year.0)?;
+
+        let mut day_part_executors =
+            collections::BTreeMap::<(u32, u8), Vec<(&str, Vec<&stats::TimingRecord>)>>::new();
+
+        for record in records
+            .iter()
+            .filter(|r| r.status != stats::AnswerStatus::Test)
+            .filter(|r| self.day.is_none_or(|day| day.0 == r.day))
+        {
+            let executors = day_part_executors
+                .entry((record.day, record.part))
+                .or_default();
+            match executors
+                .iter_mut()
+                .find(|(name, _)| *name == record.executor)
+            {
+                Some((_, runs)) => runs.push(record),
+                None => executors.push((&record.executor, vec![record])),
+            }
+        }
+
+        if day_part_executors.is_empty() {
+            println!("No timings recorded for year {}.", self.year.0);
+            return Ok(());
+        }
+
+        let name_width = day_part_executors
+            .values()
+            .flatten()
+            .map(|(name, _)| name.len())
+            .max()
+            .unwrap_or_default();
+
+        let mut last_day = None;
+
+        for ((day_i, part), executors) in day_part_executors {
+            if last_day != Some(day_i) {
+                if last_day.is_some() {
+                    println!();
+                }
+                println!("{}", format!("Day {day_i}").bold());
+                last_day = Some(day_i);
+            }
+
+            println!("  Part {part}");
+
+            for (name, runs) in executors {
+                let last = runs.last().unwrap();
+                let best = runs
+                    .iter()
+                    .filter(|r| r.status != stats::AnswerStatus::Incorrect)
+                    .min_by_key(|r| r.duration);
+                let trend = runs[runs.len().saturating_sub(Self::TREND_LEN)..]
+                    .iter()
+                    .map(|r| r.duration)
+                    .collect::<Vec<_>>();
+
+                let status = match last.status {
+                    stats::AnswerStatus::Correct => "✔".bold().green(),
+                    stats::AnswerStatus::Incorrect => "✗".bold().red(),
+                    _ => "?".bold().dark_magenta(),
+                };
+
+                let best = match best {
+                    Some(best) => format!(
+                        "{:8} {}",
+                        days::DayPartDuration(best.duration),
+                        format!(
+                            "({} {})",
+                            stats::format_date(best.timestamp),
+                            best.commit.as_deref().unwrap_or("-"),
+                        )
+                        .dark_grey(),
+                    ),
+                    None => format!("{:8}", "-"),
+                };
+
+                println!(
+                    "    {name:name_width$}  {status}  {runs:>4} runs    last {last:8}    best {best}    {trend}",
+                    runs = runs.len(),
+                    last = days::DayPartDuration(last.duration),
+                    trend = stats::sparkline(&trend).yellow(),
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
 // ###################################################################
 // # CLI - Visualize
 // ###################################################################
@@ -504,30 +1470,89 @@ impl CliRunCommand {
 #[derive(Args, Clone, Debug)]
 struct CliVisualizeCommand {
     #[command(flatten)]
-    parts: CliVisualizeCommandPartsGroup,
+    parts: CliSinglePartGroup,
 
     #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "1")]
     test: Option<u32>,
 
-    #[arg(value_parser = YearParser::new())]
-    year: Year,
+    /// Skip ahead this many steps before showing the visualization
+    #[arg(long)]
+    start_step: Option<u64>,
 
-    #[arg(value_parser = DayParser)]
-    day: Day,
-}
+    /// Start playing the visualization immediately
+    #[arg(long)]
+    autoplay: bool,
 
-#[derive(Args, Clone, Debug)]
-#[group(required = true, multiple = false)]
-struct CliVisualizeCommandPartsGroup {
+    /// The playback speed in steps per second
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..=viz::MAX_SPEED as i64))]
+    fps: Option<u32>,
+
+    /// After skipping to the start step, skip ahead until the visualization finds the answer
     #[arg(long)]
-    part1: bool,
+    until_solved: bool,
 
+    /// Record the visualization to an asciicast (`.cast`) or animated GIF (`.gif`) file
     #[arg(long)]
-    part2: bool,
+    record: Option<PathBuf>,
+
+    /// The frame rate of the recording
+    #[arg(long, default_value_t = 10, requires = "record", value_parser = clap::value_parser!(u32).range(1..=100))]
+    record_fps: u32,
+
+    /// Record a frame at most every this many steps
+    #[arg(long, default_value_t = 1, requires = "record", value_parser = clap::value_parser!(u64).range(1..))]
+    record_skip: u64,
+
+    /// Serve the visualization to the browser instead, optionally on this address
+    #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "127.0.0.1:8000", conflicts_with = "script")]
+    web: Option<SocketAddr>,
+
+    /// Show the visualization in a window instead, optionally of this size in pixels
+    #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "800x800", conflicts_with_all = ["web", "script"])]
+    gtk: Option<viz::headless::Size>,
+
+    /// Run without a terminal, pressing these keys, and print the last frame (e.g. `space*100,enter`)
+    #[arg(long)]
+    script: Option<viz::headless::Script>,
+
+    /// The size of the terminal when running a script
+    #[arg(long, default_value_t, requires = "script")]
+    size: viz::headless::Size,
+
+    /// Also print every this many frames when running a script
+    #[arg(long, requires = "script", value_parser = clap::value_parser!(u64).range(1..))]
+    dump_every: Option<u64>,
+
+    #[arg(value_parser = YearParser::new())]
+    year: Year,
+
+    #[arg(value_parser = DayParser::registered())]
+    day: Day,
 }
 
 impl CliVisualizeCommand {
     fn run(self) -> Result<()> {
+        if let Some(path) = self.record {
+            viz::record::enable(viz::record::RecordOptions {
+                path,
+                fps: self.record_fps,
+                skip: self.record_skip,
+            })?;
+        }
+        if let Some(addr) = self.web {
+            viz::web::enable(viz::web::WebOptions { addr })?;
+        }
+        if let Some(size) = self.gtk {
+            viz::desktop::enable(viz::desktop::DesktopOptions { size })?;
+        }
+        if let Some(script) = self.script {
+            viz::headless::enable(viz::headless::HeadlessOptions {
+                script,
+                size: self.size,
+                dump_every: self.dump_every,
+            })?;
+        }
+
         let Some(visualizers) = days::get_day_visualizers(self.year.0, self.day.0) else {
             println!(
                 "No implementation for year {} day {}.",
@@ -552,7 +1577,14 @@ impl CliVisualizeCommand {
             input::get_input(self.year.0, self.day.0)?
         };
 
-        if let Some(answer) = visualize(&input) {
+        let ctx = viz::VizContext {
+            start_step: self.start_step,
+            autoplay: self.autoplay,
+            fps: self.fps,
+            until_solved: self.until_solved,
+        };
+
+        if let Some(answer) = visualize(&input, &ctx)? {
             println!("Part {id}: {answer}");
         }
 
@@ -588,10 +1620,28 @@ impl CliDefaultedPartsGroup {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Args, Clone, Debug)]
+#[group(required = true, multiple = false)]
+struct CliSinglePartGroup {
+    #[arg(long)]
+    part1: bool,
+
+    #[arg(long)]
+    part2: bool,
+}
+
+impl CliSinglePartGroup {
+    fn part(&self) -> Part {
+        if self.part1 { Part::Part1 } else { Part::Part2 }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 struct Year(u32);
 
-const CLI_YEARS: &[&str] = &["2024", "2025"];
+fn year_id_to_cli(year: u32) -> &'static str {
+    year.to_string().leak() as &'static str
+}
 
 #[derive(Clone)]
 struct YearParser {
@@ -600,8 +1650,9 @@ struct YearParser {
 
 impl YearParser {
     fn new() -> Self {
+        let possible_values = days::registered_years().into_iter().map(year_id_to_cli);
         Self {
-            possible_values_parser: clap::builder::PossibleValuesParser::new(CLI_YEARS.to_vec()),
+            possible_values_parser: clap::builder::PossibleValuesParser::new(possible_values),
         }
     }
 }
@@ -624,14 +1675,73 @@ impl clap::builder::TypedValueParser for YearParser {
 }
 
 #[derive(Clone, Copy, Debug)]
+enum CliRunYearSpec {
+    All,
+    Year(Year),
+}
+
+#[derive(Clone)]
+struct CliRunYearSpecParser {
+    possible_values_parser: clap::builder::PossibleValuesParser,
+}
+
+impl CliRunYearSpecParser {
+    fn new() -> Self {
+        let possible_values = iter::once("all")
+            .chain(days::registered_years().into_iter().map(year_id_to_cli))
+            .collect::<Vec<_>>();
+        Self {
+            possible_values_parser: clap::builder::PossibleValuesParser::new(possible_values),
+        }
+    }
+}
+
+impl clap::builder::TypedValueParser for CliRunYearSpecParser {
+    type Value = CliRunYearSpec;
+
+    fn parse_ref(
+        &self,
+        cmd: &clap::Command,
+        arg: Option<&clap::Arg>,
+        value: &std::ffi::OsStr,
+    ) -> std::result::Result<Self::Value, clap::Error> {
+        let year_spec = self.possible_values_parser.parse_ref(cmd, arg, value)?;
+        Ok(if year_spec == "all" {
+            CliRunYearSpec::All
+        } else {
+            CliRunYearSpec::Year(Year(year_spec.parse().unwrap()))
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
 struct Day(u32);
 
 fn day_id_to_cli(n: u32) -> &'static str {
     format!("day{n}").leak() as &'static str
 }
 
+/// Parses `dayN`, either for a day that's registered in some year or for any day of the calendar.
 #[derive(Clone)]
-struct DayParser;
+struct DayParser {
+    days: Vec<u32>,
+}
+
+impl DayParser {
+    /// A day with a registered module, for commands that run or inspect its solution.
+    fn registered() -> Self {
+        Self {
+            days: days::registered_days_of_any_year(),
+        }
+    }
+
+    /// Any day of the calendar, for commands that work on days that aren't written yet.
+    fn any() -> Self {
+        Self {
+            days: (1..=25).collect(),
+        }
+    }
+}
 
 impl clap::builder::TypedValueParser for DayParser {
     type Value = Day;
@@ -643,38 +1753,67 @@ impl clap::builder::TypedValueParser for DayParser {
         value: &std::ffi::OsStr,
     ) -> Result<Self::Value, clap::Error> {
         let possible_day_values =
-            clap::builder::PossibleValuesParser::new((1..=25).map(day_id_to_cli));
+            clap::builder::PossibleValuesParser::new(self.days.iter().copied().map(day_id_to_cli));
         let day = possible_day_values.parse_ref(cmd, arg, value)?;
         let n = day[3..].parse().unwrap();
         Ok(Day(n))
     }
+
+    fn possible_values(
+        &self,
+    ) -> Option<Box<dyn Iterator<Item = clap::builder::PossibleValue> + '_>> {
+        Some(Box::new(
+            self.days
+                .iter()
+                .copied()
+                .map(day_id_to_cli)
+                .map(clap::builder::PossibleValue::new),
+        ))
+    }
 }
 
-#[derive(Clone, Copy, Debug)]
-enum CliRunDaySpec {
+#[derive(Clone, Debug, PartialEq)]
+enum CliDaySpec {
     All,
-    Day(Day),
+    Days(Vec<Day>),
+}
+
+impl CliDaySpec {
+    /// The days of `year` this selects, failing on any that aren't registered in that year.
+    fn days(&self, year: Year) -> Result<Vec<u32>> {
+        let registered = days::registered_days(year.0);
+        match self {
+            CliDaySpec::All => Ok(registered),
+            CliDaySpec::Days(days) => {
+                if let Some(day) = days.iter().find(|day| !registered.contains(&day.0)) {
+                    bail!(
+                        "year {} day {} isn't registered, expected one of: {}",
+                        year.0,
+                        day.0,
+                        registered.iter().map(|&day| day_id_to_cli(day)).join(", ")
+                    );
+                }
+                Ok(days.iter().map(|day| day.0).collect())
+            }
+        }
+    }
 }
 
 #[derive(Clone)]
-struct CliRunDaySpecParser {
-    possible_values_parser: clap::builder::PossibleValuesParser,
+struct CliDaySpecParser {
+    day_parser: DayParser,
 }
 
-impl CliRunDaySpecParser {
+impl CliDaySpecParser {
     fn new() -> Self {
-        let possible_values = iter::once("all")
-            .chain((1..=25).map(day_id_to_cli))
-            .collect::<Vec<_>>();
-        let possible_values_parser = clap::builder::PossibleValuesParser::new(possible_values);
         Self {
-            possible_values_parser,
+            day_parser: DayParser::registered(),
         }
     }
 }
 
-impl clap::builder::TypedValueParser for CliRunDaySpecParser {
-    type Value = CliRunDaySpec;
+impl clap::builder::TypedValueParser for CliDaySpecParser {
+    type Value = CliDaySpec;
 
     fn parse_ref(
         &self,
@@ -682,15 +1821,74 @@ impl clap::builder::TypedValueParser for CliRunDaySpecParser {
         arg: Option<&clap::Arg>,
         value: &std::ffi::OsStr,
     ) -> std::result::Result<Self::Value, clap::Error> {
-        let day_spec = self.possible_values_parser.parse_ref(cmd, arg, value)?;
-        Ok(if day_spec == "all" {
-            CliRunDaySpec::All
-        } else {
-            CliRunDaySpec::Day(Day(day_spec[3..].parse().unwrap()))
-        })
+        if value == "all" {
+            return Ok(CliDaySpec::All);
+        }
+
+        let day_spec = value
+            .to_str()
+            .ok_or_else(|| clap::Error::new(clap::error::ErrorKind::InvalidUtf8).with_cmd(cmd))?;
+
+        let mut days = Vec::new();
+
+        for item in day_spec.split(',') {
+            let day = |day_id: &str| {
+                self.day_parser
+                    .parse_ref(cmd, arg, std::ffi::OsStr::new(day_id))
+            };
+
+            match item.split_once("..") {
+                Some((start, end)) => {
+                    let start = day(start)?;
+                    let end = day(end.strip_prefix('=').unwrap_or(end))?;
+                    if start.0 > end.0 {
+                        return Err(clap::Error::raw(
+                            clap::error::ErrorKind::InvalidValue,
+                            format!("invalid day range '{item}': start is after end\n"),
+                        )
+                        .with_cmd(cmd));
+                    }
+                    days.extend((start.0..=end.0).map(Day));
+                }
+                None => days.push(day(item)?),
+            }
+        }
+
+        Ok(CliDaySpec::Days(days))
+    }
+
+    fn possible_values(
+        &self,
+    ) -> Option<Box<dyn Iterator<Item = clap::builder::PossibleValue> + '_>> {
+        Some(Box::new(
+            iter::once(clap::builder::PossibleValue::new("all"))
+                .chain(self.day_parser.possible_values()?),
+        ))
     }
 }
 
+fn parse_duration(value: &str) -> Result<Duration, String> {
+    let split_at = value
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .ok_or_else(|| format!("missing unit in duration: {value}"))?;
+    let (amount, unit) = value.split_at(split_at);
+    let amount = amount
+        .parse::<f64>()
+        .map_err(|err| format!("invalid duration `{value}`: {err}"))?;
+    let secs = match unit {
+        "s" => amount,
+        "ms" => amount / 1e3,
+        "us" | "µs" => amount / 1e6,
+        "ns" => amount / 1e9,
+        _ => {
+            return Err(format!(
+                "invalid unit in duration, expected s/ms/us/ns: {unit}"
+            ));
+        }
+    };
+    Duration::try_from_secs_f64(secs).map_err(|err| format!("invalid duration `{value}`: {err}"))
+}
+
 #[derive(Clone, Copy)]
 #[repr(u8)]
 enum Part {
@@ -709,3 +1907,70 @@ impl fmt::Display for Part {
         write!(f, "Part {}", self.number())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use clap::builder::TypedValueParser;
+
+    use super::{CliDaySpec, CliDaySpecParser, Day, Year, days};
+
+    fn parse_day_spec(value: &str) -> Result<CliDaySpec, clap::Error> {
+        let cmd = clap::Command::new("test");
+        CliDaySpecParser::new().parse_ref(&cmd, None, std::ffi::OsStr::new(value))
+    }
+
+    #[test]
+    fn parse_duration() {
+        use std::time::Duration;
+
+        assert_eq!(
+            Ok(Duration::from_millis(1500)),
+            super::parse_duration("1.5s")
+        );
+        assert_eq!(
+            Ok(Duration::from_micros(250)),
+            super::parse_duration("250us")
+        );
+        assert!(super::parse_duration("5").is_err());
+        assert!(super::parse_duration("99999999999999999999999s").is_err());
+    }
+
+    #[test]
+    fn day_spec_all() {
+        assert_eq!(CliDaySpec::All, parse_day_spec("all").unwrap());
+    }
+
+    #[test]
+    fn day_spec_list_and_ranges() {
+        let expected = [1, 2, 3, 7, 10, 11].map(Day).to_vec();
+        assert_eq!(
+            CliDaySpec::Days(expected.clone()),
+            parse_day_spec("day1..day3,day7,day10..=day11").unwrap()
+        );
+        assert_eq!(
+            CliDaySpec::Days(vec![Day(4)]),
+            parse_day_spec("day4").unwrap()
+        );
+    }
+
+    #[test]
+    fn day_spec_invalid() {
+        assert!(parse_day_spec("day5..day2").is_err());
+        assert!(parse_day_spec("day0").is_err());
+        assert!(parse_day_spec("day1,all").is_err());
+        assert!(parse_day_spec("day1..").is_err());
+    }
+
+    #[test]
+    fn day_spec_days_of_year() {
+        let registered = days::registered_days(2024);
+        assert_eq!(registered, CliDaySpec::All.days(Year(2024)).unwrap());
+        assert_eq!(
+            vec![registered[0]],
+            CliDaySpec::Days(vec![Day(registered[0])])
+                .days(Year(2024))
+                .unwrap()
+        );
+        assert!(CliDaySpec::Days(vec![Day(26)]).days(Year(2024)).is_err());
+    }
+}
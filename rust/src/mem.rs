@@ -0,0 +1,87 @@
+//! Heap usage reports for `run --mem`, counted by a global allocator that's only installed with
+//! the `mem-stats` feature, so other builds don't pay for it.
+
+use std::fmt;
+
+#[cfg(feature = "mem-stats")]
+mod counting;
+
+#[cfg(feature = "mem-stats")]
+pub(crate) use counting::{enable, measure};
+
+/// Run `f`. Nothing is measured without the `mem-stats` feature, since there's no counting
+/// allocator to ask.
+#[cfg(not(feature = "mem-stats"))]
+pub(crate) fn measure<R>(f: impl FnOnce() -> R) -> (R, Option<MemUsage>) {
+    (f(), None)
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct MemUsage {
+    pub(crate) peak_bytes: usize,
+    pub(crate) n_allocs: usize,
+}
+
+impl fmt::Display for MemUsage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(&format!(
+            "{} peak, {} allocs",
+            format_bytes(self.peak_bytes),
+            format_count(self.n_allocs)
+        ))
+    }
+}
+
+/// Format a number of bytes with a binary suffix, e.g. `12.3KiB`.
+pub(crate) fn format_bytes(bytes: usize) -> String {
+    const SUFFIXES: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+    if bytes < 1024 {
+        return format!("{bytes}B");
+    }
+
+    let mut value = bytes as f64 / 1024.0;
+    let mut suffix = SUFFIXES[0];
+    for s in &SUFFIXES[1..] {
+        if value < 1024.0 {
+            break;
+        }
+        value /= 1024.0;
+        suffix = s;
+    }
+    format!("{value:.1}{suffix}")
+}
+
+/// Format a count with a decimal suffix, e.g. `12.3k`.
+pub(crate) fn format_count(count: usize) -> String {
+    if count < 1_000 {
+        format!("{count}")
+    } else if count < 1_000_000 {
+        format!("{:.1}k", count as f64 / 1e3)
+    } else if count < 1_000_000_000 {
+        format!("{:.1}M", count as f64 / 1e6)
+    } else {
+        format!("{:.1}G", count as f64 / 1e9)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn format_bytes() {
+        assert_eq!("0B", super::format_bytes(0));
+        assert_eq!("1023B", super::format_bytes(1023));
+        assert_eq!("1.0KiB", super::format_bytes(1024));
+        assert_eq!("12.3KiB", super::format_bytes(12_595));
+        assert_eq!("2.5MiB", super::format_bytes(5 << 19));
+        assert_eq!("3.0GiB", super::format_bytes(3 << 30));
+    }
+
+    #[test]
+    fn format_count() {
+        assert_eq!("999", super::format_count(999));
+        assert_eq!("1.0k", super::format_count(1_000));
+        assert_eq!("45.7k", super::format_count(45_678));
+        assert_eq!("1.2M", super::format_count(1_234_567));
+    }
+}
@@ -0,0 +1,93 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicBool, AtomicIsize, AtomicUsize, Ordering},
+};
+
+use super::MemUsage;
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Bytes currently allocated, relative to when counting was enabled. This can go negative when
+/// memory allocated before that point is freed.
+static CURRENT: AtomicIsize = AtomicIsize::new(0);
+static PEAK: AtomicIsize = AtomicIsize::new(0);
+static N_ALLOCS: AtomicUsize = AtomicUsize::new(0);
+
+/// Wraps the system allocator, tracking the heap usage of the process once [`enable`] has been
+/// called. Until then the only overhead is a relaxed load per call.
+struct CountingAllocator;
+
+impl CountingAllocator {
+    fn grow(size: usize) {
+        N_ALLOCS.fetch_add(1, Ordering::Relaxed);
+        let current = CURRENT.fetch_add(size as isize, Ordering::Relaxed) + size as isize;
+        PEAK.fetch_max(current, Ordering::Relaxed);
+    }
+
+    fn shrink(size: usize) {
+        CURRENT.fetch_sub(size as isize, Ordering::Relaxed);
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() && ENABLED.load(Ordering::Relaxed) {
+            Self::grow(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc_zeroed(layout) };
+        if !ptr.is_null() && ENABLED.load(Ordering::Relaxed) {
+            Self::grow(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        if ENABLED.load(Ordering::Relaxed) {
+            Self::shrink(layout.size());
+        }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = unsafe { System.realloc(ptr, layout, new_size) };
+        if !new_ptr.is_null() && ENABLED.load(Ordering::Relaxed) {
+            Self::shrink(layout.size());
+            Self::grow(new_size);
+        }
+        new_ptr
+    }
+}
+
+/// Start counting allocations, must be called before [`measure`] reports anything.
+pub(crate) fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Run `f`, returning the memory it used if counting is enabled.
+///
+/// Allocations made by other threads while `f` runs are counted too.
+pub(crate) fn measure<R>(f: impl FnOnce() -> R) -> (R, Option<MemUsage>) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return (f(), None);
+    }
+
+    let start = CURRENT.load(Ordering::Relaxed);
+    PEAK.store(start, Ordering::Relaxed);
+    let start_n_allocs = N_ALLOCS.load(Ordering::Relaxed);
+
+    let ret = f();
+
+    let usage = MemUsage {
+        peak_bytes: (PEAK.load(Ordering::Relaxed) - start).max(0) as usize,
+        n_allocs: N_ALLOCS.load(Ordering::Relaxed) - start_n_allocs,
+    };
+    (ret, Some(usage))
+}
@@ -0,0 +1,185 @@
+//! Recognize the block letters that some puzzles render their answer as.
+//!
+//! Both fonts used by Advent of Code are supported: the 4x6 font (e.g. 2016 day 8, 2019 day 11,
+//! 2022 day 10) and the 6x10 font (e.g. 2018 day 10). Letters are split on blank columns, so the
+//! spacing between them and any blank margin around the grid don't matter.
+
+#[rustfmt::skip]
+const FONT_6: &[(char, [&str; 6])] = &[
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', ["###", ".#.", ".#.", ".#.", ".#.", "###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Y', ["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#.."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+#[rustfmt::skip]
+const FONT_10: &[(char, [&str; 10])] = &[
+    ('A', ["..##..", ".#..#.", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#"]),
+    ('B', ["#####.", "#....#", "#....#", "#....#", "#####.", "#....#", "#....#", "#....#", "#....#", "#####."]),
+    ('C', [".####.", "#....#", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#....#", ".####."]),
+    ('E', ["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "######"]),
+    ('F', ["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+    ('G', [".####.", "#....#", "#.....", "#.....", "#.....", "#..###", "#....#", "#....#", "#...##", ".###.#"]),
+    ('H', ["#....#", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#", "#....#"]),
+    ('J', ["...###", "....#.", "....#.", "....#.", "....#.", "....#.", "....#.", "#...#.", "#...#.", ".###.."]),
+    ('K', ["#....#", "#...#.", "#..#..", "#.#...", "##....", "##....", "#.#...", "#..#..", "#...#.", "#....#"]),
+    ('L', ["#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "######"]),
+    ('N', ["#....#", "##...#", "##...#", "#.#..#", "#.#..#", "#..#.#", "#..#.#", "#...##", "#...##", "#....#"]),
+    ('P', ["#####.", "#....#", "#....#", "#....#", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+    ('R', ["#####.", "#....#", "#....#", "#....#", "#####.", "#..#..", "#...#.", "#...#.", "#....#", "#....#"]),
+    ('X', ["#....#", "#....#", ".#..#.", ".#..#.", "..##..", "..##..", ".#..#.", ".#..#.", "#....#", "#....#"]),
+    ('Z', ["######", ".....#", ".....#", "....#.", "...#..", "..#...", ".#....", "#.....", "#.....", "######"]),
+];
+
+/// Decode a grid of lit (`true`) and unlit cells. Rows may have different lengths, missing cells
+/// are unlit.
+///
+/// Returns `None` if the letters aren't 6 or 10 cells tall or any of them isn't recognized.
+pub fn decode<R: AsRef<[bool]>>(grid: &[R]) -> Option<String> {
+    let is_blank = |row: &R| !row.as_ref().contains(&true);
+    let top = grid.iter().position(|row| !is_blank(row))?;
+    let bottom = grid.iter().rposition(|row| !is_blank(row))?;
+    let rows = grid[top..=bottom]
+        .iter()
+        .map(AsRef::as_ref)
+        .collect::<Vec<_>>();
+
+    let width = rows.iter().map(|row| row.len()).max()?;
+    let lit = |row: usize, col: usize| rows[row].get(col).copied().unwrap_or(false);
+    let is_blank_col = |col: usize| (0..rows.len()).all(|row| !lit(row, col));
+
+    let mut letters = String::new();
+    let mut col = 0;
+    while col < width {
+        if is_blank_col(col) {
+            col += 1;
+            continue;
+        }
+        let start = col;
+        while col < width && !is_blank_col(col) {
+            col += 1;
+        }
+
+        let glyph_matches = |glyph: &[&str]| {
+            glyph.iter().enumerate().all(|(row, glyph_row)| {
+                glyph_row.len() == col - start
+                    && glyph_row
+                        .bytes()
+                        .enumerate()
+                        .all(|(i, b)| (b == b'#') == lit(row, start + i))
+            })
+        };
+
+        let letter = match rows.len() {
+            6 => {
+                FONT_6
+                    .iter()
+                    .find(|(_, glyph)| glyph_matches(&trim_glyph(glyph)))?
+                    .0
+            }
+            10 => {
+                FONT_10
+                    .iter()
+                    .find(|(_, glyph)| glyph_matches(&trim_glyph(glyph)))?
+                    .0
+            }
+            _ => return None,
+        };
+        letters.push(letter);
+    }
+
+    Some(letters)
+}
+
+/// Decode a grid where `#` is lit and any other character is unlit, one row per line.
+pub fn decode_str(grid: &str) -> Option<String> {
+    let rows = grid
+        .lines()
+        .map(|line| line.chars().map(|c| c == '#').collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    decode(&rows)
+}
+
+/// Remove the blank columns on either side of a glyph so it can be compared to a letter split out
+/// of a grid.
+fn trim_glyph<'g>(glyph: &[&'g str]) -> Vec<&'g str> {
+    let width = glyph.iter().map(|row| row.len()).max().unwrap_or_default();
+    let is_lit_col = |col: usize| {
+        glyph
+            .iter()
+            .any(|row| row.as_bytes().get(col) == Some(&b'#'))
+    };
+    let start = (0..width).find(|&col| is_lit_col(col)).unwrap_or_default();
+    let end = (0..width)
+        .rfind(|&col| is_lit_col(col))
+        .map_or(start, |col| col + 1);
+    glyph.iter().map(|row| &row[start..end]).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn decode_str_6() {
+        let grid = "\
+.##..###...##..####.####..##..#..#
+#..#.#..#.#..#.#....#....#..#.#..#
+#..#.###..#....###..###..#....####
+####.#..#.#....#....#....#.##.#..#
+#..#.#..#.#..#.#....#....#..#.#..#
+#..#.###...##..####.#.....###.#..#";
+        assert_eq!(Some("ABCEFGH"), super::decode_str(grid).as_deref());
+    }
+
+    #[test]
+    fn decode_str_6_margins_and_narrow_letters() {
+        let grid = "
+  ............................
+  .###...##.#..#...#...#.####.
+  ..#.....#.#.#....#...#....#.
+  ..#.....#.##......#.#....#..
+  ..#.....#.#.#......#....#...
+  ..#..#..#.#.#......#...#....
+  .###..##..#..#.....#...####.
+";
+        assert_eq!(Some("IJKYZ"), super::decode_str(grid).as_deref());
+    }
+
+    #[test]
+    fn decode_10() {
+        let grid = [
+            "#....#..######",
+            "#....#.......#",
+            ".#..#........#",
+            ".#..#.......#.",
+            "..##.......#..",
+            "..##......#...",
+            ".#..#....#....",
+            ".#..#...#.....",
+            "#....#..#.....",
+            "#....#..######",
+        ]
+        .map(|row| row.chars().map(|c| c == '#').collect::<Vec<_>>());
+        assert_eq!(Some("XZ"), super::decode(&grid).as_deref());
+    }
+
+    #[test]
+    fn decode_unrecognized() {
+        assert_eq!(None, super::decode_str("#\n#\n#\n#\n#\n#"));
+        assert_eq!(None, super::decode_str("####\n####"));
+        assert_eq!(None, super::decode_str("...."));
+    }
+}
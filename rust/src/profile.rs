@@ -0,0 +1,202 @@
+use std::{
+    collections::BTreeMap,
+    fs, hint,
+    path::{Path, PathBuf},
+    process::{self, Stdio},
+    sync::LazyLock,
+    thread,
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, Result, bail};
+
+use crate::days::DayPartExecutor;
+
+pub(crate) static PUZZLE_PROFILES_DIR: LazyLock<PathBuf> =
+    LazyLock::new(|| Path::new(crate::PUZZLE_DIR).join("profiles"));
+
+fn create_profiles_dir(year: u32) -> Result<PathBuf> {
+    let dir = PUZZLE_PROFILES_DIR.join(year.to_string());
+    fs::create_dir_all(&dir).with_context(|| {
+        format!(
+            "failed to create puzzle profiles directory: {}",
+            dir.display()
+        )
+    })?;
+    Ok(dir)
+}
+
+/// Give `perf` time to attach before the executor loop starts so the first samples aren't lost.
+const PERF_ATTACH_DELAY: Duration = Duration::from_millis(250);
+
+pub(crate) struct Profile {
+    pub(crate) folded_path: PathBuf,
+    pub(crate) iterations: u64,
+    pub(crate) n_samples: u64,
+}
+
+/// Run `executor` in a loop for `duration` while `perf` samples this process, then collapse the
+/// recorded stacks into the folded format understood by `inferno-flamegraph` and `flamegraph.pl`.
+pub(crate) fn profile(
+    year: u32,
+    day_i: u32,
+    part: crate::Part,
+    executor: &DayPartExecutor,
+    input: &str,
+    duration: Duration,
+    frequency: u32,
+) -> Result<Profile> {
+    let dir = create_profiles_dir(year)?;
+    let stem = format!("day{day_i}.{}.{}", part.number(), executor.name());
+    let perf_data_path = dir.join(format!("{stem}.perf.data"));
+    let folded_path = dir.join(format!("{stem}.folded"));
+
+    let mut perf = process::Command::new("perf")
+        .arg("record")
+        .args(["--freq", &frequency.to_string()])
+        .args(["--call-graph", "dwarf"])
+        .args(["--pid", &process::id().to_string()])
+        .arg("--output")
+        .arg(&perf_data_path)
+        .arg("--quiet")
+        .stdout(Stdio::null())
+        .spawn()
+        .context("failed to start `perf record`, is `perf` installed?")?;
+
+    thread::sleep(PERF_ATTACH_DELAY);
+
+    let mut iterations = 0_u64;
+    let deadline = Instant::now() + duration;
+    while Instant::now() < deadline {
+        hint::black_box(executor.execute(hint::black_box(input)));
+        iterations += 1;
+    }
+
+    // `perf record` only writes a usable data file when it is interrupted.
+    let status = process::Command::new("kill")
+        .args(["-INT", &perf.id().to_string()])
+        .status()
+        .context("failed to stop `perf record`")?;
+    if !status.success() {
+        bail!("failed to stop `perf record`: kill exited with {status}");
+    }
+    perf.wait().context("failed to wait for `perf record`")?;
+
+    let output = process::Command::new("perf")
+        .arg("script")
+        .arg("--input")
+        .arg(&perf_data_path)
+        .stderr(Stdio::null())
+        .output()
+        .context("failed to run `perf script`")?;
+    if !output.status.success() {
+        bail!("`perf script` exited with {}", output.status);
+    }
+
+    let stacks = collapse_perf_script(&String::from_utf8_lossy(&output.stdout));
+    let n_samples = stacks.values().sum();
+
+    let folded = stacks
+        .iter()
+        .map(|(stack, count)| format!("{stack} {count}\n"))
+        .collect::<String>();
+    fs::write(&folded_path, folded).with_context(|| {
+        format!(
+            "failed to write collapsed stacks to file: {}",
+            folded_path.display()
+        )
+    })?;
+
+    Ok(Profile {
+        folded_path,
+        iterations,
+        n_samples,
+    })
+}
+
+/// Collapse the output of `perf script` into `root;...;leaf count` lines, keyed by stack.
+///
+/// Each sample is a header line (`comm pid time: period event:`) followed by one frame per line,
+/// leaf first (`addr symbol+offset (dso)`), and terminated by an empty line.
+fn collapse_perf_script(script: &str) -> BTreeMap<String, u64> {
+    let mut stacks = BTreeMap::<String, u64>::new();
+
+    let mut comm = None;
+    let mut frames = Vec::new();
+
+    let mut flush = |comm: &mut Option<&str>, frames: &mut Vec<&str>| {
+        if let Some(comm) = comm.take() {
+            let stack = std::iter::once(comm)
+                .chain(frames.drain(..).rev())
+                .collect::<Vec<_>>()
+                .join(";");
+            *stacks.entry(stack).or_default() += 1;
+        }
+        frames.clear();
+    };
+
+    for line in script.lines() {
+        if line.trim().is_empty() {
+            flush(&mut comm, &mut frames);
+        } else if line.starts_with(char::is_whitespace) {
+            let frame = line.trim();
+            let symbol = frame
+                .split_once(char::is_whitespace)
+                .map(|(_addr, rest)| rest)
+                .unwrap_or(frame);
+            let symbol = symbol
+                .rsplit_once(" (")
+                .map(|(symbol, _dso)| symbol)
+                .unwrap_or(symbol);
+            let symbol = symbol
+                .rsplit_once("+0x")
+                .map(|(symbol, _offset)| symbol)
+                .unwrap_or(symbol);
+            frames.push(symbol);
+        } else {
+            flush(&mut comm, &mut frames);
+            comm = line.split_whitespace().next();
+        }
+    }
+
+    flush(&mut comm, &mut frames);
+
+    stacks
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn collapse_perf_script() {
+        let script = "\
+adventofcode 1234 100.000001:     336000 cpu-clock:
+\t    55d0c0e1a2b3 adventofcode::puzzles::year2024::day16::Maze::solve_dijkstras+0x120 (/target/debug/adventofcode)
+\t    55d0c0e1a000 adventofcode::puzzles::year2024::day16::part2+0x20 (/target/debug/adventofcode)
+\t    55d0c0e10000 main+0x10 (/target/debug/adventofcode)
+
+adventofcode 1234 100.000002:     336000 cpu-clock:
+\t    55d0c0e1a2b3 adventofcode::puzzles::year2024::day16::Maze::solve_dijkstras+0x150 (/target/debug/adventofcode)
+\t    55d0c0e1a000 adventofcode::puzzles::year2024::day16::part2+0x20 (/target/debug/adventofcode)
+\t    55d0c0e10000 main+0x10 (/target/debug/adventofcode)
+
+adventofcode 1234 100.000003:     336000 cpu-clock:
+\t    7f0000000000 [unknown] ([unknown])
+";
+
+        let stacks = super::collapse_perf_script(script);
+
+        assert_eq!(
+            vec![
+                ("adventofcode;[unknown]", 1),
+                (
+                    "adventofcode;main;adventofcode::puzzles::year2024::day16::part2;adventofcode::puzzles::year2024::day16::Maze::solve_dijkstras",
+                    2
+                ),
+            ],
+            stacks
+                .iter()
+                .map(|(s, c)| (s.as_str(), *c))
+                .collect::<Vec<_>>()
+        );
+    }
+}
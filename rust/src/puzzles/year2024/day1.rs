@@ -5,7 +5,7 @@ inventory::submit!(crate::days::DayModule::new(2024, 1).with_executors(
     crate::day_part_executors![part2],
 ));
 
-fn part1(input: &str) -> Option<Box<dyn std::fmt::Display>> {
+fn part1(input: &str) -> Option<crate::answer::Answer> {
     let mut left = Vec::<u32>::new();
     let mut right = Vec::<u32>::new();
 
@@ -30,10 +30,10 @@ fn part1(input: &str) -> Option<Box<dyn std::fmt::Display>> {
         })
         .sum::<u32>();
 
-    Some(Box::new(answer))
+    Some(answer.into())
 }
 
-fn part2(input: &str) -> Option<Box<dyn std::fmt::Display>> {
+fn part2(input: &str) -> Option<crate::answer::Answer> {
     let mut left = HashMap::<u32, u32>::new();
     let mut right = HashMap::<u32, u32>::new();
 
@@ -53,5 +53,5 @@ fn part2(input: &str) -> Option<Box<dyn std::fmt::Display>> {
         .map(|(k, v)| right.get(&k).copied().unwrap_or_default() * v * k)
         .sum::<u32>();
 
-    Some(Box::new(answer))
+    Some(answer.into())
 }
@@ -3,11 +3,22 @@ use std::{
     ops,
 };
 
-inventory::submit!(crate::days::DayModule::new(2024, 10).with_executors(
-    crate::day_part_executors![part1],
-    crate::day_part_executors![part2],
-));
+use crate::viz::{
+    grid::{GridPuzzle, GridViz, Overlay},
+    scene::Rgba,
+};
 
+inventory::submit!(
+    crate::days::DayModule::new(2024, 10)
+        .with_executors(
+            crate::day_part_executors![part1],
+            crate::day_part_executors![part2],
+        )
+        .with_pt1_visualizer(part1_viz)
+        .with_pt2_visualizer(part2_viz)
+);
+
+#[derive(Clone)]
 struct Map<'input> {
     height: usize,
     width: usize,
@@ -112,6 +123,33 @@ impl<'input> Map<'input> {
             _trail_step(self, pos.ss(), next, trailends);
         }
     }
+
+    /// Collect every cell on a hiking trail from `pos` to a 9, returning whether there are any.
+    fn trail_cells(&self, pos: Pos, cells: &mut HashSet<Pos>) -> bool {
+        let height = self[pos];
+        if height == b'9' {
+            cells.insert(pos);
+            return true;
+        }
+
+        let neighbors = [
+            (pos.col > 0).then(|| pos.ww()),
+            (pos.col < self.width as u32 - 1).then(|| pos.ee()),
+            (pos.row > 0).then(|| pos.nn()),
+            (pos.row < self.height as u32 - 1).then(|| pos.ss()),
+        ];
+
+        let mut on_trail = false;
+        for next in neighbors.into_iter().flatten() {
+            if self[next] == height + 1 {
+                on_trail |= cells.contains(&next) || self.trail_cells(next, cells);
+            }
+        }
+        if on_trail {
+            cells.insert(pos);
+        }
+        on_trail
+    }
 }
 
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
@@ -155,6 +193,10 @@ impl Pos {
             col: self.col - 1,
         }
     }
+
+    fn cell(self) -> (usize, usize) {
+        (self.row as usize, self.col as usize)
+    }
 }
 
 impl ops::Index<Pos> for Map<'_> {
@@ -166,7 +208,7 @@ impl ops::Index<Pos> for Map<'_> {
     }
 }
 
-fn part1(input: &str) -> Option<Box<dyn std::fmt::Display>> {
+fn part1(input: &str) -> Option<crate::answer::Answer> {
     let map = Map::parse(input);
 
     let mut trailends = HashSet::<Pos>::new();
@@ -176,10 +218,10 @@ fn part1(input: &str) -> Option<Box<dyn std::fmt::Display>> {
         .map(|pos| map.score_trailhead(pos, &mut trailends))
         .sum::<u32>();
 
-    Some(Box::new(answer))
+    Some(answer.into())
 }
 
-fn part2(input: &str) -> Option<Box<dyn std::fmt::Display>> {
+fn part2(input: &str) -> Option<crate::answer::Answer> {
     let map = Map::parse(input);
 
     let mut trailends = HashMap::<Pos, u32>::new();
@@ -189,5 +231,186 @@ fn part2(input: &str) -> Option<Box<dyn std::fmt::Display>> {
         .map(|pos| map.rate_trailhead(pos, &mut trailends))
         .sum::<u32>();
 
-    Some(Box::new(answer))
+    Some(answer.into())
+}
+
+/// Score, or rate, one trailhead per step, showing the trails from it.
+#[derive(Clone)]
+struct TrailheadsViz<'input> {
+    map: Map<'input>,
+    rate: bool,
+    trailheads: Vec<Pos>,
+    /// The score or rating of each trailhead done so far.
+    values: Vec<u32>,
+    /// The cells on the trails of the last trailhead.
+    trails: HashSet<Pos>,
+    trailends: Vec<Pos>,
+}
+
+impl<'input> TrailheadsViz<'input> {
+    fn new(input: &'input str, rate: bool) -> Self {
+        let map = Map::parse(input);
+        let trailheads = map.iter_trailheads().collect();
+        Self {
+            map,
+            rate,
+            trailheads,
+            values: Vec::new(),
+            trails: HashSet::new(),
+            trailends: Vec::new(),
+        }
+    }
+
+    fn value_name(&self) -> &'static str {
+        if self.rate { "rating" } else { "score" }
+    }
+}
+
+impl GridPuzzle for TrailheadsViz<'_> {
+    fn step(&mut self) -> bool {
+        let Some(&trailhead) = self.trailheads.get(self.values.len()) else {
+            return false;
+        };
+
+        let value = if self.rate {
+            let mut trailends = HashMap::new();
+            let rating = self.map.rate_trailhead(trailhead, &mut trailends);
+            self.trailends = trailends.into_keys().collect();
+            rating
+        } else {
+            let mut trailends = HashSet::new();
+            let score = self.map.score_trailhead(trailhead, &mut trailends);
+            self.trailends = trailends.into_iter().collect();
+            score
+        };
+        self.values.push(value);
+
+        self.trails.clear();
+        self.map.trail_cells(trailhead, &mut self.trails);
+
+        true
+    }
+
+    fn is_solved(&self) -> bool {
+        self.values.len() == self.trailheads.len()
+    }
+
+    fn size(&self) -> (usize, usize) {
+        (self.map.height, self.map.width)
+    }
+
+    fn cell(&self, row: usize, col: usize) -> u8 {
+        self.map.grid[row][col]
+    }
+
+    fn cell_color(&self, cell: u8) -> Option<Rgba> {
+        let height = cell.checked_sub(b'0').filter(|&height| height <= 9)?;
+        let shade = 40 + height * 18;
+        Some(Rgba::rgb(shade / 3, shade, shade / 3))
+    }
+
+    fn overlays(&self) -> Vec<Overlay> {
+        let mut overlays = vec![
+            Overlay::fill(
+                "trails",
+                Rgba::rgb(255, 200, 0).with_alpha(0.5),
+                self.trails.iter().map(|pos| pos.cell()).collect(),
+            ),
+            Overlay::highlight(
+                "trail ends",
+                Rgba::rgb(255, 80, 80),
+                self.trailends.iter().map(|pos| pos.cell()).collect(),
+            ),
+            Overlay::labels(
+                if self.rate { "ratings" } else { "scores" },
+                Rgba::WHITE,
+                self.trailheads
+                    .iter()
+                    .zip(&self.values)
+                    .map(|(pos, value)| (pos.cell(), value.to_string()))
+                    .collect(),
+            ),
+        ];
+        if let Some(last) = self.values.len().checked_sub(1) {
+            overlays.push(Overlay::highlight(
+                "trailhead",
+                Rgba::rgb(80, 160, 255),
+                vec![self.trailheads[last].cell()],
+            ));
+        }
+        overlays
+    }
+
+    fn inspect(&self, row: usize, col: usize) -> Option<String> {
+        let pos = Pos::new(row, col);
+        let i = self
+            .trailheads
+            .iter()
+            .position(|&trailhead| trailhead == pos)?;
+        let value = self.values.get(i)?;
+        Some(format!("{} {value}", self.value_name()))
+    }
+
+    fn status(&self) -> Vec<(&'static str, String)> {
+        vec![
+            (
+                "Trailheads",
+                format!("{}/{}", self.values.len(), self.trailheads.len()),
+            ),
+            ("Sum", self.values.iter().sum::<u32>().to_string()),
+        ]
+    }
+
+    fn answer(&self) -> Option<crate::answer::Answer> {
+        self.is_solved()
+            .then(|| self.values.iter().sum::<u32>().into())
+    }
+}
+
+fn part1_viz(
+    input: &str,
+    ctx: &crate::viz::VizContext,
+) -> anyhow::Result<Option<crate::answer::Answer>> {
+    crate::viz::Driver::new(GridViz::new(TrailheadsViz::new(input, false)), ctx)
+        .with_rewind()
+        .run()
+}
+
+fn part2_viz(
+    input: &str,
+    ctx: &crate::viz::VizContext,
+) -> anyhow::Result<Option<crate::answer::Answer>> {
+    crate::viz::Driver::new(GridViz::new(TrailheadsViz::new(input, true)), ctx)
+        .with_rewind()
+        .run()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::viz::{
+        grid::GridViz,
+        headless::{self, Size},
+    };
+
+    use super::TrailheadsViz;
+
+    const EXAMPLE: &str = "\
+89010123
+78121874
+87430965
+96549874
+45678903
+32019012
+01329801
+10456732
+";
+
+    #[test]
+    fn sums_the_trailheads() {
+        for (rate, expected) in [(false, 36_u64), (true, 81)] {
+            let viz = GridViz::new(TrailheadsViz::new(EXAMPLE, rate));
+            let (answer, _) = headless::run_to_end(viz, Size::default());
+            assert_eq!(Some(expected.into()), answer);
+        }
+    }
 }
@@ -43,12 +43,12 @@ fn blink_in_infinite_corridor<const N_BLINKS: u32>(input: &str) -> u64 {
     stones.values().copied().sum::<u64>()
 }
 
-fn part1(input: &str) -> Option<Box<dyn std::fmt::Display>> {
+fn part1(input: &str) -> Option<crate::answer::Answer> {
     let n_stones = blink_in_infinite_corridor::<25>(input);
-    Some(Box::new(n_stones))
+    Some(n_stones.into())
 }
 
-fn part2(input: &str) -> Option<Box<dyn std::fmt::Display>> {
+fn part2(input: &str) -> Option<crate::answer::Answer> {
     let n_stones = blink_in_infinite_corridor::<75>(input);
-    Some(Box::new(n_stones))
+    Some(n_stones.into())
 }
@@ -1,10 +1,21 @@
 use std::{cmp, fmt, ops};
 
-inventory::submit!(crate::days::DayModule::new(2024, 12).with_executors(
-    crate::day_part_executors![part1],
-    crate::day_part_executors![part2],
-));
-
+use crate::viz::{
+    grid::{GridPuzzle, GridViz, Overlay},
+    scene::Rgba,
+};
+
+inventory::submit!(
+    crate::days::DayModule::new(2024, 12)
+        .with_executors(
+            crate::day_part_executors![part1],
+            crate::day_part_executors![part2],
+        )
+        .with_pt1_visualizer(part1_viz)
+        .with_pt2_visualizer(part2_viz)
+);
+
+#[derive(Clone)]
 struct Map<'input> {
     height: usize,
     width: usize,
@@ -474,6 +485,7 @@ impl<'input> Map<'input> {
     }
 }
 
+#[derive(Clone)]
 struct FlatMap<T> {
     width: usize,
     map: Vec<T>,
@@ -645,16 +657,267 @@ impl VerticalEdgeOrdering for (Pos, VOrientation) {
     }
 }
 
-fn part1(input: &str) -> Option<Box<dyn std::fmt::Display>> {
+fn part1(input: &str) -> Option<crate::answer::Answer> {
     let mut map = Map::parse(input);
     let total_cost = map.floodfill_solve();
 
-    Some(Box::new(total_cost))
+    Some(total_cost.into())
 }
 
-fn part2(input: &str) -> Option<Box<dyn std::fmt::Display>> {
+fn part2(input: &str) -> Option<crate::answer::Answer> {
     let mut map = Map::parse(input);
     let total_cost = map.floodfill_solve_bulk();
 
-    Some(Box::new(total_cost))
+    Some(total_cost.into())
+}
+
+/// A region fenced by the visualizer.
+#[derive(Clone)]
+struct Region {
+    plant: u8,
+    area: u64,
+    cost: u64,
+}
+
+/// Fence one region per step, in the order the solution finds them.
+#[derive(Clone)]
+struct RegionsViz<'input> {
+    map: Map<'input>,
+    bulk: bool,
+    /// The next cell to seed a region from, in reading order.
+    next_seed: usize,
+    /// The index in `regions` of each cell fenced so far.
+    region_of: FlatMap<Option<usize>>,
+    regions: Vec<Region>,
+    /// The cells of the last region.
+    current: Vec<Pos>,
+    to_search: Vec<Pos>,
+    horizontal_edges: Vec<(Pos, HOrientation)>,
+    vertical_edges: Vec<(Pos, VOrientation)>,
+}
+
+impl<'input> RegionsViz<'input> {
+    fn new(input: &'input str, bulk: bool) -> Self {
+        let map = Map::parse(input);
+        let region_of = FlatMap::new(map.height, map.width, None);
+        Self {
+            map,
+            bulk,
+            next_seed: 0,
+            region_of,
+            regions: Vec::new(),
+            current: Vec::new(),
+            to_search: Vec::new(),
+            horizontal_edges: Vec::new(),
+            vertical_edges: Vec::new(),
+        }
+    }
+
+    fn seed(&self) -> Pos {
+        Pos::new(
+            self.next_seed / self.map.width,
+            self.next_seed % self.map.width,
+        )
+    }
+
+    /// Collect the cells of the region just fenced from `seed`.
+    fn collect_region(&mut self, seed: Pos) {
+        let plant = self.map.plot(seed);
+        let region = self.regions.len();
+
+        self.current.clear();
+        self.region_of[seed] = Some(region);
+        let mut to_visit = vec![seed];
+        while let Some(pos) = to_visit.pop() {
+            self.current.push(pos);
+
+            let neighbors = [
+                (!self.map.pos_on_north_edge(pos)).then(|| pos.nn()),
+                (!self.map.pos_on_east_edge(pos)).then(|| pos.ee()),
+                (!self.map.pos_on_south_edge(pos)).then(|| pos.ss()),
+                (!self.map.pos_on_west_edge(pos)).then(|| pos.ww()),
+            ];
+            for next in neighbors.into_iter().flatten() {
+                if self.map.plot(next) == plant && self.region_of[next].is_none() {
+                    self.region_of[next] = Some(region);
+                    to_visit.push(next);
+                }
+            }
+        }
+    }
+}
+
+impl GridPuzzle for RegionsViz<'_> {
+    fn step(&mut self) -> bool {
+        let n_cells = self.map.height * self.map.width;
+        while self.next_seed < n_cells && self.map.mapped_plots[self.seed()] {
+            self.next_seed += 1;
+        }
+        if self.next_seed == n_cells {
+            return false;
+        }
+
+        let seed = self.seed();
+        let cost = if self.bulk {
+            self.map.floodfill_region_bulk_cost(
+                seed,
+                &mut self.to_search,
+                &mut self.horizontal_edges,
+                &mut self.vertical_edges,
+            )
+        } else {
+            self.map.floodfill_region_cost(seed, &mut self.to_search)
+        };
+
+        self.collect_region(seed);
+        self.regions.push(Region {
+            plant: self.map.plot(seed),
+            area: self.current.len() as u64,
+            cost,
+        });
+
+        true
+    }
+
+    fn is_solved(&self) -> bool {
+        self.region_of.map.iter().all(Option::is_some)
+    }
+
+    fn size(&self) -> (usize, usize) {
+        (self.map.height, self.map.width)
+    }
+
+    fn cell(&self, row: usize, col: usize) -> u8 {
+        self.map.plots[row][col]
+    }
+
+    fn cell_color(&self, cell: u8) -> Option<Rgba> {
+        const PALETTE: [Rgba; 8] = [
+            Rgba::rgb(230, 25, 75),
+            Rgba::rgb(60, 180, 75),
+            Rgba::rgb(255, 225, 25),
+            Rgba::rgb(0, 130, 200),
+            Rgba::rgb(245, 130, 48),
+            Rgba::rgb(145, 30, 180),
+            Rgba::rgb(70, 240, 240),
+            Rgba::rgb(240, 50, 230),
+        ];
+        Some(PALETTE[cell as usize % PALETTE.len()])
+    }
+
+    fn overlays(&self) -> Vec<Overlay> {
+        let fenced = (0..self.map.height)
+            .flat_map(|row| (0..self.map.width).map(move |col| (row, col)))
+            .filter(|&(row, col)| self.region_of[Pos::new(row, col)].is_some())
+            .collect();
+
+        let mut overlays = vec![
+            Overlay::fill("fenced", Rgba::rgb(90, 90, 90).with_alpha(0.6), fenced),
+            Overlay::highlight(
+                "region",
+                Rgba::rgb(255, 200, 0),
+                self.current
+                    .iter()
+                    .map(|pos| (pos.row as usize, pos.col as usize))
+                    .collect(),
+            ),
+        ];
+        if let Some(region) = self.regions.last() {
+            overlays.push(Overlay::labels(
+                "cost",
+                Rgba::WHITE,
+                vec![(
+                    (self.current[0].row as usize, self.current[0].col as usize),
+                    region.cost.to_string(),
+                )],
+            ));
+        }
+        overlays
+    }
+
+    fn inspect(&self, row: usize, col: usize) -> Option<String> {
+        let region = &self.regions[self.region_of[Pos::new(row, col)]?];
+        let fence = if self.bulk { "sides" } else { "perimeter" };
+        Some(format!(
+            "{} region, area {}, {fence} {}, cost {}",
+            region.plant as char,
+            region.area,
+            region.cost / region.area,
+            region.cost
+        ))
+    }
+
+    fn status(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("Regions", self.regions.len().to_string()),
+            (
+                "Cost",
+                self.regions
+                    .iter()
+                    .map(|region| region.cost)
+                    .sum::<u64>()
+                    .to_string(),
+            ),
+        ]
+    }
+
+    fn answer(&self) -> Option<crate::answer::Answer> {
+        self.is_solved().then(|| {
+            self.regions
+                .iter()
+                .map(|region| region.cost)
+                .sum::<u64>()
+                .into()
+        })
+    }
+}
+
+fn part1_viz(
+    input: &str,
+    ctx: &crate::viz::VizContext,
+) -> anyhow::Result<Option<crate::answer::Answer>> {
+    crate::viz::Driver::new(GridViz::new(RegionsViz::new(input, false)), ctx)
+        .with_rewind()
+        .run()
+}
+
+fn part2_viz(
+    input: &str,
+    ctx: &crate::viz::VizContext,
+) -> anyhow::Result<Option<crate::answer::Answer>> {
+    crate::viz::Driver::new(GridViz::new(RegionsViz::new(input, true)), ctx)
+        .with_rewind()
+        .run()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::viz::{
+        grid::GridViz,
+        headless::{self, Size},
+    };
+
+    use super::RegionsViz;
+
+    const EXAMPLE: &str = "\
+RRRRIICCFF
+RRRRIICCCF
+VVRRRCCFFF
+VVRCCCJFFF
+VVVVCJJCFE
+VVIVCCJJEE
+VVIIICJJEE
+MIIIIIJJEE
+MIIISIJEEE
+MMMISSJEEE
+";
+
+    #[test]
+    fn prices_the_fences() {
+        for (bulk, expected) in [(false, 1930_u64), (true, 1206)] {
+            let viz = GridViz::new(RegionsViz::new(EXAMPLE, bulk));
+            let (answer, _) = headless::run_to_end(viz, Size::default());
+            assert_eq!(Some(expected.into()), answer);
+        }
+    }
 }
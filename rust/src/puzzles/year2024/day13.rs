@@ -221,7 +221,7 @@ fn parse(input: &str) -> Vec<ClawMachine> {
     claw_machines
 }
 
-fn part1(input: &str) -> Option<Box<dyn std::fmt::Display>> {
+fn part1(input: &str) -> Option<crate::answer::Answer> {
     let claw_machines = parse(input);
 
     let answer = claw_machines
@@ -229,10 +229,10 @@ fn part1(input: &str) -> Option<Box<dyn std::fmt::Display>> {
         .filter_map(ClawMachine::solve)
         .sum::<u64>();
 
-    Some(Box::new(answer))
+    Some(answer.into())
 }
 
-fn part2(input: &str) -> Option<Box<dyn std::fmt::Display>> {
+fn part2(input: &str) -> Option<crate::answer::Answer> {
     let claw_machines = parse(input);
 
     let answer = claw_machines
@@ -240,7 +240,7 @@ fn part2(input: &str) -> Option<Box<dyn std::fmt::Display>> {
         .filter_map(ClawMachine::solve_v2)
         .sum::<u64>();
 
-    Some(Box::new(answer))
+    Some(answer.into())
 }
 
 #[cfg(test)]
@@ -1,19 +1,18 @@
-use std::{
-    cmp,
-    collections::HashMap,
-    fmt,
-    io::{self, Read, Write},
-};
+use std::{cmp, collections::HashMap, fmt};
 
-use anyhow::Result;
-use crossterm::{cursor, execute, queue, style, terminal};
 use nalgebra::Vector2;
+use ratatui::{
+    Frame,
+    layout::Rect,
+    text::{Line, Text},
+    widgets::{Block, BorderType, Paragraph},
+};
 
 inventory::submit!(
     crate::days::DayModule::new(2024, 14)
         .with_executors(
             crate::day_part_executors![part1],
-            crate::day_part_executors![part2],
+            crate::day_part_executors![part2, part2_variance],
         )
         .with_pt2_visualizer(part2_viz)
 );
@@ -89,6 +88,82 @@ impl Map {
         let mut robot_positions =
             vec![Vec::<u8>::with_capacity(self.robots.len() / 4); self.height as usize];
 
+        let mut steps = 0_u32;
+
+        while !self.has_easter_egg(&mut robot_positions) {
+            steps += 1;
+            self.step(1);
+        }
+
+        steps
+    }
+
+    /// Up to `count` steps, best first, where the robots are most bunched together within the
+    /// first `width * height` steps, after which their positions repeat.
+    ///
+    /// The x positions repeat every `width` steps and the y positions every `height` steps, so the
+    /// variance of each is only measured over its own period. The best phases of x and y are then
+    /// paired up with the Chinese remainder theorem, ranked by the total variance.
+    fn easter_egg_candidates(&self, count: usize) -> Vec<u64> {
+        /// `n²` times the variance of `n` values, which ranks the same without dividing.
+        fn spread(values: impl Iterator<Item = i64>) -> i64 {
+            let (mut n, mut sum, mut sum_sq) = (0, 0, 0);
+            for v in values {
+                n += 1;
+                sum += v;
+                sum_sq += v * v;
+            }
+            n * sum_sq - sum * sum
+        }
+
+        /// The `count` phases of a period with the least spread, and their spreads.
+        fn best_phases(
+            period: i64,
+            count: usize,
+            spread_at: impl Fn(i64) -> i64,
+        ) -> Vec<(i64, i64)> {
+            let mut phases = (0..period).map(|t| (t, spread_at(t))).collect::<Vec<_>>();
+            phases.sort_unstable_by_key(|&(t, spread)| (spread, t));
+            phases.truncate(count);
+            phases
+        }
+
+        let (width, height) = (self.width, self.height);
+        let xs = best_phases(width, count, |t| {
+            spread(
+                self.robots
+                    .iter()
+                    .map(|r| (r.position.x + t * r.velocity.x).rem_euclid(width)),
+            )
+        });
+        let ys = best_phases(height, count, |t| {
+            spread(
+                self.robots
+                    .iter()
+                    .map(|r| (r.position.y + t * r.velocity.y).rem_euclid(height)),
+            )
+        });
+
+        let mut candidates = xs
+            .iter()
+            .flat_map(|&(tx, sx)| ys.iter().map(move |&(ty, sy)| (tx, ty, sx + sy)))
+            .filter_map(|(tx, ty, spread)| {
+                // The step that's `tx` into the x period and `ty` into the y period.
+                let step = (0..height)
+                    .map(|k| tx + k * width)
+                    .find(|step| step % height == ty)?;
+                Some((spread, step as u64))
+            })
+            .collect::<Vec<_>>();
+        candidates.sort_unstable();
+        candidates.truncate(count);
+
+        candidates.into_iter().map(|(_, step)| step).collect()
+    }
+
+    /// Whether any row has 31 contiguous robots. `robot_positions` is scratch space for the x
+    /// positions of the robots in each row.
+    fn has_easter_egg(&self, robot_positions: &mut [Vec<u8>]) -> bool {
         fn has_contiguous_run(n: u32, values: &mut [u8]) -> bool {
             if values.len() < n as usize {
                 return false;
@@ -114,210 +189,16 @@ impl Map {
             false
         }
 
-        let mut steps = 0_u32;
-
-        'find: loop {
-            for robot in &self.robots {
-                robot_positions[robot.position.y as usize].push(robot.position.x as u8);
-            }
-
-            for positions in &mut robot_positions {
-                if has_contiguous_run(31, positions) {
-                    break 'find;
-                }
-            }
-
-            steps += 1;
-            self.step(1);
-            for positions in &mut robot_positions {
-                positions.clear();
-            }
+        for positions in robot_positions.iter_mut() {
+            positions.clear();
         }
-
-        steps
-    }
-
-    fn viz2_find_easter_egg(&mut self) -> Option<u32> {
-        let mut stdout = io::stdout();
-        execute!(stdout, terminal::EnterAlternateScreen, cursor::Hide).unwrap();
-        terminal::enable_raw_mode().unwrap();
-
-        let answer = self._viz2_find_easter_egg_impl(&mut stdout);
-
-        execute!(stdout, terminal::LeaveAlternateScreen, cursor::Show).unwrap();
-        terminal::disable_raw_mode().unwrap();
-
-        answer.unwrap()
-    }
-
-    fn _viz2_find_easter_egg_impl(&mut self, stdout: &mut io::Stdout) -> Result<Option<u32>> {
-        let mut steps = 7344;
-        let mut inbuf = [0, 0, 0, 0];
-        let mut n_read;
-
-        let mut guards = vec![0_u64; (self.width * (self.height + 1) / 2) as usize];
-
-        self.step(steps);
-        self._viz2_ee_draw(stdout, steps, &mut guards)?;
-
-        loop {
-            n_read = io::stdin().read(&mut inbuf)?;
-
-            match (n_read, inbuf[0]) {
-                // Escape | ^C | ^D | q | Q
-                (1, 0x1b | 0x03 | 0x04 | b'q' | b'Q') => break Ok(None),
-                // \r
-                (1, 0x0d) => break Ok(Some(steps)),
-                // Space
-                (1, b' ') => {
-                    steps += 1;
-                    self.step(1);
-                    self._viz2_ee_draw(stdout, steps, &mut guards)?;
-                }
-                // Right Arrow
-                (3, 0x1b) if inbuf[1] == 0x5b && inbuf[2] == 0x43 => {
-                    steps += 1;
-                    self.step(1);
-                    self._viz2_ee_draw(stdout, steps, &mut guards)?;
-                }
-                // n
-                (1, b'n') => {
-                    steps += 100;
-                    self.step(100);
-                    self._viz2_ee_draw(stdout, steps, &mut guards)?;
-                }
-                // N
-                (1, b'N') => {
-                    steps += 1000;
-                    self.step(1000);
-                    self._viz2_ee_draw(stdout, steps, &mut guards)?;
-                }
-                // Backspace
-                (1, 0x7f) => {
-                    if steps > 0 {
-                        steps -= 1;
-                        self.step_rev(1);
-                    }
-                    self._viz2_ee_draw(stdout, steps, &mut guards)?;
-                }
-                // Left Arrow
-                (3, 0x1b) if inbuf[1] == 0x5b && inbuf[2] == 0x44 => {
-                    if steps > 0 {
-                        steps -= 1;
-                        self.step_rev(1);
-                    }
-                    self._viz2_ee_draw(stdout, steps, &mut guards)?;
-                }
-                // b
-                (1, b'b') => {
-                    if steps > 100 {
-                        steps -= 100;
-                        self.step_rev(100);
-                    }
-                    self._viz2_ee_draw(stdout, steps, &mut guards)?;
-                }
-                // B
-                (1, b'B') => {
-                    if steps > 1000 {
-                        steps -= 1000;
-                        self.step_rev(1000);
-                    }
-                    self._viz2_ee_draw(stdout, steps, &mut guards)?;
-                }
-                _ => {}
-            }
-        }
-    }
-
-    fn _viz2_ee_draw(
-        &mut self,
-        stdout: &mut io::Stdout,
-        steps: u32,
-        robot_map: &mut [u64],
-    ) -> Result<()> {
-        let size = terminal::size()?;
-
-        let move_to_steps = {
-            let steps = steps.to_string();
-            let steps_width = steps.len().min(u16::MAX as usize).max(4);
-            cursor::MoveTo(size.0 - steps_width as u16, 0)
-        };
-        execute!(
-            stdout,
-            move_to_steps,
-            style::Print(steps),
-            terminal::Clear(terminal::ClearType::UntilNewLine)
-        )?;
-
-        const BORDER_TL: char = '╭';
-        const BORDER_TR: char = '╮';
-        const BORDER_BR: char = '╯';
-        const BORDER_BL: char = '╰';
-        const BORDER_VERT: char = '│';
-        const BORDER_HOR: char = '─';
-
-        queue!(stdout, cursor::MoveTo(0, 0), style::Print(BORDER_TL))?;
-        for _ in 0..self.width {
-            queue!(stdout, style::Print(BORDER_HOR))?;
-        }
-        queue!(stdout, style::Print(BORDER_TR))?;
-
-        for _ in 0..(self.height + 1) / 2 {
-            queue!(
-                stdout,
-                cursor::MoveToNextLine(1),
-                style::Print(BORDER_VERT),
-                cursor::MoveRight(self.width as u16),
-                style::Print(BORDER_VERT)
-            )?;
-        }
-
-        queue!(stdout, cursor::MoveToNextLine(1), style::Print(BORDER_BL))?;
-        for _ in 0..self.width {
-            queue!(stdout, style::Print(BORDER_HOR))?;
-        }
-        queue!(stdout, style::Print(BORDER_BR))?;
-
-        const LOWER_FILLED: u64 = u32::MAX as u64;
-        const UPPER_FILLED: u64 = (u32::MAX as u64) << 32;
-        const BOTH_FILLED: u64 = LOWER_FILLED | UPPER_FILLED;
-
-        robot_map.fill(0);
-
         for robot in &self.robots {
-            let pos = robot.position;
-            let map_y = pos.y / 2;
-            let i = map_y * self.width + pos.x;
-            robot_map[i as usize] |= if pos.y % 2 == 0 {
-                LOWER_FILLED
-            } else {
-                UPPER_FILLED
-            };
-        }
-
-        for (i, cell) in robot_map.iter().copied().enumerate() {
-            let c = if cell == LOWER_FILLED {
-                '▀'
-            } else if cell == UPPER_FILLED {
-                '▄'
-            } else if cell == BOTH_FILLED {
-                '█'
-            } else {
-                ' '
-            };
-            queue!(
-                stdout,
-                cursor::MoveTo(
-                    (i % self.width as usize) as u16 + 1,
-                    (i / self.width as usize) as u16 + 1,
-                ),
-                style::Print(c),
-            )?;
+            robot_positions[robot.position.y as usize].push(robot.position.x as u8);
         }
 
-        stdout.flush()?;
-
-        Ok(())
+        robot_positions
+            .iter_mut()
+            .any(|positions| has_contiguous_run(31, positions))
     }
 }
 
@@ -347,6 +228,105 @@ impl fmt::Display for Map {
     }
 }
 
+/// Watch the robots move to find the step where they arrange into a picture of a Christmas tree.
+struct EasterEggViz {
+    map: Map,
+    step: u64,
+    /// The steps where the robots are most bunched together, best first.
+    candidates: Vec<u64>,
+}
+
+impl EasterEggViz {
+    /// How many candidates are bookmarked.
+    const CANDIDATES: usize = 5;
+
+    fn new(map: Map) -> Self {
+        let candidates = map.easter_egg_candidates(Self::CANDIDATES);
+        Self {
+            map,
+            step: 0,
+            candidates,
+        }
+    }
+
+    /// The rank of the current step among the candidates, counting from 1.
+    fn rank(&self) -> Option<usize> {
+        let cycle = (self.map.width * self.map.height) as u64;
+        self.candidates
+            .iter()
+            .position(|&step| step == self.step % cycle)
+            .map(|i| i + 1)
+    }
+}
+
+impl crate::viz::Visualizer for EasterEggViz {
+    fn step(&mut self) -> bool {
+        self.map.step(1);
+        self.step += 1;
+        true
+    }
+
+    fn step_back(&mut self) -> bool {
+        self.map.step_rev(1);
+        self.step -= 1;
+        true
+    }
+
+    fn step_n(&mut self, n: u64) -> u64 {
+        let n = n.min(u32::MAX as u64);
+        self.map.step(n as u32);
+        self.step += n;
+        n
+    }
+
+    fn is_solved(&self) -> bool {
+        self.rank() == Some(1)
+    }
+
+    fn status(&self) -> Vec<(&'static str, String)> {
+        match self.rank() {
+            Some(rank) => vec![("Candidate", format!("#{rank} of {}", self.candidates.len()))],
+            None => Vec::new(),
+        }
+    }
+
+    /// Draw two rows of the map per line with half blocks.
+    fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let (width, height) = (self.map.width as usize, self.map.height as usize);
+
+        let mut filled = vec![false; width * height];
+        for robot in &self.map.robots {
+            filled[robot.position.y as usize * width + robot.position.x as usize] = true;
+        }
+
+        let text = (0..height)
+            .step_by(2)
+            .map(|y| {
+                (0..width)
+                    .map(|x| {
+                        let upper = filled[y * width + x];
+                        let lower = y + 1 < height && filled[(y + 1) * width + x];
+                        match (upper, lower) {
+                            (true, true) => '█',
+                            (true, false) => '▀',
+                            (false, true) => '▄',
+                            (false, false) => ' ',
+                        }
+                    })
+                    .collect::<String>()
+            })
+            .map(Line::from)
+            .collect::<Text>();
+
+        let block = Block::bordered().border_type(BorderType::Rounded);
+        frame.render_widget(Paragraph::new(text).block(block), area);
+    }
+
+    fn answer(&self, step: u64) -> Option<crate::answer::Answer> {
+        Some(step.into())
+    }
+}
+
 type Vec2 = Vector2<i64>;
 
 struct Robot {
@@ -378,7 +358,7 @@ impl Robot {
     }
 }
 
-fn part1(input: &str) -> Option<Box<dyn std::fmt::Display>> {
+fn part1(input: &str) -> Option<crate::answer::Answer> {
     let mut map = Map::parse(input);
     // println!("{map}");
 
@@ -387,29 +367,49 @@ fn part1(input: &str) -> Option<Box<dyn std::fmt::Display>> {
 
     let safety_factor = map.calculate_safety_factor();
 
-    Some(Box::new(safety_factor))
+    Some(safety_factor.into())
 }
 
-fn part2(input: &str) -> Option<Box<dyn std::fmt::Display>> {
+fn part2(input: &str) -> Option<crate::answer::Answer> {
     let mut map = Map::parse(input);
 
-    let steps = map.find_easter_egg();
-
     // NOTE: The conditions for this solution (31 contiguous robots in a row)
     //       was found with the part 2 visualizer.
-    assert_eq!(7344, steps);
+    let steps = map.find_easter_egg();
 
-    Some(Box::new(steps))
+    Some(steps.into())
 }
 
-fn part2_viz(input: &str) -> Option<Box<dyn std::fmt::Display>> {
-    let mut map = Map::parse(input);
-    let steps = map.viz2_find_easter_egg();
-    steps.map(|s| Box::new(s) as Box<dyn std::fmt::Display>)
+fn part2_variance(input: &str) -> Option<crate::answer::Answer> {
+    let map = Map::parse(input);
+
+    let steps = *map.easter_egg_candidates(1).first()?;
+
+    Some(steps.into())
+}
+
+fn part2_viz(
+    input: &str,
+    ctx: &crate::viz::VizContext,
+) -> anyhow::Result<Option<crate::answer::Answer>> {
+    let viz = EasterEggViz::new(Map::parse(input));
+
+    // Start at the best candidate, unless asked to start somewhere else.
+    let ctx = crate::viz::VizContext {
+        start_step: ctx.start_step.or(viz.candidates.first().copied()),
+        ..ctx.clone()
+    };
+    let bookmarks = viz.candidates.clone();
+
+    crate::viz::Driver::new(viz, &ctx)
+        .with_bookmarks(bookmarks)
+        .run()
 }
 
 #[cfg(test)]
 mod tests {
+    use super::{Map, Robot};
+
     const SIZE: i64 = 20;
 
     const CASES_NEG: &[(i64, i64)] = &[
@@ -459,4 +459,70 @@ mod tests {
             assert_eq!(expected, adjust_mod(x), "mod: {x}");
         }
     }
+
+    #[test]
+    fn ranks_the_bunched_step_first() {
+        const STEP: i64 = 40;
+        let (width, height) = (11, 7);
+
+        // Robots that meet in a 2x2 square after `STEP` steps, having started out scattered.
+        let robots = (0..40)
+            .map(|i| {
+                let velocity = ((i * 3) % width - 5, (i * 5) % height - 3);
+                let end = (5 + i % 2, 3 + (i / 2) % 2);
+                let start = (
+                    (end.0 - STEP * velocity.0).rem_euclid(width),
+                    (end.1 - STEP * velocity.1).rem_euclid(height),
+                );
+                Robot::new(start, velocity)
+            })
+            .collect();
+        let map = Map {
+            width,
+            height,
+            robots,
+        };
+
+        let candidates = map.easter_egg_candidates(3);
+        assert_eq!(3, candidates.len());
+        assert_eq!(STEP as u64, candidates[0]);
+    }
+
+    #[test]
+    fn part2_variance_agrees_with_part2() {
+        const STEP: i64 = 1234;
+        let (width, height) = (101, 103);
+
+        // A deterministic stand-in for random numbers.
+        let mut seed = 1_u64;
+        let mut random = |n: i64| {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 33) as i64 % n
+        };
+
+        // A filled 31x11 box after `STEP` steps, among robots scattered all over the map.
+        let picture = (30..61).flat_map(|x| (40..51).map(move |y| (x, y)));
+        let scattered = (0..150)
+            .map(|_| (random(width), random(height)))
+            .collect::<Vec<_>>();
+        let input = picture
+            .chain(scattered)
+            .map(|end| {
+                let velocity = (1 + random(width - 1), 1 + random(height - 1));
+                let start = (
+                    (end.0 - STEP * velocity.0).rem_euclid(width),
+                    (end.1 - STEP * velocity.1).rem_euclid(height),
+                );
+                format!(
+                    "p={},{} v={},{}\n",
+                    start.0, start.1, velocity.0, velocity.1
+                )
+            })
+            .collect::<String>();
+
+        assert_eq!(Some((STEP as u32).into()), super::part2(&input));
+        assert_eq!(super::part2(&input), super::part2_variance(&input));
+    }
 }
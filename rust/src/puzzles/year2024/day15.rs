@@ -1,14 +1,25 @@
 use std::{
+    collections::VecDeque,
     fmt::{self, Write},
     ops,
 };
 
 use crossterm::style::Stylize;
 
-inventory::submit!(crate::days::DayModule::new(2024, 15).with_executors(
-    crate::day_part_executors![part1],
-    crate::day_part_executors![part2],
-));
+use crate::viz::{
+    grid::{GridPuzzle, GridViz, Overlay},
+    scene::Rgba,
+};
+
+inventory::submit!(
+    crate::days::DayModule::new(2024, 15)
+        .with_executors(
+            crate::day_part_executors![part1],
+            crate::day_part_executors![part2],
+        )
+        .with_pt1_visualizer(part1_viz)
+        .with_pt2_visualizer(part2_viz)
+);
 
 fn parse_v1(input: &str) -> (Map, Vec<Instruction>, Pos) {
     let width = input.lines().next().unwrap().trim().len();
@@ -81,6 +92,7 @@ fn read_line() {
     std::io::stdin().read_line(&mut String::new()).unwrap();
 }
 
+#[derive(Clone)]
 struct Map {
     width: usize,
     warehouse: Vec<Cell>,
@@ -114,27 +126,32 @@ impl Map {
 
     fn run_robot(&mut self, instructions: &[Instruction], mut robot: Pos) {
         for ins in instructions.iter().copied() {
-            let probe_start = robot.move_(ins);
-            let mut probe = probe_start;
+            robot = self.move_robot(ins, robot);
+        }
+    }
 
-            match self[probe] {
-                Cell::Wall => {}
-                Cell::Empty => robot = probe,
-                Cell::Box(_) => loop {
-                    probe = probe.move_(ins);
-                    match self[probe] {
-                        Cell::Wall => break,
-                        Cell::Box(_) => {}
-                        Cell::Empty => {
-                            let i = self.index(probe_start);
-                            let j = self.index(probe);
-                            self.warehouse.swap(i, j);
-                            robot = probe_start;
-                            break;
-                        }
+    /// Follow one instruction, returning where the robot ends up.
+    #[inline(always)]
+    fn move_robot(&mut self, ins: Instruction, robot: Pos) -> Pos {
+        let probe_start = robot.move_(ins);
+        let mut probe = probe_start;
+
+        match self[probe] {
+            Cell::Wall => robot,
+            Cell::Empty => probe,
+            Cell::Box(_) => loop {
+                probe = probe.move_(ins);
+                match self[probe] {
+                    Cell::Wall => break robot,
+                    Cell::Box(_) => {}
+                    Cell::Empty => {
+                        let i = self.index(probe_start);
+                        let j = self.index(probe);
+                        self.warehouse.swap(i, j);
+                        break probe_start;
                     }
-                },
-            }
+                }
+            },
         }
     }
 
@@ -178,28 +195,42 @@ impl Map {
         let mut pushtree_swap_stack = Vec::<(Pos, Pos)>::new();
 
         for ins in instructions.iter().copied() {
-            let probe = robot.move_(ins);
-            match self[probe] {
-                Cell::Wall => {}
-                Cell::Empty => robot = probe,
-                Cell::Box(boxt) => {
-                    if let Some(next) = match ins {
-                        Instruction::East | Instruction::West => {
-                            self.move_boxes_wide_ew(probe, ins)
-                        }
-                        Instruction::North | Instruction::South => self.move_boxes_wide_ns(
-                            probe,
-                            ins,
-                            boxt,
-                            &mut pushtree_gen,
-                            &mut pushtree_next_gen,
-                            &mut pushtree_swap_stack,
-                        ),
-                    } {
-                        robot = next;
-                    }
-                }
+            robot = self.move_robot_wide(
+                ins,
+                robot,
+                &mut pushtree_gen,
+                &mut pushtree_next_gen,
+                &mut pushtree_swap_stack,
+            );
+        }
+    }
+
+    /// Follow one instruction in the wide warehouse, returning where the robot ends up.
+    #[inline(always)]
+    fn move_robot_wide(
+        &mut self,
+        ins: Instruction,
+        robot: Pos,
+        pushtree_gen: &mut Vec<Pos>,
+        pushtree_next_gen: &mut Vec<Pos>,
+        pushtree_swap_stack: &mut Vec<(Pos, Pos)>,
+    ) -> Pos {
+        let probe = robot.move_(ins);
+        match self[probe] {
+            Cell::Wall => robot,
+            Cell::Empty => probe,
+            Cell::Box(boxt) => match ins {
+                Instruction::East | Instruction::West => self.move_boxes_wide_ew(probe, ins),
+                Instruction::North | Instruction::South => self.move_boxes_wide_ns(
+                    probe,
+                    ins,
+                    boxt,
+                    pushtree_gen,
+                    pushtree_next_gen,
+                    pushtree_swap_stack,
+                ),
             }
+            .unwrap_or(robot),
         }
     }
 
@@ -444,13 +475,7 @@ enum Cell {
 
 impl fmt::Display for Cell {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match *self {
-            Self::Wall => f.write_char('#'),
-            Self::BOX_SINGLE => f.write_char('O'),
-            Self::BOX_LEFT => f.write_char('['),
-            Self::BOX_RIGHT => f.write_char(']'),
-            Self::Empty => f.write_char('.'),
-        }
+        f.write_char(self.symbol() as char)
     }
 }
 
@@ -458,6 +483,16 @@ impl Cell {
     const BOX_SINGLE: Self = Self::Box(BoxType::Single);
     const BOX_LEFT: Self = Self::Box(BoxType::Left);
     const BOX_RIGHT: Self = Self::Box(BoxType::Right);
+
+    fn symbol(self) -> u8 {
+        match self {
+            Self::Wall => b'#',
+            Self::BOX_SINGLE => b'O',
+            Self::BOX_LEFT => b'[',
+            Self::BOX_RIGHT => b']',
+            Self::Empty => b'.',
+        }
+    }
 }
 
 #[derive(Clone, Copy, PartialEq, Eq)]
@@ -467,22 +502,240 @@ enum BoxType {
     Right,
 }
 
-fn part1(input: &str) -> Option<Box<dyn std::fmt::Display>> {
+fn part1(input: &str) -> Option<crate::answer::Answer> {
     let (mut map, instructions, robot) = parse_v1(input);
 
     map.run_robot(&instructions, robot);
 
     let answer = map.sum_box_coords();
 
-    Some(Box::new(answer))
+    Some(answer.into())
 }
 
-fn part2(input: &str) -> Option<Box<dyn std::fmt::Display>> {
+fn part2(input: &str) -> Option<crate::answer::Answer> {
     let (mut map, instructions, robot) = parse_v2(input);
 
     map.run_robot_wide(&instructions, robot);
 
     let answer = map.sum_box_coords();
 
-    Some(Box::new(answer))
+    Some(answer.into())
+}
+
+/// How many of the robot's last positions to draw as its trail.
+const TRAIL_LEN: usize = 64;
+
+/// Follow one instruction per step.
+#[derive(Clone)]
+struct RobotViz {
+    map: Map,
+    wide: bool,
+    instructions: Vec<Instruction>,
+    robot: Pos,
+    /// The robot's last positions, oldest first.
+    trail: VecDeque<Pos>,
+    /// The indexes of the cells that changed on the last step.
+    moved: Vec<usize>,
+    done: usize,
+    pushtree_gen: Vec<Pos>,
+    pushtree_next_gen: Vec<Pos>,
+    pushtree_swap_stack: Vec<(Pos, Pos)>,
+}
+
+impl RobotViz {
+    fn new(input: &str, wide: bool) -> Self {
+        let (map, instructions, robot) = if wide {
+            parse_v2(input)
+        } else {
+            parse_v1(input)
+        };
+        Self {
+            map,
+            wide,
+            instructions,
+            robot,
+            trail: VecDeque::from([robot]),
+            moved: Vec::new(),
+            done: 0,
+            pushtree_gen: Vec::new(),
+            pushtree_next_gen: Vec::new(),
+            pushtree_swap_stack: Vec::new(),
+        }
+    }
+}
+
+impl GridPuzzle for RobotViz {
+    fn step(&mut self) -> bool {
+        let Some(&ins) = self.instructions.get(self.done) else {
+            return false;
+        };
+
+        let before = self.map.warehouse.clone();
+        self.robot = if self.wide {
+            self.map.move_robot_wide(
+                ins,
+                self.robot,
+                &mut self.pushtree_gen,
+                &mut self.pushtree_next_gen,
+                &mut self.pushtree_swap_stack,
+            )
+        } else {
+            self.map.move_robot(ins, self.robot)
+        };
+        self.done += 1;
+
+        self.moved.clear();
+        self.moved.extend(
+            before
+                .iter()
+                .zip(&self.map.warehouse)
+                .enumerate()
+                .filter(|(_, (before, after))| before != after)
+                .map(|(i, _)| i),
+        );
+
+        if self.trail.back() != Some(&self.robot) {
+            if self.trail.len() == TRAIL_LEN {
+                self.trail.pop_front();
+            }
+            self.trail.push_back(self.robot);
+        }
+
+        true
+    }
+
+    fn is_solved(&self) -> bool {
+        self.done == self.instructions.len()
+    }
+
+    fn size(&self) -> (usize, usize) {
+        (self.map.warehouse.len() / self.map.width, self.map.width)
+    }
+
+    fn cell(&self, row: usize, col: usize) -> u8 {
+        let pos = Pos::new(col, row);
+        if pos == self.robot {
+            b'@'
+        } else {
+            self.map[pos].symbol()
+        }
+    }
+
+    fn cell_color(&self, cell: u8) -> Option<Rgba> {
+        match cell {
+            b'#' => Some(Rgba::rgb(110, 110, 110)),
+            b'O' | b'[' | b']' => Some(Rgba::rgb(200, 140, 60)),
+            b'@' => Some(Rgba::rgb(255, 80, 80)),
+            _ => None,
+        }
+    }
+
+    fn overlays(&self) -> Vec<Overlay> {
+        let cell = |pos: &Pos| (pos.y as usize, pos.x as usize);
+        vec![
+            Overlay::path(
+                "trail",
+                Rgba::rgb(80, 160, 255).with_alpha(0.6),
+                self.trail.iter().map(cell).collect(),
+            ),
+            Overlay::fill(
+                "moved",
+                Rgba::rgb(255, 200, 0).with_alpha(0.4),
+                self.moved
+                    .iter()
+                    .map(|&i| cell(&Pos::from_index(i, self.map.width)))
+                    .collect(),
+            ),
+            Overlay::highlight("robot", Rgba::rgb(255, 80, 80), vec![cell(&self.robot)]),
+        ]
+    }
+
+    fn inspect(&self, row: usize, col: usize) -> Option<String> {
+        matches!(
+            self.map[Pos::new(col, row)],
+            Cell::Box(BoxType::Single | BoxType::Left)
+        )
+        .then(|| format!("GPS {}", Pos::new(col, row).gps_coord()))
+    }
+
+    fn status(&self) -> Vec<(&'static str, String)> {
+        vec![
+            (
+                "Instruction",
+                format!("{}/{}", self.done, self.instructions.len()),
+            ),
+            (
+                "Next",
+                self.instructions
+                    .get(self.done)
+                    .map_or_else(|| "-".to_string(), Instruction::to_string),
+            ),
+            ("GPS sum", self.map.sum_box_coords().to_string()),
+        ]
+    }
+
+    fn answer(&self) -> Option<crate::answer::Answer> {
+        self.is_solved().then(|| self.map.sum_box_coords().into())
+    }
+}
+
+fn part1_viz(
+    input: &str,
+    ctx: &crate::viz::VizContext,
+) -> anyhow::Result<Option<crate::answer::Answer>> {
+    crate::viz::Driver::new(GridViz::new(RobotViz::new(input, false)), ctx)
+        .with_rewind()
+        .run()
+}
+
+fn part2_viz(
+    input: &str,
+    ctx: &crate::viz::VizContext,
+) -> anyhow::Result<Option<crate::answer::Answer>> {
+    crate::viz::Driver::new(GridViz::new(RobotViz::new(input, true)), ctx)
+        .with_rewind()
+        .run()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::viz::{
+        grid::GridViz,
+        headless::{self, Size},
+    };
+
+    use super::RobotViz;
+
+    const EXAMPLE: &str = "\
+##########
+#..O..O.O#
+#......O.#
+#.OO..O.O#
+#..O@..O.#
+#O#..O...#
+#O..O..O.#
+#.OO.O.OO#
+#....O...#
+##########
+
+<vv>^<v^>v>^vv^v>v<>v^v<v<^vv<<<^><<><>>v<vvv<>^v^>^<<<><<v<<<v^vv^v>^
+vvv<<^>^v^^><<>>><>^<<><^vv^^<>vvv<>><^^v>^>vv<>v<<<<v<^v>^<^^>>>^<v<v
+><>vv>v^v^<>><>>>><^^>vv>v<^^^>>v^v^<^^>v^^>v^<^v>v<>>v^v^<v>v^^<^^vv<
+<<v<^>>^^^^>>>v^<>vvv^><v<<<>^^^vv^<vvv>^>v<^^^^v<>^>vvvv><>>v^<<^^^^^
+^><^><>>><>^^<<^^v>>><^<v>^<vv>>v>>>^v><>^v><<<<v>>v<v<v>vvv>^<><<>^><
+^>><>^v<><^vvv<^^<><v<<<<<><^v<<<><<<^^<v<^^^><^>>^<v^><<<^>>^v<v^v<v^
+>^>>^v>vv>^<<^v<>><<><<v<<v><>v<^vv<<<>^^v^>^^>>><<^v>>v^v><^^>>^<>vv^
+<><^^>^^^<><vvvvv^v<v<<>^v<v>v<<^><<><<><<<^^<<<^<<>><<><^^^>^^<>^>v<>
+^^>vv<^v^v<vv>^<><v<^v>^^^>>>^^vvv^>vvv<>>>^<^>>>>>^<<^v>^vvv<>^<><<v>
+v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^
+";
+
+    #[test]
+    fn sums_the_box_coordinates() {
+        for (wide, expected) in [(false, 10092_u64), (true, 9021)] {
+            let viz = GridViz::new(RobotViz::new(EXAMPLE, wide));
+            let (answer, _) = headless::run_to_end(viz, Size::default());
+            assert_eq!(Some(expected.into()), answer);
+        }
+    }
 }
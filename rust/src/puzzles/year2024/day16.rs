@@ -1,16 +1,7 @@
 use std::{
     array, cmp,
     collections::{BinaryHeap, HashMap, HashSet},
-    fmt,
-    io::{self, Read, Write},
-    mem, ops,
-};
-
-use anyhow::{Result, bail};
-use crossterm::{
-    cursor, queue,
-    style::{self, Stylize},
-    terminal,
+    fmt, mem, ops,
 };
 
 inventory::submit!(
@@ -211,272 +202,6 @@ impl Maze<'_> {
         None
     }
 
-    fn viz_solve_astar(&self) -> Option<usize> {
-        viz::run(|stdout| self._viz_solve_astar_impl(stdout))
-    }
-
-    fn _viz_solve_astar_impl(&self, stdout: &mut io::Stdout) -> Result<Option<usize>> {
-        // Algorithm setup
-
-        let start = Node::new(self.start_pos, Direction::default());
-
-        let mut open_set = MinHeap::<ScoredNode>::new();
-        open_set.push(ScoredNode::new(start));
-
-        let mut preceding = HashMap::<Node, Node>::new();
-
-        let mut g_scores = HashMap::<Node, u64>::new();
-        g_scores.insert(start, 0);
-
-        // Viz loop
-
-        let mut logs = Vec::<String>::new();
-        let mut steps = 0_u32;
-        for _ in 0..steps {
-            self._viz_solve_astar_step(
-                &mut logs,
-                steps,
-                &mut open_set,
-                &mut preceding,
-                &mut g_scores,
-            );
-        }
-
-        self._viz_solve_astar_draw(
-            stdout,
-            &logs,
-            steps,
-            &preceding,
-            open_set.0.as_slice(),
-            None,
-        )?;
-
-        let mut inbuf = [0, 0, 0, 0];
-        let mut n_read: usize;
-
-        loop {
-            n_read = match io::stdin().read(&mut inbuf) {
-                Ok(n) => n,
-                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
-                Err(err) => bail!(err),
-            };
-
-            match (n_read, inbuf[0]) {
-                // Escape | ^C | ^D | q | Q
-                (1, 0x1b | 0x03 | 0x04 | b'q' | b'Q') => break,
-                // \r | Space
-                (1, 0x0d | b' ') if !open_set.is_empty() => {
-                    steps += 1;
-                    let solution = self._viz_solve_astar_step(
-                        &mut logs,
-                        steps,
-                        &mut open_set,
-                        &mut preceding,
-                        &mut g_scores,
-                    );
-                    self._viz_solve_astar_draw(
-                        stdout,
-                        &logs,
-                        steps,
-                        &preceding,
-                        open_set.0.as_slice(),
-                        solution,
-                    )?;
-                }
-                _ => {}
-            }
-        }
-
-        Ok(None)
-    }
-
-    fn _viz_solve_astar_step(
-        &self,
-        _logs: &mut Vec<String>,
-        _step: u32,
-        open_set: &mut MinHeap<ScoredNode>,
-        preceding: &mut HashMap<Node, Node>,
-        g_scores: &mut HashMap<Node, u64>,
-    ) -> Option<Node> {
-        if let Some(current) = open_set.pop() {
-            if current.node.pos == self.end_pos {
-                _logs.push(format!(
-                    "{_step:4}| {} cost={}",
-                    "SOLVED".bold().green(),
-                    current.f_score.to_string().bold().green()
-                ));
-                open_set.clear();
-                return Some(current.node);
-            }
-
-            let mut next = current;
-
-            loop {
-                let probe_pos = next.forward_pos();
-                if self[probe_pos] == b'#' {
-                    break;
-                }
-                next.node.pos = probe_pos;
-                next.f_score += 1;
-                if self[probe_pos.orthogonal1_to(current.node.dir)] != b'#'
-                    || self[probe_pos.orthogonal2_to(current.node.dir)] != b'#'
-                {
-                    break;
-                }
-            }
-
-            if next.node.pos != current.node.pos {
-                let tentative_g_score = g_scores
-                    .get(&current.node)
-                    .map(|s| s + next.f_score - current.f_score)
-                    .unwrap_or(u64::MAX);
-                let next_g_score = *g_scores.get(&next.node).unwrap_or(&u64::MAX);
-                if tentative_g_score < next_g_score {
-                    preceding.insert(next.node, current.node);
-                    g_scores.insert(next.node, tentative_g_score);
-                    let f_score =
-                        tentative_g_score + next.node.pos.manhattan_distance(self.end_pos);
-                    if !open_set.contains(next.node) {
-                        next.f_score = f_score;
-                        open_set.push(next);
-                    }
-                }
-            }
-
-            let mut next = current.rotate_cw();
-            let next_forward_pos = next.forward_pos();
-
-            if self[next_forward_pos] != b'#' {
-                let tentative_g_score = g_scores
-                    .get(&current.node)
-                    .map(|s| s + 1000)
-                    .unwrap_or(u64::MAX);
-                let next_g_score = *g_scores.get(&next.node).unwrap_or(&u64::MAX);
-                if tentative_g_score < next_g_score {
-                    preceding.insert(next.node, current.node);
-                    g_scores.insert(next.node, tentative_g_score);
-                    let f_score =
-                        tentative_g_score + next.node.pos.manhattan_distance(self.end_pos);
-                    if !open_set.contains(next.node) {
-                        next.f_score = f_score;
-                        open_set.push(next);
-                    }
-                }
-            }
-
-            let mut next = current.rotate_ccw();
-            let next_forward_pos = next.forward_pos();
-
-            if self[next_forward_pos] != b'#' {
-                let tentative_g_score = g_scores
-                    .get(&current.node)
-                    .map(|s| s + 1000)
-                    .unwrap_or(u64::MAX);
-                let next_g_score = *g_scores.get(&next.node).unwrap_or(&u64::MAX);
-                if tentative_g_score < next_g_score {
-                    preceding.insert(next.node, current.node);
-                    g_scores.insert(next.node, tentative_g_score);
-                    let f_score =
-                        tentative_g_score + next.node.pos.manhattan_distance(self.end_pos);
-                    if !open_set.contains(next.node) {
-                        next.f_score = f_score;
-                        open_set.push(next);
-                    }
-                }
-            }
-
-            // fn g_scores_display(g_scores: &HashMap<Node, u64>) -> String {
-            //     let mut out = String::new();
-            //     for (i, (&node, &g)) in g_scores.iter().enumerate() {
-            //         if i > 0 {
-            //             out.push_str(SEP);
-            //         }
-            //         out.push_str(&format!("{},{} : {}", node.pos, node.dir, g));
-            //     }
-            //     out
-            // }
-            // _logs.push(format!("{_step:4}| open:   {open_set}"));
-            // _logs.push(format!("{_step:4}| gScore: {}", g_scores_display(g_scores)));
-        }
-
-        None
-    }
-
-    fn _viz_solve_astar_draw(
-        &self,
-        stdout: &mut io::Stdout,
-        logs: &[String],
-        step: u32,
-        preceding: &HashMap<Node, Node>,
-        open_set: &[ScoredNode],
-        solution: Option<Node>,
-    ) -> Result<()> {
-        let (term_width, _term_height) = terminal::size()?;
-
-        // Maze
-
-        viz::draw_maze(stdout, &self.rows)?;
-
-        // Open Set
-
-        queue!(stdout, cursor::SavePosition)?;
-
-        viz::draw_open_set(stdout, open_set)?;
-
-        queue!(stdout, cursor::RestorePosition)?;
-
-        // Solution Path
-
-        if let Some(end) = solution {
-            let path = "@".green();
-            let path_cmd = style::PrintStyledContent(path);
-
-            let mut next = end;
-
-            while let Some(x) = preceding.get(&next).copied() {
-                queue!(
-                    stdout,
-                    cursor::MoveTo(x.pos.col as u16, x.pos.row as u16),
-                    path_cmd
-                )?;
-                next = x;
-            }
-
-            queue!(stdout, cursor::RestorePosition)?;
-        }
-
-        // Status
-
-        queue!(
-            stdout,
-            cursor::MoveToNextLine(1),
-            style::Print("Step: "),
-            style::Print(step),
-        )?;
-
-        queue!(
-            stdout,
-            cursor::MoveToNextLine(1),
-            style::Print("Next: "),
-            terminal::Clear(terminal::ClearType::UntilNewLine),
-        )?;
-        if let Some(next) = open_set.first() {
-            queue!(stdout, style::Print(next.node.pos))?;
-        }
-
-        // Logs
-
-        queue!(
-            stdout,
-            cursor::MoveToNextLine(2),
-            style::Print("━".repeat(term_width as usize)),
-        )?;
-
-        viz::draw_logs(stdout, logs)?;
-
-        Ok(())
-    }
-
     fn width(&self) -> usize {
         self.rows[0].len()
     }
@@ -645,256 +370,6 @@ impl Maze<'_> {
 
         Some(solution_path_node_positions.len() as u64)
     }
-
-    #[allow(dead_code, reason = "WIP")]
-    fn viz_solve_dijkstras(&self) -> Option<u64> {
-        viz::run(|stdout| self._viz_solve_dijkstras_impl(stdout))
-    }
-
-    fn _viz_solve_dijkstras_impl(&self, stdout: &mut io::Stdout) -> Result<Option<u64>> {
-        // Algorithm setup
-
-        let start = Node::new(self.start_pos, Direction::default());
-
-        let mut open_set = MinHeap::<ScoredNode>::new();
-        open_set.push(ScoredNode::new(start));
-
-        let mut dist = GridVec::new(self.width(), self.height(), [u64::MAX; 4]);
-
-        let mut preceding = GridVec::new(self.width(), self.height(), array::from_fn(|_| vec![]));
-
-        // Viz loop
-
-        let mut solution = None;
-
-        let mut logs = Vec::<String>::new();
-        let mut steps = 932393; // XXX: real input: end pos first discovered
-        for _ in 0..steps {
-            let current_solution = self._viz_solve_dijkstras_step(
-                &mut logs,
-                steps,
-                &mut open_set,
-                &mut dist,
-                &mut preceding,
-            );
-            solution = solution.or(current_solution);
-        }
-
-        self._viz_solve_dijkstras_draw(
-            stdout,
-            &mut logs,
-            steps,
-            &preceding,
-            open_set.0.as_slice(),
-            solution,
-        )?;
-
-        let mut inbuf = [0, 0, 0, 0];
-        let mut n_read: usize;
-
-        loop {
-            n_read = match io::stdin().read(&mut inbuf) {
-                Ok(n) => n,
-                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
-                Err(err) => bail!(err),
-            };
-
-            match (n_read, inbuf[0]) {
-                // Escape | ^C | ^D | q | Q
-                (1, 0x1b | 0x03 | 0x04 | b'q' | b'Q') => break,
-                // \r | Space
-                (1, 0x0d | b' ') if !open_set.is_empty() => {
-                    steps += 1;
-                    let current_solution = self._viz_solve_dijkstras_step(
-                        &mut logs,
-                        steps,
-                        &mut open_set,
-                        &mut dist,
-                        &mut preceding,
-                    );
-                    solution = solution.or(current_solution);
-                    self._viz_solve_dijkstras_draw(
-                        stdout,
-                        &mut logs,
-                        steps,
-                        &preceding,
-                        open_set.0.as_slice(),
-                        solution,
-                    )?;
-                }
-                _ => {}
-            }
-        }
-
-        Ok(solution.map(|n| n.f_score))
-    }
-
-    fn _viz_solve_dijkstras_step(
-        &self,
-        _logs: &mut Vec<String>,
-        _step: u32,
-        open_set: &mut MinHeap<ScoredNode>,
-        dist: &mut GridVec<[u64; 4]>,
-        preceding: &mut GridVec<[Vec<Node>; 4]>,
-    ) -> Option<ScoredNode> {
-        if let Some(current) = open_set.pop() {
-            if current.node.pos == self.end_pos {
-                return Some(current);
-            }
-
-            {
-                let next = current.forward();
-                let next_dist = dist[next.node];
-
-                if self[next.node.pos] != b'#' && next.f_score <= next_dist {
-                    preceding[next.node].push(current.node);
-                    _logs.push(format!(
-                        "{} {} -> {}",
-                        current.node.pos, current.node.dir, next.node.pos
-                    ));
-                    dist[next.node] = next.f_score;
-                    open_set.push(next);
-                }
-            }
-
-            {
-                let next = current.rotate_cw();
-                let next_forward_pos = next.forward_pos();
-                let next_dist = dist[next.node];
-
-                if self[next_forward_pos] != b'#' && next.f_score <= next_dist {
-                    preceding[next.node].push(current.node);
-                    _logs.push(format!(
-                        "{} {} -> {}",
-                        current.node.pos, current.node.dir, next.node.dir
-                    ));
-                    dist[next.node] = next.f_score;
-                    open_set.push(next);
-                }
-            }
-
-            {
-                let next = current.rotate_ccw();
-                let next_forward_pos = next.forward_pos();
-                let next_dist = dist[next.node];
-
-                if self[next_forward_pos] != b'#' && next.f_score <= next_dist {
-                    preceding[next.node].push(current.node);
-                    _logs.push(format!(
-                        "{} {} -> {}",
-                        current.node.pos, current.node.dir, next.node.dir
-                    ));
-                    dist[next.node] = next.f_score;
-                    open_set.push(next);
-                }
-            }
-        }
-
-        None
-    }
-
-    fn _viz_solve_dijkstras_draw(
-        &self,
-        stdout: &mut io::Stdout,
-        logs: &mut Vec<String>,
-        step: u32,
-        preceding: &GridVec<[Vec<Node>; 4]>,
-        open_set: &[ScoredNode],
-        solution: Option<ScoredNode>,
-    ) -> Result<()> {
-        let (term_width, _term_height) = terminal::size()?;
-
-        // Maze
-
-        viz::draw_maze(stdout, &self.rows)?;
-
-        // Open Set
-
-        queue!(stdout, cursor::SavePosition)?;
-
-        viz::draw_open_set(stdout, open_set)?;
-
-        queue!(stdout, cursor::RestorePosition)?;
-
-        // Solution Path
-
-        if let Some(end) = solution {
-            let path = "@".green();
-            let path_cmd = style::PrintStyledContent(path);
-
-            let mut solution_path_nodes = HashSet::new();
-            solution_path_nodes.insert(end.node);
-
-            let mut node_gen = vec![end.node];
-            let mut next_node_gen = vec![];
-
-            loop {
-                if node_gen.is_empty() {
-                    break;
-                }
-                while let Some(node) = node_gen.pop() {
-                    for &prev in &*preceding[node] {
-                        if solution_path_nodes.insert(prev) && prev.pos != self.start_pos {
-                            next_node_gen.push(prev);
-                        }
-                    }
-                }
-                mem::swap(&mut node_gen, &mut next_node_gen);
-            }
-
-            let solution_path_node_positions = solution_path_nodes
-                .iter()
-                .map(|n| n.pos)
-                .collect::<HashSet<_>>();
-            logs.push(format!(
-                "Solution path has {} nodes",
-                solution_path_node_positions.len()
-            ));
-
-            for node in solution_path_nodes {
-                queue!(
-                    stdout,
-                    cursor::MoveTo(node.pos.col as u16, node.pos.row as u16),
-                    path_cmd
-                )?;
-            }
-
-            stdout.flush()?;
-
-            queue!(stdout, cursor::RestorePosition)?;
-        }
-
-        // Status
-
-        queue!(
-            stdout,
-            cursor::MoveToNextLine(1),
-            style::Print("Step: "),
-            style::Print(step),
-        )?;
-
-        queue!(
-            stdout,
-            cursor::MoveToNextLine(1),
-            style::Print("Next: "),
-            terminal::Clear(terminal::ClearType::UntilNewLine),
-        )?;
-        if let Some(next) = open_set.first() {
-            queue!(stdout, style::Print(next.node.pos))?;
-        }
-
-        // Logs
-
-        queue!(
-            stdout,
-            cursor::MoveToNextLine(2),
-            style::Print("━".repeat(term_width as usize)),
-        )?;
-
-        viz::draw_logs(stdout, logs)?;
-
-        Ok(())
-    }
 }
 
 #[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -1117,6 +592,7 @@ impl fmt::Debug for ScoredNode {
     }
 }
 
+#[derive(Clone)]
 struct MinHeap<T>(BinaryHeap<T>);
 
 impl<T> ops::Deref for MinHeap<T> {
@@ -1184,6 +660,7 @@ impl MinHeap<ScoredNode> {
 //     }
 // }
 
+#[derive(Clone)]
 struct GridVec<T> {
     width: usize,
     grid: Vec<T>,
@@ -1240,37 +717,40 @@ impl<T> ops::IndexMut<ScoredNode> for GridVec<[T; 4]> {
     }
 }
 
-fn part1(input: &str) -> Option<Box<dyn std::fmt::Display>> {
+fn part1(input: &str) -> Option<crate::answer::Answer> {
     let maze = parse(input);
 
     let cost = maze.solve_astar();
 
-    cost.map(|c| Box::new(c) as Box<dyn std::fmt::Display>)
+    cost.map(crate::answer::Answer::from)
 }
 
-fn part1_viz(input: &str) -> Option<Box<dyn std::fmt::Display>> {
+fn part1_viz(
+    input: &str,
+    ctx: &crate::viz::VizContext,
+) -> anyhow::Result<Option<crate::answer::Answer>> {
     let maze = parse(input);
 
-    let cost = maze.viz_solve_astar();
-
-    cost.map(|c| Box::new(c) as Box<dyn std::fmt::Display>)
+    crate::viz::Driver::new(viz::Astar::new(&maze), ctx)
+        .with_rewind()
+        .run()
 }
 
-fn part2(input: &str) -> Option<Box<dyn std::fmt::Display>> {
+fn part2(input: &str) -> Option<crate::answer::Answer> {
     let maze = parse(input);
 
     let cost = maze.solve_dijkstras();
 
-    cost.map(|c| Box::new(c) as Box<dyn std::fmt::Display>)
+    cost.map(crate::answer::Answer::from)
 }
 
-fn part2_viz(input: &str) -> Option<Box<dyn std::fmt::Display>> {
+fn part2_viz(
+    input: &str,
+    ctx: &crate::viz::VizContext,
+) -> anyhow::Result<Option<crate::answer::Answer>> {
     let maze = parse(input);
 
-    // let cost = maze.viz_solve_dijkstras();
-    // cost.map(|c| Box::new(c) as Box<dyn std::fmt::Display>)
-
-    viz::run_dijkstras(&maze);
-
-    None
+    crate::viz::Driver::new(viz::Dijkstras::new(&maze), ctx)
+        .with_rewind()
+        .run()
 }
@@ -1,132 +1,261 @@
-use std::io::{self, Write};
+use std::collections::HashMap;
 
-use anyhow::Result;
-use crossterm::{
-    cursor, execute, queue,
-    style::{self, Stylize},
-    terminal,
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::Stylize,
+    text::{Line, Span, Text},
+    widgets::Paragraph,
 };
 
+use crate::viz::{Logs, Visualizer};
+
+use super::{Direction, Maze, MinHeap, Node, ScoredNode};
+
 mod dijkstras;
 
-pub(super) use dijkstras::run as run_dijkstras;
+pub(super) use dijkstras::Dijkstras;
 
 pub(super) const SEP: &str = " / ";
 
-pub(super) fn run<F, R>(imp: F) -> R
-where
-    F: FnOnce(&mut io::Stdout) -> Result<R>,
-{
-    let mut stdout = io::stdout();
-    execute!(stdout, terminal::EnterAlternateScreen, cursor::Hide).unwrap();
-    terminal::enable_raw_mode().unwrap();
+fn maze_cell(byte: u8) -> Span<'static> {
+    match byte {
+        b'#' => "█".gray(),
+        b'.' => " ".into(),
+        b'S' => "S".bold().yellow(),
+        b'E' => "E".bold().yellow(),
+        _ => unreachable!(),
+    }
+}
 
-    let result = imp(&mut stdout);
+fn maze_cells(maze: &Maze) -> Vec<Vec<Span<'static>>> {
+    maze.rows
+        .iter()
+        .map(|row| row.bytes().map(maze_cell).collect())
+        .collect()
+}
 
-    execute!(stdout, terminal::LeaveAlternateScreen, cursor::Show).unwrap();
-    terminal::disable_raw_mode().unwrap();
+fn direction_arrow(dir: Direction) -> &'static str {
+    match dir {
+        Direction::North => "^",
+        Direction::East => ">",
+        Direction::South => "v",
+        Direction::West => "<",
+    }
+}
 
-    result.unwrap()
+/// Step through the A* search of part 1.
+#[derive(Clone)]
+pub(super) struct Astar<'maze> {
+    maze: &'maze Maze<'maze>,
+    open_set: MinHeap<ScoredNode>,
+    preceding: HashMap<Node, Node>,
+    g_scores: HashMap<Node, u64>,
+    solution: Option<ScoredNode>,
+    logs: Logs,
 }
 
-pub(super) fn draw_maze(stdout: &mut io::Stdout, rows: &[&str]) -> Result<()> {
-    queue!(stdout, cursor::MoveTo(0, 0))?;
-
-    for &row in rows {
-        queue!(stdout, terminal::Clear(terminal::ClearType::CurrentLine))?;
-        for b in row.bytes() {
-            let content = match b {
-                b'#' => "█".grey(),
-                b'.' => " ".stylize(),
-                b'S' => "S".bold().yellow(),
-                b'E' => "E".bold().yellow(),
-                _ => unreachable!(),
-            };
-            queue!(stdout, style::PrintStyledContent(content))?;
+impl<'maze> Astar<'maze> {
+    pub(super) fn new<'m: 'maze>(maze: &'m Maze<'m>) -> Self {
+        let start = Node::new(maze.start_pos, Direction::default());
+
+        let mut open_set = MinHeap::<ScoredNode>::new();
+        open_set.push(ScoredNode::new(start));
+
+        let mut g_scores = HashMap::<Node, u64>::new();
+        g_scores.insert(start, 0);
+
+        Self {
+            maze,
+            open_set,
+            preceding: HashMap::new(),
+            g_scores,
+            solution: None,
+            logs: Logs::default(),
         }
-        queue!(stdout, cursor::MoveToNextLine(1))?;
     }
-
-    Ok(())
 }
 
-pub(super) fn draw_open_set(stdout: &mut io::Stdout, open_set: &[super::ScoredNode]) -> Result<()> {
-    for (i, ns) in open_set.iter().enumerate() {
-        queue!(
-            stdout,
-            cursor::MoveTo(ns.node.pos.col as u16, ns.node.pos.row as u16),
-        )?;
-        let mut content = match ns.node.dir {
-            super::Direction::North => "^",
-            super::Direction::East => ">",
-            super::Direction::South => "v",
-            super::Direction::West => "<",
+impl Visualizer for Astar<'_> {
+    fn step(&mut self) -> bool {
+        let Some(current) = self.open_set.pop() else {
+            return false;
+        };
+
+        if current.node.pos == self.maze.end_pos {
+            self.logs.push(format!("SOLVED cost={}", current.f_score));
+            self.open_set.clear();
+            self.solution = Some(current);
+            return true;
         }
-        .yellow();
-        if i == 0 {
-            content = content.on_yellow().black();
+
+        let mut next = current;
+
+        loop {
+            let probe_pos = next.forward_pos();
+            if self.maze[probe_pos] == b'#' {
+                break;
+            }
+            next.node.pos = probe_pos;
+            next.f_score += 1;
+            if self.maze[probe_pos.orthogonal1_to(current.node.dir)] != b'#'
+                || self.maze[probe_pos.orthogonal2_to(current.node.dir)] != b'#'
+            {
+                break;
+            }
         }
-        queue!(stdout, style::PrintStyledContent(content))?;
-    }
 
-    Ok(())
-}
+        if next.node.pos != current.node.pos {
+            let tentative_g_score = self
+                .g_scores
+                .get(&current.node)
+                .map(|s| s + next.f_score - current.f_score)
+                .unwrap_or(u64::MAX);
+            self.relax(current, next, tentative_g_score);
+        }
 
-pub(super) fn draw_logs(stdout: &mut io::Stdout, logs: &[String]) -> Result<()> {
-    stdout.flush()?;
+        for next in [current.rotate_cw(), current.rotate_ccw()] {
+            if self.maze[next.forward_pos()] != b'#' {
+                let tentative_g_score = self
+                    .g_scores
+                    .get(&current.node)
+                    .map(|s| s + 1000)
+                    .unwrap_or(u64::MAX);
+                self.relax(current, next, tentative_g_score);
+            }
+        }
 
-    queue!(stdout, terminal::Clear(terminal::ClearType::FromCursorDown))?;
+        // fn g_scores_display(g_scores: &HashMap<Node, u64>) -> String {
+        //     let mut out = String::new();
+        //     for (i, (&node, &g)) in g_scores.iter().enumerate() {
+        //         if i > 0 {
+        //             out.push_str(SEP);
+        //         }
+        //         out.push_str(&format!("{},{} : {}", node.pos, node.dir, g));
+        //     }
+        //     out
+        // }
+        // self.logs.push(format!("open:   {}", self.open_set));
+        // self.logs.push(format!("gScore: {}", g_scores_display(&self.g_scores)));
 
-    let (_cur_col, cur_row) = cursor::position()?;
+        true
+    }
 
-    let (term_width, term_height) = terminal::size()?;
-    let rows_remaining = term_height.saturating_sub(cur_row + 1) as usize;
+    fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let mut cells = maze_cells(self.maze);
 
-    let log_chunks = logs
-        .iter()
-        .rev()
-        .flat_map(|line| {
-            let Some(i) = line.find(SEP) else {
-                return vec![line.as_str()].into_iter().rev();
+        // Draw the open set in reverse so the next node to visit is on top.
+        for (i, sn) in self.open_set.as_slice().iter().enumerate().rev() {
+            let arrow = direction_arrow(sn.node.dir);
+            cells[sn.node.pos.row][sn.node.pos.col] = if i == 0 {
+                arrow.black().on_yellow()
+            } else {
+                arrow.yellow()
             };
+        }
 
-            let mut chunk_start = 0;
-            let mut chunk_end = i;
-            let mut chunks = vec![];
-
-            while chunk_end < line.len() {
-                match line[chunk_end + SEP.len()..].find(SEP) {
-                    Some(i) => {
-                        if chunk_end - chunk_start + SEP.len() + i <= term_width as usize {
-                            chunk_end += SEP.len() + i;
-                        } else {
-                            chunks.push(&line[chunk_start..chunk_end]);
-                            chunk_start = chunk_end;
-                            chunk_end += SEP.len() + i;
-                        }
-                    }
-                    None => {
-                        if line.len() - chunk_start <= term_width as usize {
-                            chunks.push(&line[chunk_start..]);
-                        } else {
-                            chunks.push(&line[chunk_start..chunk_end]);
-                            chunks.push(&line[chunk_end..]);
-                        }
-                        break;
-                    }
-                }
+        if let Some(end) = self.solution {
+            let mut next = end.node;
+            while let Some(&prev) = self.preceding.get(&next) {
+                cells[prev.pos.row][prev.pos.col] = "@".green();
+                next = prev;
             }
+        }
+
+        let text = cells.into_iter().map(Line::from).collect::<Text>();
+        frame.render_widget(Paragraph::new(text), area);
+    }
+
+    fn is_solved(&self) -> bool {
+        self.solution.is_some()
+    }
+
+    fn status(&self) -> Vec<(&'static str, String)> {
+        let next = self
+            .open_set
+            .peek()
+            .map(|sn| sn.node.pos.to_string())
+            .unwrap_or_default();
+        vec![("Next", next), ("Open", self.open_set.len().to_string())]
+    }
 
-            chunks.into_iter().rev()
-        })
-        .take(rows_remaining)
-        .collect::<Vec<_>>();
+    fn logs(&self) -> Option<&Logs> {
+        Some(&self.logs)
+    }
 
-    for chunk in log_chunks.into_iter().rev() {
-        queue!(stdout, cursor::MoveToNextLine(1), style::Print(chunk))?;
+    fn answer(&self, _step: u64) -> Option<crate::answer::Answer> {
+        self.solution.map(|sn| sn.f_score.into())
     }
+}
 
-    stdout.flush()?;
+impl Astar<'_> {
+    fn relax(&mut self, current: ScoredNode, mut next: ScoredNode, tentative_g_score: u64) {
+        let next_g_score = *self.g_scores.get(&next.node).unwrap_or(&u64::MAX);
+        if tentative_g_score < next_g_score {
+            self.preceding.insert(next.node, current.node);
+            self.g_scores.insert(next.node, tentative_g_score);
+            let f_score = tentative_g_score + next.node.pos.manhattan_distance(self.maze.end_pos);
+            if !self.open_set.contains(next.node) {
+                next.f_score = f_score;
+                self.open_set.push(next);
+            }
+        }
+    }
+}
 
-    Ok(())
+#[cfg(test)]
+mod tests {
+    use crate::viz::headless;
+
+    const EXAMPLE: &str = "\
+###############
+#.......#....E#
+#.#.###.#.###.#
+#.....#.#...#.#
+#.###.#####.#.#
+#.#.#.......#.#
+#.#.#####.###.#
+#...........#.#
+###.#.#####.#.#
+#...#.....#.#.#
+#.#.#.###.#.#.#
+#.....#...#.#.#
+#.###.#.#.#.#.#
+#S..#.....#...#
+###############
+";
+
+    const SOLVED_FRAME: &str = r"███████████████
+█       █    E█
+█ █ ███ █ ███ █
+█     █ █   █ █
+█ ███ █████ █ █
+█ █ █       █ █
+█ █ █████ ███ █
+█    @   @ @█ █
+███ █ █████ █ █
+█   █@    █ █ █
+█ █ █ ███ █ █ █
+█@ @ @█   █ █ █
+█ ███ █ █ █ █ █
+█@  █     █@ @█
+███████████████
+┌ Logs ──────────────────────────────────────────┐
+│SOLVED cost=7036                                │
+│                                                │
+│                                                │
+└────────────────────────────────────────────────┘
+ ────────────────────────────────────────────● 107
+ Step 107  finished  Next:   Open: 0  ? help
+";
+
+    #[test]
+    fn astar_solved() {
+        let maze = super::super::parse(EXAMPLE);
+        let (answer, frame) =
+            headless::run_to_end(super::Astar::new(&maze), "50x22".parse().unwrap());
+
+        assert_eq!(Some(7036_u64.into()), answer);
+        assert_eq!(SOLVED_FRAME, headless::frame_text(&frame));
+    }
 }
@@ -1,36 +1,30 @@
-use std::{
-    collections::{HashSet, VecDeque},
-    fmt::Write,
-    time::{Duration, Instant},
-};
+use std::{collections::HashSet, fmt::Write};
 
-use anyhow::Result;
 use crossterm::event;
 use ratatui::{
-    DefaultTerminal, Frame, layout,
+    Frame, layout,
     style::{Style, Stylize},
     symbols, text, widgets,
 };
 
-use crate::puzzles::year2024::day16 as day;
-
-pub fn run(maze: &super::super::Maze) {
-    let result = {
-        let mut terminal = ratatui::init();
-        App::new(maze).run(&mut terminal)
-    };
-
-    ratatui::restore();
+use crate::{
+    puzzles::year2024::day16 as day,
+    viz::{Logs, Visualizer},
+};
 
-    result.unwrap()
-}
+const HELP: &[(&str, &str)] = &[
+    ("h j k l", "move the maze cursor, shift for 10"),
+    ("↑ ↓", "move the maze cursor"),
+    ("s e", "move the maze cursor to the start / end"),
+];
 
-struct App<'maze> {
+/// Step through the Dijkstra's search of part 2, which finds every tile on a best path.
+#[derive(Clone)]
+pub(in crate::puzzles::year2024::day16) struct Dijkstras<'maze> {
     // Maze
     maze: &'maze day::Maze<'maze>,
     // Solution algorithm state metadata
-    steps: u32,
-    logs: VecDeque<String>,
+    logs: Logs,
     // Solution algorithm state
     discovered: day::GridVec<bool>,
     open_set: day::MinHeap<day::ScoredNode>,
@@ -41,12 +35,10 @@ struct App<'maze> {
     // UI state
     maze_table_state: widgets::TableState,
     maze_scroll_state: widgets::ScrollbarState,
-    logs_scroll_offset: usize,
-    logs_scroll_state: widgets::ScrollbarState,
 }
 
-impl<'maze> App<'maze> {
-    fn new<'m: 'maze>(maze: &'m day::Maze<'m>) -> Self {
+impl<'maze> Dijkstras<'maze> {
+    pub(in crate::puzzles::year2024::day16) fn new<'m: 'maze>(maze: &'m day::Maze<'m>) -> Self {
         let selected = Some(maze.start_pos.into());
 
         let start = day::Node::new(maze.start_pos, day::Direction::default());
@@ -58,10 +50,9 @@ impl<'maze> App<'maze> {
 
         let preceding = day::GridVec::new(maze.width(), maze.height(), Default::default());
 
-        let mut this = Self {
+        Self {
             maze,
-            steps: 0,
-            logs: VecDeque::new(),
+            logs: Logs::default(),
             discovered: day::GridVec::new(maze.width(), maze.height(), false),
             open_set,
             dist,
@@ -74,156 +65,59 @@ impl<'maze> App<'maze> {
                 state.last();
                 state
             },
-            logs_scroll_offset: 0,
-            logs_scroll_state: widgets::ScrollbarState::default(),
-        };
-
-        // this.steps = 215; //413; // XXX: test1: before end pos discovered
-        // this.steps = 285; // XXX: test2: before end pos discovered
-        // this.steps = 279; // XXX: test3: before end pos discovered
-        // this.steps = 932393; // XXX: input ??: before end pos discovered
-        // this.steps = 24074; // XXX: input2: before end pos discovered
-        this.steps = 15983; // XXX: input2: before end pos discovered (with backtracking prevention)
-        this.steps = 3000;
-
-        for i in 1..=this.steps {
-            let current_solution = this.step();
-            match current_solution {
-                Some(solution) if this.solution.is_none() => this.log(format!(
-                    "Found solution during startup: step={} cost={}",
-                    i, solution.f_score
-                )),
-                _ => {}
-            }
-            this.solution = this.solution.or(current_solution);
         }
-
-        this
     }
 
     fn log(&mut self, log: impl Into<String>) {
-        while self.logs.len() >= 4096 {
-            self.logs.pop_front();
-        }
-        self.logs.push_back(log.into());
+        self.logs.push(log);
     }
 
-    fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<()> {
-        let tick_rate = Duration::from_millis(250);
-        let mut last_tick = Instant::now();
-
-        terminal.draw(|frame| self.draw(frame))?;
-
-        loop {
-            let timeout = tick_rate.saturating_sub(last_tick.elapsed());
-            if event::poll(timeout)? {
-                let evt = event::read()?;
-                if let event::Event::Key(key) = evt {
-                    match key.code {
-                        // Quit
-                        event::KeyCode::Char('q') => break,
-                        // Step algorithm
-                        event::KeyCode::Char(' ') => {
-                            self.steps += 1;
-                            let current_solution = self.step();
-                            match current_solution {
-                                Some(solution) if self.solution.is_none() => {
-                                    self.log(format!("SOLVED {}", solution.f_score));
-                                    self.solve_path(solution);
-                                }
-                                _ => {}
-                            }
-                            self.solution = self.solution.or(current_solution);
-                        }
-
-                        //
-                        // Cursor
-                        //
-
-                        // Down
-                        event::KeyCode::Down => self.scroll_maze_down(1),
-                        event::KeyCode::Char('j') => self.scroll_maze_down(1),
-                        event::KeyCode::Char('J') => self.scroll_maze_down(10),
-                        // Up
-                        event::KeyCode::Up => self.scroll_maze_up(1),
-                        event::KeyCode::Char('k') => self.scroll_maze_up(1),
-                        event::KeyCode::Char('K') => self.scroll_maze_up(10),
-                        // Left
-                        event::KeyCode::Left => self.scroll_maze_left(1),
-                        event::KeyCode::Char('h') => self.scroll_maze_left(1),
-                        event::KeyCode::Char('H') => self.scroll_maze_left(10),
-                        // Right
-                        event::KeyCode::Right => self.scroll_maze_right(1),
-                        event::KeyCode::Char('l') => self.scroll_maze_right(1),
-                        event::KeyCode::Char('L') => self.scroll_maze_right(10),
-                        // Goto start
-                        event::KeyCode::Char('s') => {
-                            self.maze_table_state
-                                .select_cell(Some(self.maze.start_pos.into()));
-                            self.maze_scroll_state.last();
-                        }
-                        // Goto end
-                        event::KeyCode::Char('e') => {
-                            self.maze_table_state
-                                .select_cell(Some(self.maze.end_pos.into()));
-                            self.maze_scroll_state.first();
-                        }
-
-                        //
-                        // Logs
-                        //
-
-                        // Down
-                        event::KeyCode::PageDown => {
-                            let amount = if key.modifiers.intersects(event::KeyModifiers::SHIFT) {
-                                10
-                            } else {
-                                1
-                            };
-                            self.scroll_logs_down(amount);
-                        }
-                        // Up
-                        event::KeyCode::PageUp => {
-                            let amount = if key.modifiers.intersects(event::KeyModifiers::SHIFT) {
-                                10
-                            } else {
-                                1
-                            };
-                            self.scroll_logs_up(amount);
-                        }
-                        // Start
-                        event::KeyCode::Home => {
-                            self.logs_scroll_offset = 0;
-                            self.logs_scroll_state = self.logs_scroll_state.position(0);
-                        }
-                        // End
-                        event::KeyCode::End => {
-                            let offset = self.logs.len().saturating_sub(1);
-                            self.logs_scroll_offset = offset;
-                            self.logs_scroll_state = self.logs_scroll_state.position(offset);
-                        }
-                        _ => {}
-                    }
+    /// Move the maze cursor, returning `false` if `event` isn't a cursor movement.
+    fn handle_cursor_event(&mut self, event: &event::Event) -> bool {
+        if let event::Event::Key(key) = event {
+            match key.code {
+                // Down
+                event::KeyCode::Down => self.scroll_maze_down(1),
+                event::KeyCode::Char('j') => self.scroll_maze_down(1),
+                event::KeyCode::Char('J') => self.scroll_maze_down(10),
+                // Up
+                event::KeyCode::Up => self.scroll_maze_up(1),
+                event::KeyCode::Char('k') => self.scroll_maze_up(1),
+                event::KeyCode::Char('K') => self.scroll_maze_up(10),
+                // Left and right arrows are left to the driver for stepping.
+                // Left
+                event::KeyCode::Char('h') => self.scroll_maze_left(1),
+                event::KeyCode::Char('H') => self.scroll_maze_left(10),
+                // Right
+                event::KeyCode::Char('l') => self.scroll_maze_right(1),
+                event::KeyCode::Char('L') => self.scroll_maze_right(10),
+                // Goto start
+                event::KeyCode::Char('s') => {
+                    self.maze_table_state
+                        .select_cell(Some(self.maze.start_pos.into()));
+                    self.maze_scroll_state.last();
                 }
-                if let event::Event::Mouse(mouse) = evt {
-                    match mouse.kind {
-                        event::MouseEventKind::ScrollDown => self.scroll_maze_down(1),
-                        event::MouseEventKind::ScrollUp => self.scroll_maze_up(1),
-                        event::MouseEventKind::ScrollLeft => self.scroll_maze_left(1),
-                        event::MouseEventKind::ScrollRight => self.scroll_maze_right(1),
-                        _ => {}
-                    }
+                // Goto end
+                event::KeyCode::Char('e') => {
+                    self.maze_table_state
+                        .select_cell(Some(self.maze.end_pos.into()));
+                    self.maze_scroll_state.first();
                 }
+                _ => return false,
             }
-
-            terminal.draw(|frame| self.draw(frame))?;
-
-            if last_tick.elapsed() >= tick_rate {
-                last_tick = Instant::now();
+            return true;
+        }
+        if let event::Event::Mouse(mouse) = event {
+            match mouse.kind {
+                event::MouseEventKind::ScrollDown => self.scroll_maze_down(1),
+                event::MouseEventKind::ScrollUp => self.scroll_maze_up(1),
+                event::MouseEventKind::ScrollLeft => self.scroll_maze_left(1),
+                event::MouseEventKind::ScrollRight => self.scroll_maze_right(1),
+                _ => return false,
             }
+            return true;
         }
-
-        Ok(())
+        false
     }
 
     fn scroll_maze_up(&mut self, amount: u32) {
@@ -252,19 +146,7 @@ impl<'maze> App<'maze> {
         }
     }
 
-    fn scroll_logs_up(&mut self, amount: usize) {
-        let offset = self.logs_scroll_offset.saturating_sub(amount);
-        self.logs_scroll_offset = offset;
-        self.logs_scroll_state = self.logs_scroll_state.position(offset);
-    }
-
-    fn scroll_logs_down(&mut self, amount: usize) {
-        let offset = self.logs_scroll_offset.saturating_add(amount);
-        self.logs_scroll_offset = offset;
-        self.logs_scroll_state = self.logs_scroll_state.position(offset);
-    }
-
-    fn step(&mut self) -> Option<day::ScoredNode> {
+    fn step_dijkstras(&mut self) -> Option<day::ScoredNode> {
         if let Some(current) = self.open_set.pop() {
             if current.node.pos == self.maze.end_pos {
                 return Some(current);
@@ -483,12 +365,12 @@ impl<'maze> App<'maze> {
         ));
     }
 
-    fn draw(&mut self, frame: &mut Frame) {
+    fn draw(&mut self, frame: &mut Frame, area: layout::Rect) {
         // Layout
 
         let [mut maze_area, debug_area] =
             layout::Layout::horizontal([layout::Constraint::Fill(3), layout::Constraint::Fill(2)])
-                .areas(frame.area());
+                .areas(area);
 
         // Maze
 
@@ -539,7 +421,6 @@ impl<'maze> App<'maze> {
 
         let cursor_pos: day::Pos = self.maze_table_state.selected_cell().unwrap().into();
         let mut debug_info_values = vec![
-            ("Step".to_owned(), self.steps.to_string()),
             ("Open Set Count".to_owned(), self.open_set.len().to_string()),
             ("Cursor".to_owned(), cursor_pos.to_string()),
         ];
@@ -559,43 +440,11 @@ impl<'maze> App<'maze> {
             debug_info_values.push((format!("       Dist {dir}"), format!("{dist}")))
         }
 
-        let [debug_info_area, debug_lower_area] = layout::Layout::vertical([
-            layout::Constraint::Length(debug_info_values.len() as u16),
-            layout::Constraint::Fill(1),
-        ])
-        .spacing(1)
-        .areas(debug_inner_area);
-
         let debug_info = debug_info_values
             .iter()
             .map(|(label, value)| text::Line::from_iter([label, ": ", value]))
             .collect::<text::Text>();
-        frame.render_widget(widgets::Paragraph::new(debug_info), debug_info_area);
-
-        // Logs
-
-        let logs =
-            widgets::Paragraph::new(self.logs.iter().map(String::as_str).collect::<text::Text>())
-                .scroll((self.logs_scroll_offset as u16, 0))
-                .block(widgets::Block::bordered());
-        frame.render_widget(&logs, debug_lower_area);
-
-        // Logs scrollbar
-
-        let logs_scrollbar = widgets::Scrollbar::new(widgets::ScrollbarOrientation::VerticalRight)
-            .symbols(symbols::scrollbar::VERTICAL)
-            .begin_symbol(None)
-            .end_symbol(None);
-        self.logs_scroll_state = self.logs_scroll_state.content_length(self.logs.len());
-        // self.logs_scroll_state = self.logs_scroll_state.content_length(self.logs.len());
-        // let offset = self.logs_scroll_offset.min(4096);
-        // self.logs_scroll_offset = offset;
-        // self.logs_scroll_state = self.logs_scroll_state.position(offset);
-        frame.render_stateful_widget(
-            logs_scrollbar,
-            debug_lower_area.inner(layout::Margin::new(0, 1)),
-            &mut self.logs_scroll_state,
-        );
+        frame.render_widget(widgets::Paragraph::new(debug_info), debug_inner_area);
     }
 
     fn str_to_maze_row(&self, r: usize, maze_row: &str) -> widgets::Row<'static> {
@@ -608,18 +457,112 @@ impl<'maze> App<'maze> {
     }
 
     fn byte_to_maze_cell(&self, pos: day::Pos, byte: u8) -> text::Span<'static> {
-        match byte {
-            b'#' => "█".gray(),
-            b'.' => {
-                if self.solution_path.contains(&pos) {
-                    "@".green()
-                } else {
-                    " ".into()
-                }
+        if byte == b'.' && self.solution_path.contains(&pos) {
+            "@".green()
+        } else {
+            super::maze_cell(byte)
+        }
+    }
+}
+
+impl Visualizer for Dijkstras<'_> {
+    fn step(&mut self) -> bool {
+        if self.open_set.is_empty() {
+            return false;
+        }
+
+        let current_solution = self.step_dijkstras();
+        match current_solution {
+            Some(solution) if self.solution.is_none() => {
+                self.log(format!("SOLVED {}", solution.f_score));
+                self.solve_path(solution);
             }
-            b'S' => "S".bold().yellow(),
-            b'E' => "E".bold().yellow(),
-            _ => unreachable!(),
+            _ => {}
+        }
+        self.solution = self.solution.or(current_solution);
+
+        true
+    }
+
+    fn render(&mut self, frame: &mut Frame, area: layout::Rect) {
+        self.draw(frame, area);
+    }
+
+    fn is_solved(&self) -> bool {
+        self.solution.is_some()
+    }
+
+    fn logs(&self) -> Option<&Logs> {
+        Some(&self.logs)
+    }
+
+    fn help(&self) -> &'static [(&'static str, &'static str)] {
+        HELP
+    }
+
+    fn handle_event(&mut self, event: &event::Event) -> bool {
+        self.handle_cursor_event(event)
+    }
+
+    /// The number of tiles on a best path, counted by part 2's own search. The path drawn here
+    /// tracks predecessors per tile rather than per direction, so it can take in extra tiles.
+    fn answer(&self, _step: u64) -> Option<crate::answer::Answer> {
+        self.solution?;
+        self.maze.solve_dijkstras().map(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::viz::headless::{self, Size};
+
+    use super::Dijkstras;
+
+    const EXAMPLES: &[&str] = &[
+        "\
+###############
+#.......#....E#
+#.#.###.#.###.#
+#.....#.#...#.#
+#.###.#####.#.#
+#.#.#.......#.#
+#.#.#####.###.#
+#...........#.#
+###.#.#####.#.#
+#...#.....#.#.#
+#.#.#.###.#.#.#
+#.....#...#.#.#
+#.###.#.#.#.#.#
+#S..#.....#...#
+###############
+",
+        "\
+#################
+#...#...#...#..E#
+#.#.#.#.#.#.#.#.#
+#.#.#.#...#...#.#
+#.#.#.#.###.#.#.#
+#...#.#.#.....#.#
+#.#.#.#.#.#####.#
+#.#...#.#.#.....#
+#.#.#####.#.###.#
+#.#.#.......#...#
+#.#.###.#####.###
+#.#.#...#.....#.#
+#.#.#.#####.###.#
+#.#.#.........#.#
+#.#.#.#########.#
+#S#.............#
+#################
+",
+    ];
+
+    #[test]
+    fn counts_the_best_path_tiles() {
+        for example in EXAMPLES {
+            let maze = super::day::parse(example);
+            let (answer, _) = headless::run_to_end(Dijkstras::new(&maze), Size::default());
+            assert_eq!(super::day::part2(example), answer);
         }
     }
 }
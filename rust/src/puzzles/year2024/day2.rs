@@ -21,7 +21,7 @@ fn report_to_level_deltas(report: &str) -> impl Iterator<Item = LvlDelta> {
         })
 }
 
-fn part1(input: &str) -> Option<Box<dyn std::fmt::Display>> {
+fn part1(input: &str) -> Option<crate::answer::Answer> {
     let mut safe_count = 0;
 
     for mut level_deltas in input.lines().map(report_to_level_deltas) {
@@ -35,10 +35,10 @@ fn part1(input: &str) -> Option<Box<dyn std::fmt::Display>> {
         }
     }
 
-    Some(Box::new(safe_count))
+    Some(safe_count.into())
 }
 
-fn part2(input: &str) -> Option<Box<dyn std::fmt::Display>> {
+fn part2(input: &str) -> Option<crate::answer::Answer> {
     let mut safe_count = 0;
 
     'reports: for level_deltas in input.lines().map(report_to_level_deltas) {
@@ -61,5 +61,5 @@ fn part2(input: &str) -> Option<Box<dyn std::fmt::Display>> {
         safe_count += 1;
     }
 
-    Some(Box::new(safe_count))
+    Some(safe_count.into())
 }
@@ -5,7 +5,7 @@ inventory::submit!(crate::days::DayModule::new(2024, 3).with_executors(
     crate::day_part_executors![part2],
 ));
 
-fn part1(input: &str) -> Option<Box<dyn std::fmt::Display>> {
+fn part1(input: &str) -> Option<crate::answer::Answer> {
     let args_re = Regex::new(r"mul\((?<a>\d+),(?<b>\d+)\)").unwrap();
 
     let answer = args_re
@@ -17,10 +17,10 @@ fn part1(input: &str) -> Option<Box<dyn std::fmt::Display>> {
         })
         .sum::<u32>();
 
-    Some(Box::new(answer))
+    Some(answer.into())
 }
 
-fn part2(input: &str) -> Option<Box<dyn std::fmt::Display>> {
+fn part2(input: &str) -> Option<crate::answer::Answer> {
     let args_re =
         Regex::new(r"(?<do>do\(\))|(?<dont>don't\(\))|(mul\((?<a>\d+),(?<b>\d+)\))").unwrap();
 
@@ -41,5 +41,5 @@ fn part2(input: &str) -> Option<Box<dyn std::fmt::Display>> {
         }
     }
 
-    Some(Box::new(sum))
+    Some(sum.into())
 }
@@ -216,18 +216,18 @@ impl Index<Pos> for WordSearch<'_> {
     }
 }
 
-fn part1(input: &str) -> Option<Box<dyn std::fmt::Display>> {
+fn part1(input: &str) -> Option<crate::answer::Answer> {
     let word_search = WordSearch::new(input);
 
     let answer = word_search.count_xmas();
 
-    Some(Box::new(answer))
+    Some(answer.into())
 }
 
-fn part2(input: &str) -> Option<Box<dyn std::fmt::Display>> {
+fn part2(input: &str) -> Option<crate::answer::Answer> {
     let word_search = WordSearch::new(input);
 
     let answer = word_search.count_mas_x();
 
-    Some(Box::new(answer))
+    Some(answer.into())
 }
@@ -75,7 +75,7 @@ impl PrintRules {
     }
 }
 
-fn part1(input: &str) -> Option<Box<dyn std::fmt::Display>> {
+fn part1(input: &str) -> Option<crate::answer::Answer> {
     let (print_rules, updates) = parse(input);
 
     let answer = updates
@@ -84,10 +84,10 @@ fn part1(input: &str) -> Option<Box<dyn std::fmt::Display>> {
         .map(|update| update[update.len() / 2])
         .sum::<u32>();
 
-    Some(Box::new(answer))
+    Some(answer.into())
 }
 
-fn part2(input: &str) -> Option<Box<dyn std::fmt::Display>> {
+fn part2(input: &str) -> Option<crate::answer::Answer> {
     let (print_rules, mut updates) = parse(input);
 
     let answer = updates
@@ -105,5 +105,5 @@ fn part2(input: &str) -> Option<Box<dyn std::fmt::Display>> {
         })
         .sum::<u32>();
 
-    Some(Box::new(answer))
+    Some(answer.into())
 }
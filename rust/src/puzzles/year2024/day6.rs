@@ -4,6 +4,7 @@ use std::{
     ops::{Index, IndexMut},
 };
 
+mod viz;
 mod viz_gtk;
 
 inventory::submit!(
@@ -58,17 +59,6 @@ struct Map {
 }
 
 impl Map {
-    const fn empty() -> Self {
-        Self {
-            height: 0,
-            width: 0,
-            grid: Vec::new(),
-            _viz_obstacle: Pos::ZERO,
-            _viz_walk_path: Vec::new(),
-            _viz_probe_path: Vec::new(),
-        }
-    }
-
     #[allow(dead_code)]
     fn print(&self, cursor: Pos) {
         let suffix = "\x1b[0m";
@@ -294,7 +284,6 @@ impl Map {
         }
     }
 
-    #[allow(dead_code)]
     fn viz_walk_and_find_loop_candidates(
         &mut self,
         path: &mut HashSet<Cursor>,
@@ -321,6 +310,7 @@ impl Map {
 
             if self[next_obstacle.pos].is_obstacle() {
                 *cursor = cursor.rotate();
+                next_obstacle = cursor.forward();
             }
         }
 
@@ -360,7 +350,7 @@ impl Map {
                 loop_path.insert(probe);
                 self._viz_probe_path.push(probe);
                 continue;
-            } else if loop_path.contains(&probe) {
+            } else if loop_path.contains(&probe_next) {
                 self._viz_probe_path.push(probe);
                 return true;
             }
@@ -381,7 +371,6 @@ struct Pos {
 }
 
 impl Pos {
-    const ZERO: Self = Self { row: 0, col: 0 };
     const INVALID: Self = Self { row: -1, col: -1 };
 
     const fn new(row: usize, col: usize) -> Self {
@@ -472,11 +461,6 @@ impl From<Pos> for Cursor {
 }
 
 impl Cursor {
-    const DEFAULT: Self = Self {
-        pos: Pos::ZERO,
-        dir: Direction::North,
-    };
-
     fn new(pos: Pos, dir: Direction) -> Self {
         Self { pos, dir }
     }
@@ -561,38 +545,47 @@ impl fmt::Display for Direction {
     }
 }
 
-fn part1(input: &str) -> Option<Box<dyn std::fmt::Display>> {
+fn part1(input: &str) -> Option<crate::answer::Answer> {
     let (mut map, start_pos) = parse(input);
 
     map.walk_from(start_pos);
 
     let answer = map.grid.iter().filter(|&c| c.is_visited()).count();
 
-    Some(Box::new(answer))
+    Some(answer.into())
 }
 
-fn part2_brute(input: &str) -> Option<Box<dyn std::fmt::Display>> {
+fn part2_brute(input: &str) -> Option<crate::answer::Answer> {
     let (mut map, start_pos) = parse(input);
 
     map.walk_from(start_pos);
 
     let answer = map.walk_and_find_loop_candidates_brute(start_pos);
 
-    Some(Box::new(answer))
+    Some(answer.into())
 }
 
-fn part2_fast(input: &str) -> Option<Box<dyn std::fmt::Display>> {
+fn part2_fast(input: &str) -> Option<crate::answer::Answer> {
     let (mut map, cursor) = parse(input);
 
     let answer = map.walk_and_find_loop_candidates(cursor);
 
-    Some(Box::new(answer))
+    Some(answer.into())
 }
 
-fn part2_fast_viz(input: &str) -> Option<Box<dyn std::fmt::Display>> {
-    let (mut map, cursor) = parse(input);
+fn part2_fast_viz(
+    input: &str,
+    ctx: &crate::viz::VizContext,
+) -> anyhow::Result<Option<crate::answer::Answer>> {
+    let (map, cursor) = parse(input);
+
+    if crate::viz::use_driver(ctx)? {
+        return crate::viz::Driver::new(viz::LoopCandidates::new(map, cursor), ctx)
+            .with_rewind()
+            .run();
+    }
 
-    viz_gtk::viz_main(&mut map, cursor);
+    viz_gtk::viz_main(map, cursor)?;
 
-    None
+    Ok(None)
 }
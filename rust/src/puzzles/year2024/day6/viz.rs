@@ -0,0 +1,199 @@
+use std::collections::HashSet;
+
+use crate::{
+    answer::Answer,
+    viz::{
+        Visualizer,
+        scene::{Rgba, Scene, Shape},
+    },
+};
+
+use super::{Cell, Cursor, Map, Pos};
+
+const COLOR_MAP_BG: Rgba = Rgba::rgb(51, 51, 51);
+const COLOR_CELL_OBSTACLE: Rgba = Rgba::rgb(255, 0, 0);
+const COLOR_CELL_TARGET: Rgba = Rgba::rgb(255, 0, 255).with_alpha(0.7);
+const COLOR_CELL_CURSOR: Rgba = Rgba::WHITE;
+const COLOR_PATH: Rgba = Rgba::rgb(128, 128, 128);
+const COLOR_PATH_PROBE_FAILURE: Rgba = Rgba::rgb(204, 51, 51).with_alpha(0.3);
+const COLOR_PATH_PROBE_SUCCESS: Rgba = Rgba::rgb(51, 204, 51).with_alpha(0.3);
+
+const PATH_WIDTH: f64 = 0.8;
+
+/// Step along the guard's walk of part 2, probing for a loop with an obstacle in front of it at
+/// each step.
+#[derive(Clone)]
+pub(super) struct LoopCandidates {
+    map: Map,
+    cursor: Cursor,
+    path: HashSet<Cursor>,
+    walked: HashSet<Pos>,
+    probe_succeeded: bool,
+    candidates: HashSet<Pos>,
+}
+
+impl LoopCandidates {
+    pub(super) fn new(map: Map, cursor: Cursor) -> Self {
+        Self {
+            map,
+            cursor,
+            path: HashSet::new(),
+            walked: HashSet::from([cursor.pos]),
+            probe_succeeded: false,
+            candidates: HashSet::new(),
+        }
+    }
+}
+
+impl Visualizer for LoopCandidates {
+    fn step(&mut self) -> bool {
+        if !self.map.contains_cursor(self.cursor.forward()) {
+            return false;
+        }
+
+        self.probe_succeeded = self
+            .map
+            .viz_walk_and_find_loop_candidates(&mut self.path, &mut self.cursor);
+
+        // An obstacle can't go where the guard has already been, since it would have changed the
+        // walk up to here.
+        if self.probe_succeeded && !self.walked.contains(&self.map._viz_obstacle) {
+            self.candidates.insert(self.map._viz_obstacle);
+        }
+        self.walked.insert(self.cursor.pos);
+
+        true
+    }
+
+    fn scene(&self) -> Option<Scene> {
+        let map = &self.map;
+        let mut scene =
+            Scene::new(map.width as f64, map.height as f64).with_background(COLOR_MAP_BG);
+
+        let obstacles = map
+            .grid
+            .chunks(map.width)
+            .enumerate()
+            .flat_map(|(r, row)| {
+                row.iter()
+                    .enumerate()
+                    .filter(|(_, cell)| **cell == Cell::Obstacle)
+                    .map(move |(c, _)| (r, c))
+            })
+            .collect();
+        scene.layer("obstacles", COLOR_CELL_OBSTACLE, Shape::Cells(obstacles));
+
+        // The walk path is only extended at the start of the next step.
+        let mut walk = turns(&map._viz_walk_path);
+        walk.push(cell(self.cursor.pos));
+        scene.layer(
+            "walk",
+            COLOR_PATH,
+            Shape::Path {
+                cells: walk,
+                width: PATH_WIDTH,
+            },
+        );
+
+        let probe_color = if self.probe_succeeded {
+            COLOR_PATH_PROBE_SUCCESS
+        } else {
+            COLOR_PATH_PROBE_FAILURE
+        };
+        scene.layer(
+            "probe",
+            probe_color,
+            Shape::Path {
+                cells: turns(&map._viz_probe_path),
+                width: PATH_WIDTH,
+            },
+        );
+
+        if map.contains_pos(map._viz_obstacle) {
+            scene.layer(
+                "obstacle candidate",
+                COLOR_CELL_TARGET,
+                Shape::Cells(vec![cell(map._viz_obstacle)]),
+            );
+        }
+
+        scene.layer(
+            "cursor",
+            COLOR_CELL_CURSOR,
+            Shape::Cells(vec![cell(self.cursor.pos)]),
+        );
+
+        Some(scene)
+    }
+
+    fn status(&self) -> Vec<(&'static str, String)> {
+        vec![
+            (
+                "Cursor",
+                format!(
+                    "{},{} {}",
+                    self.cursor.pos.row, self.cursor.pos.col, self.cursor.dir
+                ),
+            ),
+            (
+                "Probe",
+                if self.probe_succeeded { "loop" } else { "exit" }.to_string(),
+            ),
+            ("Loops", self.candidates.len().to_string()),
+        ]
+    }
+
+    fn answer(&self, _step: u64) -> Option<Answer> {
+        Some(self.candidates.len().into())
+    }
+}
+
+fn cell(pos: Pos) -> (usize, usize) {
+    (pos.row as usize, pos.col as usize)
+}
+
+/// The cells where a walk turns, plus its ends, which is all that's needed to draw it.
+fn turns(path: &[Cursor]) -> Vec<(usize, usize)> {
+    let mut cells = Vec::new();
+    let mut direction = path.first().map(|cursor| cursor.rotate().dir);
+    for cursor in path {
+        if Some(cursor.dir) != direction {
+            direction = Some(cursor.dir);
+            cells.push(cell(cursor.pos));
+        }
+    }
+    if let Some(last) = path.last() {
+        cells.push(cell(last.pos));
+    }
+    cells
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::viz::headless::{self, Size};
+
+    use super::{
+        super::{parse, part2_fast},
+        LoopCandidates,
+    };
+
+    const EXAMPLE: &str = "\
+....#.....
+.........#
+..........
+..#.......
+.......#..
+..........
+.#..^.....
+........#.
+#.........
+......#...
+";
+
+    #[test]
+    fn finds_the_same_loops_as_part2_fast() {
+        let (map, cursor) = parse(EXAMPLE);
+        let (answer, _) = headless::run_to_end(LoopCandidates::new(map, cursor), Size::default());
+        assert_eq!(part2_fast(EXAMPLE), answer);
+    }
+}
@@ -1,12 +1,6 @@
 use std::fmt;
 
-use gtk::{gdk, glib};
-
-pub(super) fn eat_err(r: anyhow::Result<()>) {
-    if let Err(err) = r {
-        glib::g_error!("viz", "{err}");
-    }
-}
+use gtk::gdk;
 
 #[allow(dead_code)]
 pub(super) struct K_(pub(super) gdk::Key, pub(super) gdk::ModifierType);
@@ -93,7 +93,7 @@ impl Operator {
     }
 }
 
-fn part1(input: &str) -> Option<Box<dyn std::fmt::Display>> {
+fn part1(input: &str) -> Option<crate::answer::Answer> {
     let calibration = parse(input);
 
     let ops = [Operator::Add, Operator::Mul];
@@ -103,10 +103,10 @@ fn part1(input: &str) -> Option<Box<dyn std::fmt::Display>> {
         .filter_map(|eq| eq.try_solve(ops))
         .sum::<u64>();
 
-    Some(Box::new(answer))
+    Some(answer.into())
 }
 
-fn part2(input: &str) -> Option<Box<dyn std::fmt::Display>> {
+fn part2(input: &str) -> Option<crate::answer::Answer> {
     let calibration = parse(input);
 
     let ops = [Operator::Add, Operator::Mul, Operator::Concat];
@@ -116,5 +116,5 @@ fn part2(input: &str) -> Option<Box<dyn std::fmt::Display>> {
         .filter_map(|eq| eq.try_solve(ops))
         .sum::<u64>();
 
-    Some(Box::new(answer))
+    Some(answer.into())
 }
@@ -251,18 +251,18 @@ impl ops::SubAssign for Pos {
     }
 }
 
-fn part1(input: &str) -> Option<Box<dyn std::fmt::Display>> {
+fn part1(input: &str) -> Option<crate::answer::Answer> {
     let mut map = parse(input);
 
     let answer = map.find_antinodes();
 
-    Some(Box::new(answer))
+    Some(answer.into())
 }
 
-fn part2(input: &str) -> Option<Box<dyn std::fmt::Display>> {
+fn part2(input: &str) -> Option<crate::answer::Answer> {
     let mut map = parse(input);
 
     let answer = map.find_antinodes_with_resonant_harmonics();
 
-    Some(Box::new(answer))
+    Some(answer.into())
 }
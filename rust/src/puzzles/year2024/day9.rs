@@ -21,7 +21,7 @@ fn parse_byte8(b: u8) -> u8 {
     }
 }
 
-fn part1(input: &str) -> Option<Box<dyn std::fmt::Display>> {
+fn part1(input: &str) -> Option<crate::answer::Answer> {
     let diskmap_str = input.trim();
 
     let disk_size = diskmap_str.bytes().map(parse_byte32).sum::<u32>() as usize;
@@ -59,7 +59,7 @@ fn part1(input: &str) -> Option<Box<dyn std::fmt::Display>> {
         checksum += i * fblock_id as usize;
     }
 
-    Some(Box::new(checksum))
+    Some(checksum.into())
 }
 
 /// A span checksum is a subset of the full checksum spanning `blen` blocks
@@ -99,7 +99,7 @@ fn span_checksum(start_bid: u32, blen: u32, fid: usize) -> usize {
     fid * (blen * start_bid as usize + (blen * (blen - 1) / 2))
 }
 
-fn part1_v2(input: &str) -> Option<Box<dyn std::fmt::Display>> {
+fn part1_v2(input: &str) -> Option<crate::answer::Answer> {
     let diskmap_str = input.trim();
     let mut diskmap = Vec::<u32>::with_capacity(diskmap_str.len());
 
@@ -160,7 +160,7 @@ fn part1_v2(input: &str) -> Option<Box<dyn std::fmt::Display>> {
 
     checksum += span_checksum(bid, r_blen, r_fid);
 
-    Some(Box::new(checksum))
+    Some(checksum.into())
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -182,7 +182,7 @@ impl SpaceSpan {
     }
 }
 
-fn part2(input: &str) -> Option<Box<dyn std::fmt::Display>> {
+fn part2(input: &str) -> Option<crate::answer::Answer> {
     let diskmap_str = input.trim();
 
     let mut file_spans = Vec::<FileSpan>::with_capacity(diskmap_str.len() / 2 + 1);
@@ -239,5 +239,5 @@ fn part2(input: &str) -> Option<Box<dyn std::fmt::Display>> {
         r_fi -= 1;
     }
 
-    Some(Box::new(checksum))
+    Some(checksum.into())
 }
@@ -73,7 +73,7 @@ fn parse_rotations(input: &str) -> impl Iterator<Item = Rotation> {
     })
 }
 
-fn part1(input: &str) -> Option<Box<dyn std::fmt::Display>> {
+fn part1(input: &str) -> Option<crate::answer::Answer> {
     let mut dial = Dial::default();
     let mut times_wrapped = 0;
 
@@ -87,10 +87,10 @@ fn part1(input: &str) -> Option<Box<dyn std::fmt::Display>> {
         }
     }
 
-    Some(Box::new(times_wrapped))
+    Some(times_wrapped.into())
 }
 
-fn part2(input: &str) -> Option<Box<dyn std::fmt::Display>> {
+fn part2(input: &str) -> Option<crate::answer::Answer> {
     let mut dial = Dial::default();
     let mut times_wrapped = 0;
 
@@ -103,7 +103,7 @@ fn part2(input: &str) -> Option<Box<dyn std::fmt::Display>> {
         times_wrapped += current_times_wrapped;
     }
 
-    Some(Box::new(times_wrapped))
+    Some(times_wrapped.into())
 }
 
 #[cfg(test)]
@@ -29,7 +29,7 @@ fn repeat_id(half_id: u64) -> u64 {
     half_id * 10_u64.pow(n_digits as u32) + half_id
 }
 
-fn part1_fast(input: &str) -> Option<Box<dyn std::fmt::Display>> {
+fn part1_fast(input: &str) -> Option<crate::answer::Answer> {
     let mut invalid_id_acc = 0_u64;
 
     for ProductIdRange { raw_start, raw_end } in parse_product_id_ranges(input) {
@@ -84,10 +84,10 @@ fn part1_fast(input: &str) -> Option<Box<dyn std::fmt::Display>> {
         }
     }
 
-    Some(Box::new(invalid_id_acc))
+    Some(invalid_id_acc.into())
 }
 
-fn part1_brute(input: &str) -> Option<Box<dyn std::fmt::Display>> {
+fn part1_brute(input: &str) -> Option<crate::answer::Answer> {
     let mut id_string = String::new();
     let mut invalid_id_acc = 0_u64;
 
@@ -116,7 +116,7 @@ fn part1_brute(input: &str) -> Option<Box<dyn std::fmt::Display>> {
         }
     }
 
-    Some(Box::new(invalid_id_acc))
+    Some(invalid_id_acc.into())
 }
 
 fn is_repeated_digits_of_len(chunk_len: usize, id: &str) -> bool {
@@ -140,7 +140,7 @@ fn is_repeated_digits_of_len(chunk_len: usize, id: &str) -> bool {
     true
 }
 
-fn part2(input: &str) -> Option<Box<dyn std::fmt::Display>> {
+fn part2(input: &str) -> Option<crate::answer::Answer> {
     let mut id_string = String::new();
     let mut invalid_id_acc = 0_u64;
 
@@ -161,5 +161,5 @@ fn part2(input: &str) -> Option<Box<dyn std::fmt::Display>> {
         }
     }
 
-    Some(Box::new(invalid_id_acc))
+    Some(invalid_id_acc.into())
 }
@@ -51,22 +51,22 @@ fn parse_battery_banks(input: &str) -> impl Iterator<Item = BatteryBank<'_>> {
     input.lines().map(str::as_bytes).map(BatteryBank)
 }
 
-fn part1(input: &str) -> Option<Box<dyn std::fmt::Display>> {
+fn part1(input: &str) -> Option<crate::answer::Answer> {
     let mut total_joltage = 0_u32;
 
     for bank in parse_battery_banks(input) {
         total_joltage += bank.max_joltage();
     }
 
-    Some(Box::new(total_joltage))
+    Some(total_joltage.into())
 }
 
-fn part2(input: &str) -> Option<Box<dyn std::fmt::Display>> {
+fn part2(input: &str) -> Option<crate::answer::Answer> {
     let mut total_joltage = 0_u64;
 
     for bank in parse_battery_banks(input) {
         total_joltage += bank.max_joltage_high_power();
     }
 
-    Some(Box::new(total_joltage))
+    Some(total_joltage.into())
 }
@@ -77,12 +77,12 @@ impl<'input> PaperMap<'input> {
     }
 }
 
-fn part1(input: &str) -> Option<Box<dyn std::fmt::Display>> {
+fn part1(input: &str) -> Option<crate::answer::Answer> {
     let map = PaperMap::parse(input);
 
     let accessible = map.count_accessible_rolls();
 
-    Some(Box::new(accessible))
+    Some(accessible.into())
 }
 
 struct ForkliftManager {
@@ -191,11 +191,11 @@ impl ForkliftManager {
     }
 }
 
-fn part2(input: &str) -> Option<Box<dyn std::fmt::Display>> {
+fn part2(input: &str) -> Option<crate::answer::Answer> {
     let map = PaperMap::parse(input);
     let mut forklift_mngr = ForkliftManager::from_paper_map(&map);
 
     let moved = forklift_mngr.remove_all_accesible_rolls();
 
-    Some(Box::new(moved))
+    Some(moved.into())
 }
@@ -1,4 +1,12 @@
-use std::{cmp, ops, sync};
+use std::{cmp, ops};
+
+use crossterm::event::{KeyCode, KeyEvent};
+use gtk::{cairo, gdk, prelude::*};
+
+use crate::viz::{
+    gtk_host::{Canvas, GtkVisualizer},
+    scene::{Rgba, Scene, Shape},
+};
 
 inventory::submit!(
     crate::days::DayModule::new(2025, 5)
@@ -96,81 +104,119 @@ where
     }
 }
 
-fn part1(input: &str) -> Option<Box<dyn std::fmt::Display>> {
+fn part1(input: &str) -> Option<crate::answer::Answer> {
     let mut db = parse_ingredient_database(input);
 
     let fresh_ingredient_id_count = db.count_fresh_ids();
 
-    Some(Box::new(fresh_ingredient_id_count))
+    Some(fresh_ingredient_id_count.into())
 }
 
-const APP_ID: &str = "com.nelsonearle.adventofcode.year2025.day5.viz";
+const PART1_SCALE: u64 = 1000_u64.pow(4) / 2;
+
+/// The fresh ID ranges of part 1, one bar each.
+struct IdRangesViz {
+    id_ranges: Vec<ops::RangeInclusive<u64>>,
+    fresh_ingredient_id_count: usize,
+}
 
-fn part1_viz(input: &str) -> Option<Box<dyn std::fmt::Display>> {
-    use gtk::{gdk, glib, prelude::*};
+impl crate::viz::Visualizer for IdRangesViz {
+    fn step(&mut self) -> bool {
+        false
+    }
 
-    let db = parse_ingredient_database(input);
+    fn scene(&self) -> Option<Scene> {
+        let max_range = self
+            .id_ranges
+            .iter()
+            .map(|r| *r.end() / PART1_SCALE)
+            .max()
+            .unwrap_or_default();
+
+        let mut scene = Scene::new(max_range as f64, (self.id_ranges.len() * 3) as f64);
+        let bars = self
+            .id_ranges
+            .iter()
+            .enumerate()
+            .map(|(i, range)| {
+                let start = *range.start() / PART1_SCALE;
+                let end = *range.end() / PART1_SCALE;
+                [start as f64, (i * 3) as f64, (end - start) as f64, 2.]
+            })
+            .collect();
+        scene.layer("ranges", Rgba::rgb(0, 255, 0), Shape::Rects(bars));
+
+        Some(scene)
+    }
+
+    fn status(&self) -> Vec<(&'static str, String)> {
+        vec![("Ranges", self.id_ranges.len().to_string())]
+    }
+
+    fn answer(&self, _step: u64) -> Option<crate::answer::Answer> {
+        Some(self.fresh_ingredient_id_count.into())
+    }
+}
+
+impl GtkVisualizer for IdRangesViz {
+    fn size(&self) -> (i32, i32) {
+        let max_range = self
+            .id_ranges
+            .iter()
+            .map(|r| *r.end() / PART1_SCALE)
+            .max()
+            .unwrap_or_default();
+        (max_range as i32, self.id_ranges.len() as i32 * 3)
+    }
+
+    fn draw(&self, ctx: &cairo::Context, _canvas: &Canvas) -> anyhow::Result<()> {
+        const GREEN: gdk::RGBA = gdk::RGBA::new(0., 1., 0., 1.);
+
+        ctx.set_source_color(&GREEN);
+        for (i, range) in self.id_ranges.iter().enumerate() {
+            let start = *range.start() / PART1_SCALE;
+            let end = *range.end() / PART1_SCALE;
+            ctx.rectangle(start as f64, (i * 3) as f64, (end - start) as f64, 2.);
+        }
+        ctx.fill()?;
+
+        Ok(())
+    }
+}
+
+fn part1_viz(
+    input: &str,
+    ctx: &crate::viz::VizContext,
+) -> anyhow::Result<Option<crate::answer::Answer>> {
+    let mut db = parse_ingredient_database(input);
 
-    let id_ranges: &[ops::RangeInclusive<u64>] = db
+    let id_ranges = db
         .fresh_ingredient_id_range_buckets
         .iter()
         .flat_map(|b| &b.ranges)
         .cloned()
-        .collect::<Vec<_>>()
-        .leak();
-
-    const SCALE: u64 = 1000_u64.pow(4) / 2;
-    let max_range = id_ranges.iter().map(|r| *r.end() / SCALE).max().unwrap();
-
-    let width = max_range as i32;
-    let height = id_ranges.len() as i32 * 3;
-
-    let app = gtk::Application::builder().application_id(APP_ID).build();
-    app.connect_activate(move |app| {
-        let drawing_area = gtk::DrawingArea::builder()
-            .content_width(width)
-            .content_height(height)
-            .build();
-
-        let window = gtk::ApplicationWindow::builder()
-            .application(app)
-            .title("AoC - Year 2025 - Day 6 - Part 1")
-            .default_width(width)
-            .default_height(height)
-            .resizable(false)
-            .child(&drawing_area)
-            .build();
-
-        drawing_area.set_draw_func(move |_widget, ctx, _w, _h| {
-            // const RED: gdk::RGBA = gdk::RGBA::new(1., 0., 0., 1.);
-            const GREEN: gdk::RGBA = gdk::RGBA::new(0., 1., 0., 1.);
-            // const BLUE: gdk::RGBA = gdk::RGBA::new(0., 0., 1., 1.);
-
-            for (i, range) in id_ranges.iter().enumerate() {
-                let start = *range.start() / SCALE;
-                let end = *range.end() / SCALE;
-                let extent = end - start;
-                ctx.set_source_color(&GREEN);
-                ctx.rectangle(start as f64, (i * 3) as f64, extent as f64, 2.);
-                ctx.fill().unwrap();
-            }
-        });
-
-        window.present();
-    });
+        .collect();
+    let fresh_ingredient_id_count = db.count_fresh_ids();
+    let viz = IdRangesViz {
+        id_ranges,
+        fresh_ingredient_id_count,
+    };
 
-    let exit_code = app.run_with_args(&[] as &[&str]);
-    if exit_code != glib::ExitCode::SUCCESS {
-        eprintln!("{exit_code:?}");
+    if crate::viz::use_driver(ctx)? {
+        return crate::viz::Driver::new(viz, ctx).run();
     }
 
-    None
+    crate::viz::gtk_host::GtkHost::default()
+        .window("AoC - Year 2025 - Day 6 - Part 1", viz)
+        .run()?;
+
+    Ok(None)
 }
 
 const TRIM: usize = 164; // equal at 165
 
-fn part2(input: &str) -> Option<Box<dyn std::fmt::Display>> {
-    let mut fresh_ingredient_id_ranges = Vec::new();
+fn parse_sorted_id_ranges(input: &str) -> Vec<ops::RangeInclusive<u64>> {
+    let mut id_ranges = Vec::new();
 
     for line in input.lines() {
         if line.is_empty() {
@@ -180,11 +226,17 @@ fn part2(input: &str) -> Option<Box<dyn std::fmt::Display>> {
         let (min, max) = line.split_once('-').unwrap();
         let start = min.parse::<u64>().unwrap();
         let end = max.parse().unwrap();
-        fresh_ingredient_id_ranges.push(start..=end);
+        id_ranges.push(start..=end);
     }
 
     // Sort by range start asc.
-    fresh_ingredient_id_ranges.sort_unstable_by(|a, b| a.start().cmp(b.start()));
+    id_ranges.sort_unstable_by(|a, b| a.start().cmp(b.start()));
+
+    id_ranges
+}
+
+fn part2(input: &str) -> Option<crate::answer::Answer> {
+    let mut fresh_ingredient_id_ranges = parse_sorted_id_ranges(input);
 
     let mut max = 0;
     let mut max_i = 0;
@@ -210,245 +262,245 @@ fn part2(input: &str) -> Option<Box<dyn std::fmt::Display>> {
         .map(|r| *r.end() - *r.start() + 1)
         .sum::<u64>();
 
-    Some(Box::new(count))
+    Some(count.into())
 }
 
-fn part2_viz(input: &str) -> Option<Box<dyn std::fmt::Display>> {
-    use gtk::{gdk, glib, prelude::*};
-
-    struct State {
-        id_ranges: &'static mut [ops::RangeInclusive<u64>],
-        pending_merge: Option<[usize; 2]>,
-        last_merge_target_i: Option<usize>,
-    }
+const PART2_SCALE: f64 = 1. / ((1000_u64.pow(4) / 8) as f64);
+const PART2_BAR_HEIGHT: f64 = 8.;
+const PART2_MARGIN: f64 = 8.0;
+
+/// Step through merging the overlapping fresh ID ranges of part 2, showing each merge before
+/// making it.
+#[derive(Clone)]
+struct RangeMergesViz {
+    id_ranges: Vec<ops::RangeInclusive<u64>>,
+    pending_merge: Option<[usize; 2]>,
+    last_merge_target_i: Option<usize>,
+}
 
-    impl State {
-        const fn new() -> Self {
-            Self {
-                id_ranges: &mut [],
-                pending_merge: None,
-                last_merge_target_i: None,
-            }
-        }
+impl RangeMergesViz {
+    fn new(input: &str) -> Self {
+        let mut id_ranges = parse_sorted_id_ranges(input);
+        id_ranges.truncate(id_ranges.len().saturating_sub(TRIM));
 
-        fn from_id_ranges(id_ranges: &'static mut [ops::RangeInclusive<u64>]) -> Self {
-            Self {
-                id_ranges,
-                pending_merge: None,
-                last_merge_target_i: None,
-            }
+        Self {
+            id_ranges,
+            pending_merge: None,
+            last_merge_target_i: None,
         }
     }
 
-    static STATE: sync::RwLock<State> = sync::RwLock::new(State::new());
-
-    let mut id_ranges = Vec::new();
-
-    for line in input.lines() {
-        if line.is_empty() {
-            break;
-        }
-
-        let (min, max) = line.split_once('-').unwrap();
-        let start = min.parse::<u64>().unwrap();
-        let end = max.parse().unwrap();
-        id_ranges.push(start..=end);
+    fn fresh_ingredient_id_count(&self) -> u64 {
+        self.id_ranges
+            .iter()
+            .filter(|r| *r.end() > 0)
+            .map(|r| *r.end() - *r.start() + 1)
+            .sum()
     }
+}
 
-    // Sort by range start asc.
-    id_ranges.sort_unstable_by(|a, b| a.start().cmp(b.start()));
-
-    id_ranges.drain(id_ranges.len() - TRIM..).count();
+impl crate::viz::Visualizer for RangeMergesViz {
+    fn step(&mut self) -> bool {
+        let mut max = 0;
+        let mut max_i = 0;
 
-    // for r in &id_ranges {
-    //     // for r in id_ranges.iter().flatten() {
-    //     println!(
-    //         "{}..={} ({})",
-    //         *r.start(),
-    //         *r.end(),
-    //         *r.end() - *r.start() + 1
-    //     );
-    // }
+        for i in 0..self.id_ranges.len() {
+            let range = &self.id_ranges[i];
+            let (range_start, range_end) = (*range.start(), *range.end());
+            if range_end == 0 {
+                continue;
+            }
+            if range_start <= max {
+                let start = *self.id_ranges[max_i].start();
+                let end = cmp::max(max, range_end);
 
-    // const SCALE: f64 = 1. / ((1000_u64.pow(4) / 2) as f64);
-    const SCALE: f64 = 1. / ((1000_u64.pow(4) / 8) as f64);
-    // const BAR_HEIGHT: f64 = 4.;
-    const BAR_HEIGHT: f64 = 8.;
+                if self.pending_merge.is_none() {
+                    self.pending_merge = Some([i, max_i]);
+                    return true;
+                }
 
-    // const SCALE: f64 = 30.;
-    // const BAR_HEIGHT: f64 = 8.;
+                self.id_ranges[max_i] = start..=end;
+                self.id_ranges[i] = 0..=0;
 
-    const MARGIN: f64 = 8.0;
+                self.pending_merge = None;
+                self.last_merge_target_i = Some(max_i);
+                return true;
+            } else {
+                max = range_end;
+                max_i = i;
+            }
+        }
 
-    let max_range = id_ranges.iter().map(|r| *r.end()).max().unwrap() as f64 * SCALE;
+        false
+    }
 
-    let width = max_range as i32 + 2 * MARGIN.ceil() as i32;
+    fn scene(&self) -> Option<Scene> {
+        const RED: Rgba = Rgba::rgb(255, 0, 0);
+        const GREEN: Rgba = Rgba::rgb(0, 204, 0);
+        const BLUE: Rgba = Rgba::rgb(0, 0, 255);
+        const LIGHT_BLUE: Rgba = Rgba::rgb(0, 179, 255);
+
+        let max_range = self
+            .id_ranges
+            .iter()
+            .map(|r| *r.end())
+            .max()
+            .unwrap_or_default() as f64
+            * PART2_SCALE;
+        let width = max_range.ceil() + 2. * PART2_MARGIN;
+        let height = (self.id_ranges.len() as f64 * PART2_BAR_HEIGHT).ceil() + 2. * PART2_MARGIN;
+
+        let mut scene = Scene::new(width, height);
+        scene.layer(
+            "field",
+            Rgba::WHITE,
+            Shape::Rects(vec![[
+                PART2_MARGIN,
+                PART2_MARGIN,
+                width - 2. * PART2_MARGIN,
+                height - 2. * PART2_MARGIN,
+            ]]),
+        );
+
+        let bar = |i: usize| {
+            let range = &self.id_ranges[i];
+            let start = *range.start() as f64 * PART2_SCALE;
+            let end = *range.end() as f64 * PART2_SCALE;
+            [
+                PART2_MARGIN + start,
+                PART2_MARGIN + i as f64 * PART2_BAR_HEIGHT,
+                end - start,
+                PART2_BAR_HEIGHT - 2.,
+            ]
+        };
+        let highlighted = match self.pending_merge {
+            Some(merge) => merge.to_vec(),
+            None => self.last_merge_target_i.into_iter().collect(),
+        };
 
-    let height = (id_ranges.len() as f64 * BAR_HEIGHT).ceil() as i32 + 2 * MARGIN.ceil() as i32;
+        let ranges = (0..self.id_ranges.len())
+            .filter(|i| *self.id_ranges[*i].end() > 0 && !highlighted.contains(i))
+            .map(bar)
+            .collect();
+        scene.layer("ranges", GREEN, Shape::Rects(ranges));
 
-    *STATE.write().unwrap() = State::from_id_ranges(id_ranges.leak());
+        match self.pending_merge {
+            Some([source_i, target_i]) => {
+                scene.layer("merge source", RED, Shape::Rects(vec![bar(source_i)]));
+                scene.layer("merge target", BLUE, Shape::Rects(vec![bar(target_i)]));
+            }
+            None => {
+                if let Some(target_i) = self.last_merge_target_i {
+                    scene.layer("last merge", LIGHT_BLUE, Shape::Rects(vec![bar(target_i)]));
+                }
+            }
+        }
 
-    let app = gtk::Application::builder().application_id(APP_ID).build();
-    app.connect_activate(move |app| {
-        let drawing_area = gtk::DrawingArea::builder()
-            .content_width(width)
-            .content_height(height)
-            .build();
+        Some(scene)
+    }
 
-        let window = gtk::ApplicationWindow::builder()
-            .application(app)
-            .title("AoC - Year 2025 - Day 6 - Part 2")
-            .default_width(width)
-            .default_height(height)
-            .resizable(false)
-            .child(&drawing_area)
-            .build();
+    fn status(&self) -> Vec<(&'static str, String)> {
+        let ranges = self.id_ranges.iter().filter(|r| *r.end() > 0).count();
+        vec![
+            ("Ranges", ranges.to_string()),
+            ("Fresh IDs", self.fresh_ingredient_id_count().to_string()),
+        ]
+    }
 
-        drawing_area.set_draw_func(move |_widget, ctx, w, h| {
-            const RED: gdk::RGBA = gdk::RGBA::new(1., 0., 0., 1.);
-            const GREEN: gdk::RGBA = gdk::RGBA::new(0., 0.8, 0., 1.);
-            const BLUE: gdk::RGBA = gdk::RGBA::new(0., 0., 1., 1.);
-            const LIGHT_BLUE: gdk::RGBA = gdk::RGBA::new(0., 0.7, 1., 1.);
+    fn answer(&self, _step: u64) -> Option<crate::answer::Answer> {
+        Some(self.fresh_ingredient_id_count().into())
+    }
+}
 
-            ctx.set_source_rgb(0., 0., 0.);
-            ctx.rectangle(0., 0., w as f64, h as f64);
-            ctx.fill().unwrap();
+impl GtkVisualizer for RangeMergesViz {
+    fn size(&self) -> (i32, i32) {
+        let max_range = self
+            .id_ranges
+            .iter()
+            .map(|r| *r.end())
+            .max()
+            .unwrap_or_default() as f64
+            * PART2_SCALE;
+        let width = max_range as i32 + 2 * PART2_MARGIN.ceil() as i32;
+        let height = (self.id_ranges.len() as f64 * PART2_BAR_HEIGHT).ceil() as i32
+            + 2 * PART2_MARGIN.ceil() as i32;
+        (width, height)
+    }
 
-            ctx.set_source_rgb(1., 1., 1.);
+    fn draw(&self, ctx: &cairo::Context, canvas: &Canvas) -> anyhow::Result<()> {
+        const RED: gdk::RGBA = gdk::RGBA::new(1., 0., 0., 1.);
+        const GREEN: gdk::RGBA = gdk::RGBA::new(0., 0.8, 0., 1.);
+        const BLUE: gdk::RGBA = gdk::RGBA::new(0., 0., 1., 1.);
+        const LIGHT_BLUE: gdk::RGBA = gdk::RGBA::new(0., 0.7, 1., 1.);
+
+        ctx.set_source_rgb(0., 0., 0.);
+        ctx.rectangle(0., 0., canvas.width, canvas.height);
+        ctx.fill()?;
+
+        ctx.set_source_rgb(1., 1., 1.);
+        ctx.rectangle(
+            PART2_MARGIN,
+            PART2_MARGIN,
+            canvas.width - 2. * PART2_MARGIN,
+            canvas.height - 2. * PART2_MARGIN,
+        );
+        ctx.fill()?;
+
+        for (i, range) in self.id_ranges.iter().enumerate() {
+            let color = match self.pending_merge {
+                Some([source_i, _]) if source_i == i => &RED,
+                Some([_, target_i]) if target_i == i => &BLUE,
+                None if self.last_merge_target_i == Some(i) => &LIGHT_BLUE,
+                _ => &GREEN,
+            };
+            ctx.set_source_color(color);
+
+            let start = *range.start() as f64 * PART2_SCALE;
+            let end = *range.end() as f64 * PART2_SCALE;
             ctx.rectangle(
-                MARGIN,
-                MARGIN,
-                w as f64 - 2. * MARGIN,
-                h as f64 - 2. * MARGIN,
+                PART2_MARGIN + start,
+                PART2_MARGIN + i as f64 * PART2_BAR_HEIGHT,
+                end - start,
+                PART2_BAR_HEIGHT - 2.,
             );
-            ctx.fill().unwrap();
-
-            {
-                let state = STATE.read().unwrap();
-                let id_ranges = &*state.id_ranges;
-                for (i, range) in id_ranges.iter().enumerate() {
-                    let color = match state.pending_merge {
-                        Some([source_i, _]) if source_i == i => &RED,
-                        Some([_, target_i]) if target_i == i => &BLUE,
-                        None if state.last_merge_target_i == Some(i) => &LIGHT_BLUE,
-                        _ => &GREEN,
-                    };
-                    ctx.set_source_color(color);
-
-                    let start = *range.start() as f64 * SCALE;
-                    let end = *range.end() as f64 * SCALE;
-                    let extent = end - start;
-                    ctx.rectangle(
-                        MARGIN + start,
-                        MARGIN + i as f64 * BAR_HEIGHT,
-                        extent,
-                        BAR_HEIGHT - 2.,
-                    );
-
-                    ctx.fill().unwrap();
+            ctx.fill()?;
+        }
+
+        Ok(())
+    }
+
+    fn key_pressed(&mut self, key: KeyEvent) -> bool {
+        match key.code {
+            KeyCode::Char(' ') => {
+                if !crate::viz::Visualizer::step(self) {
+                    println!("no overlaps found");
                 }
+                true
             }
-        });
-
-        let key_controller = gtk::EventControllerKey::new();
-        key_controller.connect_key_pressed(glib::clone!(
-            #[weak]
-            app,
-            #[weak]
-            drawing_area,
-            #[upgrade_or]
-            glib::Propagation::Proceed,
-            move |controller, keyval, keycode, modifier| {
-                cb_key_pressed(app, drawing_area, controller, keyval, keycode, modifier)
-            }
-        ));
-        window.add_controller(key_controller);
-
-        fn cb_key_pressed(
-            app: gtk::Application,
-            drawing_area: gtk::DrawingArea,
-            _controller: &gtk::EventControllerKey,
-            keyval: gdk::Key,
-            _keycode: u32,
-            modifier: gdk::ModifierType,
-        ) -> glib::Propagation {
-            // eprintln!("Press {:?}", utils::K_(keyval, modifier));
-
-            if modifier == gdk::ModifierType::META_MASK && keyval == gdk::Key::q {
-                app.quit();
-            } else if keyval == gdk::Key::space {
-                {
-                    let mut state = STATE.write().unwrap();
-                    let State {
-                        id_ranges,
-                        pending_merge,
-                        last_merge_target_i,
-                    } = &mut *state;
-
-                    let mut found_overlap = false;
-                    let mut max = 0;
-                    let mut max_i = 0;
-
-                    for i in 0..id_ranges.len() {
-                        let range = &id_ranges[i];
-                        let (range_start, range_end) = (*range.start(), *range.end());
-                        if range_end == 0 {
-                            continue;
-                        }
-                        if range_start <= max {
-                            found_overlap = true;
-
-                            let start = *id_ranges[max_i].start();
-                            let end = cmp::max(max, range_end);
-
-                            if pending_merge.is_none() {
-                                *pending_merge = Some([i, max_i]);
-                                break;
-                            }
-
-                            id_ranges[max_i] = start..=end;
-                            id_ranges[i] = 0..=0;
-
-                            *pending_merge = None;
-                            *last_merge_target_i = Some(max_i);
-                            break;
-                        } else {
-                            max = range_end;
-                            max_i = i;
-                        }
-                    }
-
-                    if !found_overlap {
-                        println!("no overlaps found");
-                    }
-                }
-
-                drawing_area.queue_draw();
-            } else if keyval == gdk::Key::Return {
+            KeyCode::Enter => {
                 println!();
                 println!("Calculate sum:");
-                let state = STATE.read().unwrap();
-                let count = state
-                    .id_ranges
-                    .iter()
-                    .filter(|r| *r.end() > 0)
-                    .map(|r| *r.end() - *r.start() + 1)
-                    .sum::<u64>();
-                println!(":: {count}");
-
-                drawing_area.queue_draw();
+                println!(":: {}", self.fresh_ingredient_id_count());
+                false
             }
-
-            glib::Propagation::Proceed
+            _ => false,
         }
+    }
+}
 
-        window.present();
-    });
+fn part2_viz(
+    input: &str,
+    ctx: &crate::viz::VizContext,
+) -> anyhow::Result<Option<crate::answer::Answer>> {
+    let viz = RangeMergesViz::new(input);
 
-    let exit_code = app.run_with_args(&[] as &[&str]);
-    if exit_code != glib::ExitCode::SUCCESS {
-        eprintln!("{exit_code:?}");
+    if crate::viz::use_driver(ctx)? {
+        return crate::viz::Driver::new(viz, ctx).with_rewind().run();
     }
 
-    None
+    crate::viz::gtk_host::GtkHost::default()
+        .window("AoC - Year 2025 - Day 6 - Part 2", viz)
+        .run()?;
+
+    Ok(None)
 }
@@ -0,0 +1,107 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::LazyLock,
+};
+
+use anyhow::{Context, Result, bail};
+
+static PUZZLES_SRC_DIR: LazyLock<PathBuf> =
+    LazyLock::new(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("src/puzzles"));
+
+const DAY_TEMPLATE: &str = include_str!("puzzles/dayN.txt");
+const DAY_TEMPLATE_MODULE: &str = "DayModule::new(2025, 0)";
+
+/// Render the day template into `src/puzzles/year{year}/day{day_i}.rs` and declare its module,
+/// creating the year module first if this is its first day.
+///
+/// Returns the path of the new source file.
+pub(crate) fn new_day(year: u32, day_i: u32) -> Result<PathBuf> {
+    let year_dir = PUZZLES_SRC_DIR.join(format!("year{year}"));
+    let day_path = year_dir.join(format!("day{day_i}.rs"));
+
+    if day_path.exists() {
+        bail!("day source file already exists: {}", day_path.display());
+    }
+
+    if !year_dir.exists() {
+        fs::create_dir_all(&year_dir)
+            .with_context(|| format!("failed to create year directory: {}", year_dir.display()))?;
+        add_mod(&PUZZLES_SRC_DIR.join("mod.rs"), &format!("year{year}"))?;
+    }
+
+    if !DAY_TEMPLATE.contains(DAY_TEMPLATE_MODULE) {
+        bail!("day template is missing `{DAY_TEMPLATE_MODULE}`");
+    }
+    let day_source = DAY_TEMPLATE.replacen(
+        DAY_TEMPLATE_MODULE,
+        &format!("DayModule::new({year}, {day_i})"),
+        1,
+    );
+    fs::write(&day_path, day_source)
+        .with_context(|| format!("failed to write day source file: {}", day_path.display()))?;
+
+    add_mod(&year_dir.join("mod.rs"), &format!("day{day_i}"))?;
+
+    Ok(day_path)
+}
+
+fn add_mod(mod_path: &Path, name: &str) -> Result<()> {
+    let contents = if mod_path.exists() {
+        fs::read_to_string(mod_path)
+            .with_context(|| format!("failed to read module file: {}", mod_path.display()))?
+    } else {
+        String::new()
+    };
+
+    fs::write(mod_path, insert_mod(&contents, name))
+        .with_context(|| format!("failed to write module file: {}", mod_path.display()))
+}
+
+/// Insert `mod {name};` among the existing `mod` lines, keeping them in the same order as rustfmt.
+fn insert_mod(contents: &str, name: &str) -> String {
+    let mod_line = format!("mod {name};");
+    let mut lines = contents.lines().collect::<Vec<_>>();
+
+    if lines.contains(&mod_line.as_str()) {
+        return contents.to_owned();
+    }
+
+    fn mod_name(line: &str) -> Option<&str> {
+        line.strip_prefix("mod ")?.strip_suffix(';')
+    }
+
+    let i = lines
+        .iter()
+        .position(|line| mod_name(line).is_some_and(|m| m > name))
+        .or_else(|| {
+            lines
+                .iter()
+                .rposition(|line| mod_name(line).is_some())
+                .map(|i| i + 1)
+        })
+        .unwrap_or(lines.len());
+    lines.insert(i, &mod_line);
+
+    let mut contents = lines.join("\n");
+    contents.push('\n');
+    contents
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn insert_mod() {
+        let contents = "mod day1;\nmod day10;\nmod day2;\n";
+        assert_eq!(
+            "mod day1;\nmod day10;\nmod day11;\nmod day2;\n",
+            super::insert_mod(contents, "day11")
+        );
+        assert_eq!(
+            "mod day1;\nmod day10;\nmod day2;\nmod day3;\n",
+            super::insert_mod(contents, "day3")
+        );
+        assert_eq!(contents, super::insert_mod(contents, "day10"));
+        assert_eq!("mod day1;\n", super::insert_mod("", "day1"));
+    }
+}
@@ -0,0 +1,257 @@
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process,
+    sync::LazyLock,
+    time::{Duration, SystemTime},
+};
+
+use anyhow::{Context, Result, bail};
+
+use crate::{commit::DayPartCommit, days::DayPartResult};
+
+pub(crate) static PUZZLE_TIMINGS_DIR: LazyLock<PathBuf> =
+    LazyLock::new(|| Path::new(crate::PUZZLE_DIR).join("timings"));
+
+fn timings_path(year: u32) -> PathBuf {
+    PUZZLE_TIMINGS_DIR.join(format!("{year}.log"))
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum AnswerStatus {
+    Correct,
+    Incorrect,
+    Uncommitted,
+    Test,
+}
+
+impl AnswerStatus {
+    pub(crate) fn new(existing_commit: Option<&DayPartCommit>, current: &DayPartCommit) -> Self {
+        match existing_commit {
+            Some(existing) if current == existing => Self::Correct,
+            Some(_) => Self::Incorrect,
+            None => Self::Uncommitted,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Correct => "correct",
+            Self::Incorrect => "incorrect",
+            Self::Uncommitted => "uncommitted",
+            Self::Test => "test",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        Some(match s {
+            "correct" => Self::Correct,
+            "incorrect" => Self::Incorrect,
+            "uncommitted" => Self::Uncommitted,
+            "test" => Self::Test,
+            _ => return None,
+        })
+    }
+}
+
+pub(crate) struct TimingRecord {
+    pub(crate) timestamp: u64,
+    pub(crate) commit: Option<String>,
+    pub(crate) day: u32,
+    pub(crate) part: u8,
+    pub(crate) executor: String,
+    pub(crate) duration: Duration,
+    pub(crate) status: AnswerStatus,
+}
+
+impl TimingRecord {
+    fn parse(line: &str) -> Result<Self> {
+        let fields = line.split('\t').collect::<Vec<_>>();
+        let &[timestamp, commit, day, part, executor, nanos, status] = fields.as_slice() else {
+            bail!("expected 7 fields, found {}", fields.len());
+        };
+        Ok(Self {
+            timestamp: timestamp.parse().context("invalid timestamp")?,
+            commit: (commit != "-").then(|| commit.to_owned()),
+            day: day.parse().context("invalid day")?,
+            part: part.parse().context("invalid part")?,
+            executor: executor.to_owned(),
+            duration: Duration::from_nanos(nanos.parse().context("invalid duration")?),
+            status: AnswerStatus::parse(status)
+                .with_context(|| format!("invalid answer status: {status}"))?,
+        })
+    }
+}
+
+/// Collects the results of a single `run` invocation so they can be appended
+/// to the year's timing log in one write.
+pub(crate) struct TimingLog {
+    year: u32,
+    timestamp: u64,
+    commit: Option<String>,
+    lines: String,
+}
+
+impl TimingLog {
+    pub(crate) fn new(year: u32) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        Self {
+            year,
+            timestamp,
+            commit: git_commit(),
+            lines: String::new(),
+        }
+    }
+
+    pub(crate) fn record(
+        &mut self,
+        day_i: u32,
+        part: crate::Part,
+        result: &DayPartResult,
+        status: AnswerStatus,
+    ) {
+        self.lines.push_str(&format!(
+            "{}\t{}\t{day_i}\t{}\t{}\t{}\t{}\n",
+            self.timestamp,
+            self.commit.as_deref().unwrap_or("-"),
+            part.number(),
+            result.name,
+            result.duration.0.as_nanos(),
+            status.as_str(),
+        ));
+    }
+
+    pub(crate) fn save(self) -> Result<()> {
+        if self.lines.is_empty() {
+            return Ok(());
+        }
+
+        fs::create_dir_all(&*PUZZLE_TIMINGS_DIR).with_context(|| {
+            format!(
+                "failed to create puzzle timings directory: {}",
+                PUZZLE_TIMINGS_DIR.display()
+            )
+        })?;
+
+        let path = timings_path(self.year);
+        fs::File::options()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .and_then(|mut file| file.write_all(self.lines.as_bytes()))
+            .with_context(|| format!("failed to append to timing log: {}", path.display()))
+    }
+}
+
+fn git_commit() -> Option<String> {
+    let output = process::Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_owned())
+}
+
+pub(crate) fn read_timings(year: u32) -> Result<Vec<TimingRecord>> {
+    let path = timings_path(year);
+    let log = match fs::read_to_string(&path) {
+        Ok(log) => log,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => {
+            return Err(err)
+                .with_context(|| format!("failed to read timing log: {}", path.display()));
+        }
+    };
+
+    log.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(i, line)| {
+            TimingRecord::parse(line).with_context(|| {
+                format!(
+                    "failed to parse timing log line {}: {}",
+                    i + 1,
+                    path.display()
+                )
+            })
+        })
+        .collect()
+}
+
+pub(crate) fn sparkline(durations: &[Duration]) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+    let Some(min) = durations.iter().min() else {
+        return String::new();
+    };
+    let max = durations.iter().max().unwrap();
+    let range = (*max - *min).as_secs_f64();
+
+    durations
+        .iter()
+        .map(|d| {
+            if range == 0.0 {
+                BARS[0]
+            } else {
+                let level = (*d - *min).as_secs_f64() / range * (BARS.len() - 1) as f64;
+                BARS[level.round() as usize]
+            }
+        })
+        .collect()
+}
+
+/// Format a unix timestamp as a UTC `YYYY-MM-DD` date.
+///
+/// [Source](https://howardhinnant.github.io/date_algorithms.html#civil_from_days)
+pub(crate) fn format_date(timestamp: u64) -> String {
+    let z = (timestamp / 86_400) as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    #[test]
+    fn format_date() {
+        assert_eq!("1970-01-01", super::format_date(0));
+        assert_eq!("2024-12-16", super::format_date(1_734_307_200));
+        assert_eq!("2025-03-01", super::format_date(1_740_787_200));
+    }
+
+    #[test]
+    fn sparkline() {
+        let durations = [1, 8, 4, 1].map(Duration::from_millis);
+        assert_eq!("▁█▄▁", super::sparkline(&durations));
+        assert_eq!("▁▁", super::sparkline(&[Duration::ZERO; 2]));
+        assert_eq!("", super::sparkline(&[]));
+    }
+
+    #[test]
+    fn parse_record() {
+        let record =
+            super::TimingRecord::parse("1734307200\ta1b2c3d\t16\t2\tpart2\t1500000\tcorrect")
+                .unwrap();
+        assert_eq!(Some("a1b2c3d"), record.commit.as_deref());
+        assert_eq!((16, 2), (record.day, record.part));
+        assert_eq!(Duration::from_micros(1500), record.duration);
+        assert_eq!(super::AnswerStatus::Correct, record.status);
+
+        assert!(super::TimingRecord::parse("1734307200\t-\t16\t2\tpart2\t1").is_err());
+    }
+}
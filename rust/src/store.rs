@@ -0,0 +1,240 @@
+//! Optional encryption of the puzzle inputs and answers, so the puzzle data directory can be
+//! committed to a public repository.
+//!
+//! Encryption is enabled by configuring a key, either with the `AOC_STORE_KEY` environment variable
+//! or in `$XDG_CONFIG_HOME/adventofcode/store-key` (`~/.config/adventofcode/store-key` by default).
+//! The key isn't stretched, so it should be random, e.g. from `openssl rand -hex 32`, and keys
+//! shorter than [`MIN_KEY_LEN`] are rejected. Files are
+//! always decrypted transparently on read, and written encrypted only when a key is configured.
+
+use std::{
+    env, fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::LazyLock,
+};
+
+use ring::{
+    aead, hkdf,
+    rand::{SecureRandom, SystemRandom},
+};
+
+const KEY_ENV_VAR: &str = "AOC_STORE_KEY";
+
+/// Prefix of encrypted files, also authenticated as the AEAD associated data.
+const MAGIC: &[u8] = b"AOCENC1\n";
+
+const NONCE_LEN: usize = 12;
+
+const MIN_KEY_LEN: usize = 16;
+
+/// The configured key, or why it can't be used.
+static KEY: LazyLock<Result<Option<aead::LessSafeKey>, String>> = LazyLock::new(|| {
+    read_configured_key()
+        .map(|(key, source)| parse_key(&key, &source))
+        .transpose()
+});
+
+fn key_path() -> Option<PathBuf> {
+    let config_dir = match env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) => PathBuf::from(dir),
+        None => PathBuf::from(env::var_os("HOME")?).join(".config"),
+    };
+    Some(config_dir.join("adventofcode").join("store-key"))
+}
+
+/// The configured key and where it came from.
+fn read_configured_key() -> Option<(String, String)> {
+    if let Ok(key) = env::var(KEY_ENV_VAR) {
+        return Some((key, KEY_ENV_VAR.to_owned()));
+    }
+    let path = key_path()?;
+    let key = fs::read_to_string(&path).ok()?;
+    Some((key, path.display().to_string()))
+}
+
+fn parse_key(key: &str, source: &str) -> Result<aead::LessSafeKey, String> {
+    let key = key.trim();
+    if key.len() < MIN_KEY_LEN {
+        return Err(format!(
+            "the store key in {source} is too short, it must be at least {MIN_KEY_LEN} characters, \
+            e.g. from `openssl rand -hex 32`"
+        ));
+    }
+    Ok(derive_key(key))
+}
+
+fn derive_key(key: &str) -> aead::LessSafeKey {
+    let salt = hkdf::Salt::new(hkdf::HKDF_SHA256, b"adventofcode puzzle store");
+    let prk = salt.extract(key.as_bytes());
+    let okm = prk
+        .expand(&[], &aead::CHACHA20_POLY1305)
+        .expect("the key length is valid for HKDF-SHA256");
+    aead::LessSafeKey::new(aead::UnboundKey::from(okm))
+}
+
+fn key() -> io::Result<Option<&'static aead::LessSafeKey>> {
+    #[cfg(test)]
+    if let Some(key) = tests::KEY.get() {
+        return Ok(Some(key));
+    }
+    match &*KEY {
+        Ok(key) => Ok(key.as_ref()),
+        Err(err) => Err(io::Error::new(io::ErrorKind::InvalidInput, err.clone())),
+    }
+}
+
+pub(crate) fn is_encryption_enabled() -> io::Result<bool> {
+    Ok(key()?.is_some())
+}
+
+pub(crate) fn is_encrypted(contents: &[u8]) -> bool {
+    contents.starts_with(MAGIC)
+}
+
+/// Encrypt `contents` if a key is configured.
+pub(crate) fn encode(contents: &[u8]) -> io::Result<Vec<u8>> {
+    match key()? {
+        Some(key) => seal(key, contents),
+        None => Ok(contents.to_vec()),
+    }
+}
+
+/// Decrypt `contents` if it's encrypted.
+pub(crate) fn decode(contents: Vec<u8>) -> io::Result<Vec<u8>> {
+    if !is_encrypted(&contents) {
+        return Ok(contents);
+    }
+    let Some(key) = key()? else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("file is encrypted, but no key is configured, set {KEY_ENV_VAR}"),
+        ));
+    };
+    open(key, &contents)
+}
+
+fn seal(key: &aead::LessSafeKey, plaintext: &[u8]) -> io::Result<Vec<u8>> {
+    let mut nonce = [0_u8; NONCE_LEN];
+    SystemRandom::new()
+        .fill(&mut nonce)
+        .map_err(|_| io::Error::other("failed to generate a nonce"))?;
+
+    let mut in_out = plaintext.to_vec();
+    key.seal_in_place_append_tag(
+        aead::Nonce::assume_unique_for_key(nonce),
+        aead::Aad::from(MAGIC),
+        &mut in_out,
+    )
+    .map_err(|_| io::Error::other("failed to encrypt"))?;
+
+    let mut sealed = Vec::with_capacity(MAGIC.len() + NONCE_LEN + in_out.len());
+    sealed.extend_from_slice(MAGIC);
+    sealed.extend_from_slice(&nonce);
+    sealed.extend_from_slice(&in_out);
+    Ok(sealed)
+}
+
+fn open(key: &aead::LessSafeKey, sealed: &[u8]) -> io::Result<Vec<u8>> {
+    let invalid = || io::Error::new(io::ErrorKind::InvalidData, "failed to decrypt, wrong key?");
+
+    let sealed = sealed.strip_prefix(MAGIC).ok_or_else(invalid)?;
+    if sealed.len() < NONCE_LEN {
+        return Err(invalid());
+    }
+    let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+    let nonce = aead::Nonce::try_assume_unique_for_key(nonce).map_err(|_| invalid())?;
+
+    let mut in_out = ciphertext.to_vec();
+    let plaintext_len = key
+        .open_in_place(nonce, aead::Aad::from(MAGIC), &mut in_out)
+        .map_err(|_| invalid())?
+        .len();
+    in_out.truncate(plaintext_len);
+    Ok(in_out)
+}
+
+/// Read a file from the store, decrypting it if needed.
+pub(crate) fn read_to_string(path: impl AsRef<Path>) -> io::Result<String> {
+    let contents = decode(fs::read(path)?)?;
+    String::from_utf8(contents).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+/// Write a file to the store, encrypting it if a key is configured.
+pub(crate) fn write(path: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    fs::write(path, encode(contents.as_ref())?)
+}
+
+/// Append to a file in the store. Encrypted files are sealed as a whole, so they're rewritten.
+pub(crate) fn append(path: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    let path = path.as_ref();
+    if !is_encryption_enabled()? {
+        let is_plaintext = match fs::read(path) {
+            Ok(existing) => !is_encrypted(&existing),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => true,
+            Err(err) => return Err(err),
+        };
+        if is_plaintext {
+            return fs::File::options()
+                .create(true)
+                .append(true)
+                .open(path)?
+                .write_all(contents.as_ref());
+        }
+    }
+
+    let mut existing = match fs::read(path) {
+        Ok(existing) => decode(existing)?,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Vec::new(),
+        Err(err) => return Err(err),
+    };
+    existing.extend_from_slice(contents.as_ref());
+    write(path, existing)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::cell::Cell;
+
+    use ring::aead;
+
+    thread_local! {
+        /// Overrides the configured key on this thread.
+        pub(super) static KEY: Cell<Option<&'static aead::LessSafeKey>> = const { Cell::new(None) };
+    }
+
+    /// Run `f` as if `key` was configured.
+    pub(crate) fn with_key<T>(key: &str, f: impl FnOnce() -> T) -> T {
+        let key = Box::leak(Box::new(super::derive_key(key)));
+        KEY.set(Some(key));
+        let result = f();
+        KEY.set(None);
+        result
+    }
+
+    #[test]
+    fn seal_open() {
+        let key = super::derive_key("0123456789abcdef");
+        let plaintext = b"3   4\n4   3\n";
+
+        let sealed = super::seal(&key, plaintext).unwrap();
+        assert!(super::is_encrypted(&sealed));
+        assert_ne!(plaintext.as_slice(), &sealed[super::MAGIC.len()..]);
+        assert_eq!(plaintext.as_slice(), super::open(&key, &sealed).unwrap());
+
+        let other_key = super::derive_key("fedcba9876543210");
+        assert!(super::open(&other_key, &sealed).is_err());
+
+        let mut tampered = sealed;
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(super::open(&key, &tampered).is_err());
+    }
+
+    #[test]
+    fn rejects_short_keys() {
+        assert!(super::parse_key("", super::KEY_ENV_VAR).is_err());
+        assert!(super::parse_key(" \n\t", super::KEY_ENV_VAR).is_err());
+        assert!(super::parse_key("0123456789abcde\n", super::KEY_ENV_VAR).is_err());
+        assert!(super::parse_key("0123456789abcdef\n", super::KEY_ENV_VAR).is_ok());
+    }
+}
@@ -0,0 +1,77 @@
+//! Put the terminal into raw mode, restoring it even if the program panics.
+
+use std::{
+    io, panic,
+    sync::{
+        Once,
+        atomic::{AtomicU8, Ordering},
+    },
+};
+
+use crossterm::{
+    cursor, execute,
+    terminal::{self, EnterAlternateScreen, LeaveAlternateScreen},
+};
+
+/// What needs to be restored by the panic hook.
+static ACTIVE: AtomicU8 = AtomicU8::new(INACTIVE);
+
+const INACTIVE: u8 = 0;
+const RAW: u8 = 1;
+const FULLSCREEN: u8 = 2;
+
+static INSTALL_PANIC_HOOK: Once = Once::new();
+
+/// Restores the terminal when dropped, or when the program panics while it's alive.
+pub(crate) struct TerminalGuard(());
+
+impl TerminalGuard {
+    /// Enable raw mode.
+    pub(crate) fn raw() -> io::Result<Self> {
+        Self::enable(RAW)
+    }
+
+    /// Enable raw mode, switch to the alternate screen and hide the cursor.
+    pub(crate) fn fullscreen() -> io::Result<Self> {
+        Self::enable(FULLSCREEN)
+    }
+
+    fn enable(mode: u8) -> io::Result<Self> {
+        INSTALL_PANIC_HOOK.call_once(|| {
+            let hook = panic::take_hook();
+            panic::set_hook(Box::new(move |info| {
+                restore(ACTIVE.swap(INACTIVE, Ordering::SeqCst));
+                hook(info);
+            }));
+        });
+
+        // Create the guard first so anything enabled is restored if a later step fails.
+        ACTIVE.store(mode, Ordering::SeqCst);
+        let guard = Self(());
+
+        terminal::enable_raw_mode()?;
+        if mode == FULLSCREEN {
+            execute!(io::stdout(), EnterAlternateScreen, cursor::Hide)?;
+        }
+
+        Ok(guard)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        // The panic hook may have already restored the terminal.
+        restore(ACTIVE.swap(INACTIVE, Ordering::SeqCst));
+    }
+}
+
+/// Restore the terminal on a best-effort basis, since there's nothing to do if it fails.
+fn restore(mode: u8) {
+    if mode == INACTIVE {
+        return;
+    }
+    if mode == FULLSCREEN {
+        _ = execute!(io::stdout(), LeaveAlternateScreen, cursor::Show);
+    }
+    _ = terminal::disable_raw_mode();
+}
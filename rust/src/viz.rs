@@ -0,0 +1,1024 @@
+//! A shared driver for step-based terminal visualizers.
+//!
+//! A visualizer holds the state of a puzzle's solution algorithm and knows how to advance it and
+//! render it. The [`Driver`] owns the terminal and the event loop, and gives every visualizer the
+//! same keybindings for stepping, playback and quitting.
+
+use std::{
+    collections::{BTreeSet, VecDeque},
+    fmt::Write as _,
+    io,
+    ops::ControlFlow,
+    time::{Duration, Instant},
+};
+
+use anyhow::{Result, bail};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::{
+    DefaultTerminal, Frame, Terminal,
+    backend::{CrosstermBackend, TestBackend},
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::Stylize,
+    text::{Line, Span, Text},
+    widgets::{Block, Clear, Paragraph},
+};
+
+use crate::{answer::Answer, terminal::TerminalGuard};
+
+use headless::{HeadlessOptions, Script, ScriptEvent, Size};
+use record::Recorder;
+use rewind::Rewind;
+use scene::Scene;
+use web::WebOptions;
+
+pub(crate) mod desktop;
+pub(crate) mod grid;
+pub(crate) mod gtk_host;
+pub(crate) mod headless;
+pub(crate) mod record;
+mod rewind;
+pub(crate) mod scene;
+pub(crate) mod web;
+
+pub(crate) trait Visualizer {
+    /// Advance the algorithm by one step, returning `false` if it's finished.
+    fn step(&mut self) -> bool;
+
+    /// Undo one step, returning `false` if that isn't supported.
+    fn step_back(&mut self) -> bool {
+        false
+    }
+
+    /// Advance up to `n` steps, returning how many were taken. Override this when skipping ahead is
+    /// cheaper than taking each step.
+    fn step_n(&mut self, n: u64) -> u64 {
+        (0..n).take_while(|_| self.step()).count() as u64
+    }
+
+    /// Draw the visualization in the terminal, by default its [`Scene`] on a canvas.
+    fn render(&mut self, frame: &mut Frame, area: Rect) {
+        if let Some(scene) = self.scene() {
+            scene.render(frame, area);
+        }
+    }
+
+    /// What to draw in the browser or a window. Visualizers without a scene are shown as text.
+    fn scene(&self) -> Option<Scene> {
+        None
+    }
+
+    /// Anything worth knowing about a cell of the scene, shown when it's pointed at.
+    fn inspect(&self, _row: usize, _col: usize) -> Option<String> {
+        None
+    }
+
+    /// Whether the algorithm has found the answer.
+    fn is_solved(&self) -> bool {
+        false
+    }
+
+    /// Labelled values to show in the status bar.
+    fn status(&self) -> Vec<(&'static str, String)> {
+        Vec::new()
+    }
+
+    /// Messages to show in a panel below the visualization.
+    fn logs(&self) -> Option<&Logs> {
+        None
+    }
+
+    /// Keybindings of the visualizer's own, shown in the help after the driver's.
+    fn help(&self) -> &'static [(&'static str, &'static str)] {
+        &[]
+    }
+
+    /// Handle an event before the driver does, returning `true` if it was consumed.
+    fn handle_event(&mut self, _event: &Event) -> bool {
+        false
+    }
+
+    /// The answer shown by the visualization after `step` steps, if there is one.
+    fn answer(&self, _step: u64) -> Option<Answer> {
+        None
+    }
+}
+
+/// Options for running a visualization, passed to every visualizer.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct VizContext {
+    /// Skip ahead this many steps before showing the visualization. Visualizers may pick a step
+    /// of their own to start at when this isn't given.
+    pub(crate) start_step: Option<u64>,
+    /// Start playing immediately.
+    pub(crate) autoplay: bool,
+    /// Steps per second during playback.
+    pub(crate) fps: Option<u32>,
+    /// After skipping to the start step, skip ahead until the visualization is solved.
+    pub(crate) until_solved: bool,
+}
+
+/// The messages of a visualizer, keeping only the most recent.
+#[derive(Clone, Default)]
+pub(crate) struct Logs(VecDeque<String>);
+
+impl Logs {
+    const CAPACITY: usize = 4096;
+
+    pub(crate) fn push(&mut self, log: impl Into<String>) {
+        if self.0.len() >= Self::CAPACITY {
+            self.0.pop_front();
+        }
+        self.0.push_back(log.into());
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub(crate) fn iter(&self) -> impl DoubleEndedIterator<Item = &str> {
+        self.0.iter().map(String::as_str)
+    }
+}
+
+const FRAME_INTERVAL: Duration = Duration::from_millis(33);
+const IDLE_INTERVAL: Duration = Duration::from_millis(250);
+
+const DEFAULT_SPEED: u32 = 8;
+pub(crate) const MAX_SPEED: u32 = 1 << 14;
+
+/// How many of the latest logs are sent to the browser.
+const WEB_LOGS: usize = 200;
+
+const SMALL_JUMP: u64 = 100;
+const LARGE_JUMP: u64 = 1000;
+
+const HELP: &[(&str, &str)] = &[
+    ("space →", "step forward"),
+    ("bksp ←", "step back"),
+    ("0-9", "repeat the next step"),
+    ("n N", "forward 100 / 1000"),
+    ("b B", "back 100 / 1000"),
+    ("m", "bookmark the step"),
+    ("[ ]", "previous / next bookmark"),
+    ("g", "go to the repeat count's step, e.g. 250g"),
+    ("G", "go to the step the answer was found"),
+    ("p", "play / pause"),
+    ("+ -", "faster / slower"),
+    ("pgup pgdn", "scroll logs, shift for 10"),
+    ("home end", "first / latest logs"),
+    ("enter", "quit with the answer"),
+    ("q esc", "quit"),
+    ("?", "toggle this help"),
+];
+
+/// Whether visualizers that open a GTK window of their own should run in the [`Driver`] instead,
+/// because the visualization is being served to the browser, recorded, shown in the scene window
+/// or run headless. Their own windows ignore the driver's options, so those are rejected there.
+pub(crate) fn use_driver(ctx: &VizContext) -> Result<bool> {
+    if web::options().is_some()
+        || headless::options().is_some()
+        || record::options().is_some()
+        || desktop::options().is_some()
+    {
+        return Ok(true);
+    }
+
+    let VizContext {
+        start_step,
+        autoplay,
+        fps,
+        until_solved,
+    } = ctx;
+    if start_step.is_some() || *autoplay || fps.is_some() || *until_solved {
+        bail!(
+            "this visualizer only takes --start-step, --autoplay, --fps and --until-solved with \
+            --web, --gtk, --record or --script"
+        );
+    }
+    Ok(false)
+}
+
+/// Runs a [`Visualizer`] in the terminal.
+pub(crate) struct Driver<V> {
+    visualizer: V,
+    start_step: u64,
+    until_solved: bool,
+    step: u64,
+    finished: bool,
+    playing: bool,
+    /// Steps per second during playback.
+    speed: u32,
+    last_advance: Instant,
+    /// Fractional steps owed to playback since the last advance.
+    step_budget: f64,
+    count: Option<u64>,
+    /// Snapshots for stepping back, if the visualizer can't itself.
+    rewind: Option<Rewind<V>>,
+    /// The furthest step reached.
+    furthest: u64,
+    /// The earliest step the visualizer was seen to be solved at.
+    solved_at: Option<u64>,
+    bookmarks: BTreeSet<u64>,
+    /// `None` follows the latest logs.
+    logs_scroll: Option<usize>,
+    show_help: bool,
+    message: Option<String>,
+}
+
+impl<V: Visualizer> Driver<V> {
+    pub(crate) fn new(visualizer: V, ctx: &VizContext) -> Self {
+        Self {
+            visualizer,
+            start_step: ctx.start_step.unwrap_or_default(),
+            until_solved: ctx.until_solved,
+            step: 0,
+            finished: false,
+            playing: ctx.autoplay,
+            speed: ctx.fps.unwrap_or(DEFAULT_SPEED).clamp(1, MAX_SPEED),
+            last_advance: Instant::now(),
+            step_budget: 0.0,
+            count: None,
+            rewind: None,
+            furthest: 0,
+            solved_at: None,
+            bookmarks: BTreeSet::new(),
+            logs_scroll: None,
+            show_help: false,
+            message: None,
+        }
+    }
+
+    /// Take snapshots of the visualizer as it steps, so it can be rewound to any earlier step and
+    /// scrubbed through on the timeline.
+    pub(crate) fn with_rewind(mut self) -> Self
+    where
+        V: Clone,
+    {
+        self.rewind = Some(Rewind::new(&self.visualizer, V::clone));
+        self
+    }
+
+    /// Bookmark steps worth jumping to, such as candidates for the answer found ahead of time.
+    pub(crate) fn with_bookmarks(mut self, steps: impl IntoIterator<Item = u64>) -> Self {
+        self.bookmarks.extend(steps);
+        self
+    }
+
+    /// Run the visualization until it's quit, returning its answer if it was quit with enter.
+    pub(crate) fn run(mut self) -> Result<Option<Answer>> {
+        self.step_forward(self.start_step);
+        if self.until_solved {
+            while !self.finished && !self.visualizer.is_solved() {
+                self.step_forward(1);
+            }
+        }
+        self.last_advance = Instant::now();
+
+        let mut recorder = record::options().map(Recorder::create).transpose()?;
+
+        let result = if let Some(options) = headless::options() {
+            self.run_headless(options, recorder.as_mut())
+        } else if let Some(options) = web::options() {
+            self.run_web(options, recorder.as_mut())
+        } else if let Some(options) = desktop::options() {
+            self.run_desktop(options, recorder.as_mut())
+        } else {
+            let guard = TerminalGuard::fullscreen()?;
+            let mut terminal = Terminal::new(CrosstermBackend::new(io::stdout()))?;
+            let result = self.run_loop(&mut terminal, recorder.as_mut());
+            drop(guard);
+            result
+        };
+
+        if let Some(recorder) = recorder {
+            let path = recorder.path().to_path_buf();
+            let n_frames = recorder.finish()?;
+            println!("Recorded {n_frames} frames to {}", path.display());
+        }
+
+        result
+    }
+
+    fn run_loop(
+        &mut self,
+        terminal: &mut DefaultTerminal,
+        mut recorder: Option<&mut Recorder>,
+    ) -> Result<Option<Answer>> {
+        loop {
+            let completed = terminal.draw(|frame| self.draw(frame))?;
+            if let Some(recorder) = recorder.as_mut() {
+                recorder.capture(self.step, completed.buffer)?;
+            }
+
+            let timeout = if self.playing {
+                FRAME_INTERVAL.saturating_sub(self.last_advance.elapsed())
+            } else {
+                IDLE_INTERVAL
+            };
+            if event::poll(timeout)? {
+                let event = event::read()?;
+                if !self.visualizer.handle_event(&event)
+                    && let Event::Key(key) = event
+                    && key.kind == KeyEventKind::Press
+                    && let ControlFlow::Break(answer) = self.handle_key(key)
+                {
+                    return Ok(answer);
+                }
+            }
+
+            if self.playing {
+                let now = Instant::now();
+                self.advance_playback(now.duration_since(self.last_advance));
+                self.last_advance = now;
+            }
+        }
+    }
+
+    /// Serve the visualization until it's quit from the browser.
+    fn run_web(
+        &mut self,
+        options: &WebOptions,
+        mut recorder: Option<&mut Recorder>,
+    ) -> Result<Option<Answer>> {
+        let server = web::Server::start(options.addr)?;
+        println!("Serving the visualization at http://{}", server.addr());
+
+        // Visualizers without a scene are sent as the text they'd show in the terminal.
+        let size = Size::default();
+        let mut terminal = Terminal::new(TestBackend::new(size.width, size.height))?;
+
+        loop {
+            let completed = terminal.draw(|frame| self.draw(frame))?;
+            if let Some(recorder) = recorder.as_mut() {
+                recorder.capture(self.step, completed.buffer)?;
+            }
+            server.publish(self.web_frame(completed.buffer));
+
+            let timeout = if self.playing {
+                FRAME_INTERVAL.saturating_sub(self.last_advance.elapsed())
+            } else {
+                IDLE_INTERVAL
+            };
+            if let Some(key) = server.next_key(timeout)?
+                && !self.visualizer.handle_event(&Event::Key(key))
+                && let ControlFlow::Break(answer) = self.handle_key(key)
+            {
+                server.close();
+                return Ok(answer);
+            }
+
+            if self.playing {
+                let now = Instant::now();
+                self.advance_playback(now.duration_since(self.last_advance));
+                self.last_advance = now;
+            }
+        }
+    }
+
+    /// The JSON sent to the browser for the current state.
+    fn web_frame(&self, text: &Buffer) -> String {
+        let mut json = format!(
+            r#"{{"step": {}, "finished": {}, "playing": {}, "speed": {}, "status": ["#,
+            self.step, self.finished, self.playing, self.speed
+        );
+        for (i, (label, value)) in self.visualizer.status().into_iter().enumerate() {
+            if i > 0 {
+                json.push_str(", ");
+            }
+            write!(json, "[{}, {}]", json_string(label), json_string(&value)).unwrap();
+        }
+
+        json.push_str(r#"], "message": "#);
+        match &self.message {
+            Some(message) => json.push_str(&json_string(message)),
+            None => json.push_str("null"),
+        }
+
+        json.push_str(r#", "timeline": "#);
+        if self.has_timeline() {
+            let bookmarks = self
+                .bookmarks
+                .iter()
+                .map(u64::to_string)
+                .collect::<Vec<_>>();
+            let solved = self
+                .solved_at
+                .map_or("null".to_owned(), |step| step.to_string());
+            write!(
+                json,
+                r#"{{"end": {}, "solved": {solved}, "bookmarks": [{}]}}"#,
+                self.timeline_end(),
+                bookmarks.join(", ")
+            )
+            .unwrap();
+        } else {
+            json.push_str("null");
+        }
+
+        json.push_str(r#", "logs": ["#);
+        if let Some(logs) = self.visualizer.logs() {
+            let skip = logs.len().saturating_sub(WEB_LOGS);
+            let logs = logs.iter().skip(skip).map(json_string).collect::<Vec<_>>();
+            json.push_str(&logs.join(", "));
+        }
+        json.push(']');
+
+        match self.visualizer.scene() {
+            Some(scene) => write!(json, r#", "scene": {}, "text": null}}"#, scene.to_json()),
+            None => write!(
+                json,
+                r#", "scene": null, "text": {}}}"#,
+                json_string(&headless::frame_text(text))
+            ),
+        }
+        .unwrap();
+
+        json
+    }
+
+    /// Run the script, dumping the last frame, and every `dump_every` frames, to stdout.
+    fn run_headless(
+        &mut self,
+        options: &HeadlessOptions,
+        mut recorder: Option<&mut Recorder>,
+    ) -> Result<Option<Answer>> {
+        let mut last_dumped = None;
+        let (answer, i, frame) =
+            self.run_script(&options.script, options.size, |i, step, frame| {
+                if let Some(recorder) = recorder.as_mut() {
+                    recorder.capture(step, frame)?;
+                }
+                if options.dump_every.is_some_and(|n| i % n == 0) {
+                    dump_frame(i, step, frame);
+                    last_dumped = Some(i);
+                }
+                Ok(())
+            })?;
+
+        if last_dumped != Some(i) {
+            dump_frame(i, self.step, &frame);
+        }
+
+        Ok(answer)
+    }
+
+    /// Draw a frame for each event of the script into an in-memory terminal, calling `on_frame`
+    /// with the frame's index and step. Returns the answer, if the script quit with one, and the
+    /// index of the last frame and the frame itself.
+    pub(crate) fn run_script(
+        &mut self,
+        script: &Script,
+        size: Size,
+        mut on_frame: impl FnMut(u64, u64, &Buffer) -> Result<()>,
+    ) -> Result<(Option<Answer>, u64, Buffer)> {
+        let mut terminal = Terminal::new(TestBackend::new(size.width, size.height))?;
+        let mut events = script.events();
+        let mut answer = None;
+        let mut i = 0;
+
+        loop {
+            let completed = terminal.draw(|frame| self.draw(frame))?;
+            on_frame(i, self.step, completed.buffer)?;
+
+            let Some(event) = events.next() else {
+                break;
+            };
+            match event {
+                ScriptEvent::Key(key) => {
+                    if !self.visualizer.handle_event(&Event::Key(key))
+                        && let ControlFlow::Break(quit_answer) = self.handle_key(key)
+                    {
+                        answer = quit_answer;
+                        break;
+                    }
+                }
+                ScriptEvent::Tick if self.playing => self.advance_playback(FRAME_INTERVAL),
+                ScriptEvent::Tick => {}
+            }
+            i += 1;
+        }
+
+        Ok((answer, i, terminal.backend().buffer().clone()))
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> ControlFlow<Option<Answer>> {
+        self.message = None;
+        let count = self.count.take();
+
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                return ControlFlow::Break(None);
+            }
+            KeyCode::Esc if count.is_some() => {}
+            KeyCode::Char('q') | KeyCode::Esc => return ControlFlow::Break(None),
+            KeyCode::Enter => return ControlFlow::Break(self.visualizer.answer(self.step)),
+            KeyCode::Char(c @ '0'..='9') => {
+                let digit = c.to_digit(10).unwrap_or_default() as u64;
+                self.count = Some(
+                    count
+                        .unwrap_or_default()
+                        .saturating_mul(10)
+                        .saturating_add(digit),
+                );
+            }
+            KeyCode::Char(' ') | KeyCode::Right => self.step_forward(count.unwrap_or(1)),
+            KeyCode::Backspace | KeyCode::Left => self.step_backward(count.unwrap_or(1)),
+            KeyCode::Char('n') => self.step_forward(SMALL_JUMP),
+            KeyCode::Char('N') => self.step_forward(LARGE_JUMP),
+            KeyCode::Char('b') => self.step_backward(SMALL_JUMP),
+            KeyCode::Char('B') => self.step_backward(LARGE_JUMP),
+            KeyCode::Char('m') => {
+                if !self.bookmarks.remove(&self.step) {
+                    self.bookmarks.insert(self.step);
+                }
+            }
+            KeyCode::Char('[') => match self.bookmarks.range(..self.step).next_back() {
+                Some(&step) => self.seek(step),
+                None => self.message = Some("no bookmark before this step".to_owned()),
+            },
+            KeyCode::Char(']') => match self.bookmarks.range(self.step + 1..).next() {
+                Some(&step) => self.seek(step),
+                None => self.message = Some("no bookmark after this step".to_owned()),
+            },
+            KeyCode::Char('g') => self.seek(count.unwrap_or_default()),
+            KeyCode::Char('G') => match self.solved_at {
+                Some(step) => self.seek(step),
+                None => self.message = Some("the answer hasn't been found yet".to_owned()),
+            },
+            KeyCode::Char('p') => self.toggle_playback(),
+            KeyCode::Char('+' | '=') => self.speed = (self.speed * 2).min(MAX_SPEED),
+            KeyCode::Char('-') => self.speed = (self.speed / 2).max(1),
+            KeyCode::PageUp | KeyCode::PageDown | KeyCode::Home | KeyCode::End => {
+                self.scroll_logs(key)
+            }
+            KeyCode::Char('?') => self.show_help = !self.show_help,
+            _ => {}
+        }
+
+        ControlFlow::Continue(())
+    }
+
+    fn step_forward(&mut self, n: u64) {
+        let mut remaining = n;
+        while remaining > 0 && !self.finished {
+            // Stop at each step a snapshot is due.
+            let chunk = match &self.rewind {
+                Some(rewind) => remaining.min(rewind.steps_to_next(self.step)),
+                None => remaining,
+            };
+            let start = self.step;
+            let was_unsolved =
+                self.solved_at.is_none_or(|step| step > start) && !self.visualizer.is_solved();
+            let taken = self.visualizer.step_n(chunk);
+            self.step += taken;
+            remaining -= taken;
+            if taken < chunk {
+                self.finished = true;
+                self.playing = false;
+            }
+            if let Some(rewind) = &mut self.rewind {
+                rewind.save(self.step, &self.visualizer);
+            }
+            if self.visualizer.is_solved() {
+                let step = if was_unsolved && taken > 1 {
+                    self.first_solved_step(start)
+                } else {
+                    self.step
+                };
+                self.solved_at = Some(self.solved_at.map_or(step, |at| at.min(step)));
+            }
+        }
+
+        self.furthest = self.furthest.max(self.step);
+    }
+
+    /// The first step after `start` the visualizer is solved at, when it wasn't at `start` but is
+    /// now, found by replaying the steps in between one at a time.
+    fn first_solved_step(&mut self, start: u64) -> u64 {
+        let end = self.step;
+        if let Some(rewind) = &self.rewind {
+            let (at, mut visualizer) = rewind.restore(start);
+            visualizer.step_n(start - at);
+            return (start + 1..end)
+                .find(|_| visualizer.step() && visualizer.is_solved())
+                .unwrap_or(end);
+        }
+
+        // Without snapshots, walk back for as long as it stays solved, if the visualizer can.
+        let mut first = end;
+        let mut at = end;
+        while first > start + 1 && self.visualizer.step_back() {
+            at -= 1;
+            if !self.visualizer.is_solved() {
+                break;
+            }
+            first = at;
+        }
+        self.visualizer.step_n(end - at);
+        first
+    }
+
+    fn step_backward(&mut self, n: u64) {
+        if self.rewind.is_some() {
+            self.seek(self.step.saturating_sub(n));
+            return;
+        }
+
+        for _ in 0..n.min(self.step) {
+            if !self.visualizer.step_back() {
+                self.message = Some("this visualization can't step back".to_owned());
+                return;
+            }
+            self.step -= 1;
+            self.finished = false;
+        }
+    }
+
+    /// Go to a step, replaying from the nearest snapshot before it if it's behind.
+    fn seek(&mut self, step: u64) {
+        if step >= self.step {
+            self.step_forward(step - self.step);
+            return;
+        }
+
+        let Some(rewind) = &self.rewind else {
+            self.step_backward(self.step - step);
+            return;
+        };
+        let (at, visualizer) = rewind.restore(step);
+        self.visualizer = visualizer;
+        self.step = at;
+        self.finished = false;
+        self.step_forward(step - at);
+    }
+
+    fn toggle_playback(&mut self) {
+        self.playing = !self.playing && !self.finished;
+        self.last_advance = Instant::now();
+        self.step_budget = 0.0;
+    }
+
+    fn advance_playback(&mut self, elapsed: Duration) {
+        self.step_budget += elapsed.as_secs_f64() * self.speed as f64;
+        let n = self.step_budget as u64;
+        self.step_budget -= n as f64;
+        self.step_forward(n);
+    }
+
+    fn scroll_logs(&mut self, key: KeyEvent) {
+        let n_logs = self.visualizer.logs().map_or(0, Logs::len);
+        let last = n_logs.saturating_sub(1);
+        let amount = if key.modifiers.contains(KeyModifiers::SHIFT) {
+            10
+        } else {
+            1
+        };
+
+        self.logs_scroll = match key.code {
+            KeyCode::PageUp => Some(self.logs_scroll.unwrap_or(last).saturating_sub(amount)),
+            KeyCode::PageDown => self
+                .logs_scroll
+                .map(|offset| offset + amount)
+                .filter(|&offset| offset < last),
+            KeyCode::Home => Some(0),
+            _ => None,
+        };
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let logs_height = match self.visualizer.logs() {
+            Some(logs) if !logs.is_empty() => (frame.area().height / 4).clamp(3, 12),
+            _ => 0,
+        };
+        let timeline_height = self.has_timeline() as u16;
+        let [main_area, logs_area, timeline_area, status_area] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(logs_height),
+            Constraint::Length(timeline_height),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        self.visualizer.render(frame, main_area);
+
+        if let Some(logs) = self.visualizer.logs()
+            && logs_height > 0
+        {
+            draw_logs(frame, logs_area, logs, self.logs_scroll);
+        }
+
+        if timeline_height > 0 {
+            frame.render_widget(self.timeline(timeline_area.width), timeline_area);
+        }
+        frame.render_widget(self.status_line(), status_area);
+
+        if self.show_help {
+            draw_help(frame, self.visualizer.help());
+        }
+    }
+
+    /// Whether there's anything to show on a timeline: steps to scrub through, or to jump to.
+    fn has_timeline(&self) -> bool {
+        self.rewind.is_some() || !self.bookmarks.is_empty() || self.solved_at.is_some()
+    }
+
+    /// The last step shown on the timeline.
+    fn timeline_end(&self) -> u64 {
+        let last_bookmark = self.bookmarks.last().copied().unwrap_or_default();
+        self.furthest
+            .max(self.solved_at.unwrap_or_default())
+            .max(last_bookmark)
+            .max(1)
+    }
+
+    /// A bar of the steps taken so far, marking the current step, bookmarks and the step the
+    /// answer was found.
+    fn timeline(&self, width: u16) -> Line<'static> {
+        let end = self.timeline_end();
+        let label = format!(" {end}");
+        let bar_width = (width as usize).saturating_sub(label.len() + 1).max(1);
+        let x = |step: u64| (step as f64 / end as f64 * (bar_width - 1) as f64).round() as usize;
+
+        let mut bar = vec!["─".dark_gray(); bar_width];
+        for &step in &self.bookmarks {
+            bar[x(step)] = "◆".yellow();
+        }
+        if let Some(step) = self.solved_at {
+            bar[x(step)] = "★".green();
+        }
+        bar[x(self.step)] = "●".bold();
+
+        let mut spans = vec![Span::raw(" ")];
+        spans.extend(bar);
+        spans.push(label.dim());
+        Line::from(spans)
+    }
+
+    fn status_line(&self) -> Line<'static> {
+        let mut spans = vec![
+            format!(" Step {} ", self.step).bold().reversed(),
+            Span::raw(" "),
+        ];
+
+        let playback = if self.finished {
+            "finished".green()
+        } else if self.playing {
+            format!("▶ {}/s", self.speed).into()
+        } else {
+            format!("⏸ {}/s", self.speed).dim()
+        };
+        spans.push(playback);
+
+        for (label, value) in self.visualizer.status() {
+            spans.push(Span::raw(format!("  {label}: {value}")));
+        }
+        if let Some(count) = self.count {
+            spans.push(format!("  ×{count}").yellow());
+        }
+        if let Some(message) = &self.message {
+            spans.push(format!("  {message}").red());
+        }
+        spans.push("  ? help".dim());
+
+        Line::from(spans)
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut json = String::with_capacity(s.len() + 2);
+    json.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            c if c.is_control() => write!(json, "\\u{:04x}", c as u32).unwrap(),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+fn dump_frame(i: u64, step: u64, frame: &Buffer) {
+    println!("--- frame {i}, step {step} ---");
+    print!("{}", headless::frame_text(frame));
+}
+
+fn draw_logs(frame: &mut Frame, area: Rect, logs: &Logs, scroll: Option<usize>) {
+    let block = Block::bordered().title(" Logs ");
+    let height = block.inner(area).height as usize;
+    let offset = scroll.unwrap_or_else(|| logs.len().saturating_sub(height));
+
+    let text = logs.iter().skip(offset).take(height).collect::<Text>();
+    frame.render_widget(Paragraph::new(text).block(block), area);
+}
+
+fn draw_help(frame: &mut Frame, extra: &[(&str, &str)]) {
+    let help = || HELP.iter().chain(extra);
+    let key_width = help().map(|(keys, _)| keys.len()).max().unwrap_or(0);
+    let text = help()
+        .map(|(keys, action)| {
+            Line::from(vec![
+                format!("{keys:key_width$}  ").bold(),
+                (*action).into(),
+            ])
+        })
+        .collect::<Text>();
+
+    let area = frame.area();
+    let width = (text.width() as u16 + 4).min(area.width);
+    let height = (text.height() as u16 + 2).min(area.height);
+    let popup = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    );
+
+    frame.render_widget(Clear, popup);
+    frame.render_widget(
+        Paragraph::new(text).block(Block::bordered().title(" Keys ")),
+        popup,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::{Frame, layout::Rect, widgets::Paragraph};
+
+    use super::{Driver, Visualizer, VizContext, headless};
+    use crate::answer::Answer;
+
+    /// Counts up to a limit.
+    struct Counter {
+        n: u64,
+        limit: u64,
+    }
+
+    impl Visualizer for Counter {
+        fn step(&mut self) -> bool {
+            if self.n == self.limit {
+                return false;
+            }
+            self.n += 1;
+            true
+        }
+
+        fn step_back(&mut self) -> bool {
+            self.n -= 1;
+            true
+        }
+
+        fn render(&mut self, frame: &mut Frame, area: Rect) {
+            frame.render_widget(Paragraph::new(format!("n = {}", self.n)), area);
+        }
+
+        fn is_solved(&self) -> bool {
+            self.n >= 100
+        }
+
+        fn answer(&self, _step: u64) -> Option<Answer> {
+            Some(self.n.into())
+        }
+    }
+
+    #[test]
+    fn step_forward_and_back() {
+        let mut driver = Driver::new(Counter { n: 0, limit: 150 }, &VizContext::default());
+
+        driver.step_forward(100);
+        assert_eq!(
+            (100, 100, false),
+            (driver.step, driver.visualizer.n, driver.finished)
+        );
+
+        driver.step_forward(100);
+        assert_eq!(
+            (150, 150, true),
+            (driver.step, driver.visualizer.n, driver.finished)
+        );
+
+        driver.step_backward(1000);
+        assert_eq!(
+            (0, 0, false),
+            (driver.step, driver.visualizer.n, driver.finished)
+        );
+    }
+
+    #[test]
+    fn timeline_without_rewind() {
+        let mut driver =
+            Driver::new(Counter { n: 0, limit: 150 }, &VizContext::default()).with_bookmarks([50]);
+        let (_, _, frame) = driver
+            .run_script(
+                &"N,enter".parse().unwrap(),
+                "24x3".parse().unwrap(),
+                |_, _, _| Ok(()),
+            )
+            .unwrap();
+
+        assert_eq!(Some(100), driver.solved_at);
+        assert_eq!(
+            "n = 150\n ──────◆─────★─────● 150\n Step 150  finished  ? h\n",
+            headless::frame_text(&frame)
+        );
+    }
+
+    /// Counts up to a limit, but can't count back down.
+    #[derive(Clone)]
+    struct Tally {
+        n: u64,
+        limit: u64,
+    }
+
+    impl Visualizer for Tally {
+        fn step(&mut self) -> bool {
+            if self.n == self.limit {
+                return false;
+            }
+            self.n += 1;
+            true
+        }
+
+        fn render(&mut self, frame: &mut Frame, area: Rect) {
+            frame.render_widget(Paragraph::new(format!("n = {}", self.n)), area);
+        }
+
+        fn is_solved(&self) -> bool {
+            self.n >= 100
+        }
+
+        fn answer(&self, _step: u64) -> Option<Answer> {
+            Some(self.n.into())
+        }
+    }
+
+    #[test]
+    fn rewind_and_bookmarks() {
+        let mut driver =
+            Driver::new(Tally { n: 0, limit: 150 }, &VizContext::default()).with_rewind();
+        let mut steps = Vec::new();
+
+        let script = "N,G,1,0,0,g,m,g,],5,0,bksp,enter".parse().unwrap();
+        let (answer, _, frame) = driver
+            .run_script(&script, "24x3".parse().unwrap(), |_, step, _| {
+                steps.push(step);
+                Ok(())
+            })
+            .unwrap();
+
+        // Jumping past the answer still finds the step it was first seen at.
+        assert_eq!(
+            vec![0, 150, 100, 100, 100, 100, 100, 100, 0, 100, 100, 100, 50],
+            steps
+        );
+        assert_eq!(Some(100), driver.solved_at);
+        assert_eq!(Some(50_u64.into()), answer);
+        assert_eq!(
+            "n = 50\n ──────●─────★────── 150\n Step 50  ⏸ 8/s  ? help\n",
+            headless::frame_text(&frame)
+        );
+    }
+
+    #[test]
+    fn runs_a_script_headless() {
+        let size = "24x3".parse().unwrap();
+        let mut frames = Vec::new();
+
+        let script = "space*3,1,2,space,bksp,p,tick*16,enter".parse().unwrap();
+        let mut driver = Driver::new(Counter { n: 0, limit: 150 }, &VizContext::default());
+        let (answer, i, frame) = driver
+            .run_script(&script, size, |i, step, _| {
+                frames.push((i, step));
+                Ok(())
+            })
+            .unwrap();
+
+        assert_eq!(Some(18_u64.into()), answer);
+        assert_eq!(24, i);
+        assert_eq!(
+            vec![
+                (0, 0),
+                (1, 1),
+                (2, 2),
+                (3, 3),
+                (4, 3),
+                (5, 3),
+                (6, 15),
+                (7, 14)
+            ],
+            frames[..8]
+        );
+        assert_eq!(
+            "n = 18\n\n Step 18  ▶ 8/s  ? help\n",
+            headless::frame_text(&frame)
+        );
+    }
+}
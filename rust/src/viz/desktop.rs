@@ -0,0 +1,519 @@
+//! Show a visualization in a GTK window, for when there's a desktop session.
+//!
+//! The window draws the visualizer's scene, which can be zoomed by scrolling and panned by
+//! dragging, and inspects the cell under the pointer. Visualizers without a scene, and the help,
+//! are shown as the text they'd show in the terminal. The [`gtk_host`](super::gtk_host) opens the
+//! same window for visualizers that draw themselves.
+
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    ops::ControlFlow,
+    rc::Rc,
+    sync::OnceLock,
+    thread,
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, Result, bail};
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+use gtk::{cairo, gdk, glib, prelude::*};
+use ratatui::{Terminal, backend::TestBackend, buffer::Buffer};
+
+use crate::answer::Answer;
+
+use super::{
+    Driver, FRAME_INTERVAL, IDLE_INTERVAL, Visualizer,
+    headless::{self, Size},
+    record::Recorder,
+    scene::{Rgba, Scene, Shape},
+};
+
+static OPTIONS: OnceLock<DesktopOptions> = OnceLock::new();
+
+/// How long to sleep between runs of the GTK main loop while waiting for input.
+const POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+const ZOOM_STEP: f64 = 1.25;
+const MAX_ZOOM: f64 = 64.0;
+
+/// The height of labels, in cells.
+const LABEL_SIZE: f64 = 0.6;
+
+/// The width of the outline of the inspected cell, in pixels.
+const INSPECTOR_WIDTH: f64 = 2.0;
+
+pub(crate) struct DesktopOptions {
+    /// The initial size of the window, in pixels.
+    pub(crate) size: Size,
+}
+
+/// Show the next visualization that runs in a window.
+pub(crate) fn enable(options: DesktopOptions) -> Result<()> {
+    if OPTIONS.set(options).is_err() {
+        bail!("desktop mode is already enabled");
+    }
+    Ok(())
+}
+
+pub(super) fn options() -> Option<&'static DesktopOptions> {
+    OPTIONS.get()
+}
+
+pub(super) enum WindowEvent {
+    Key(KeyEvent),
+    /// The pointer moved, or the view was zoomed or panned.
+    Pointer,
+    Closed,
+}
+
+/// Where the scene is drawn in the drawing area.
+pub(super) struct View {
+    zoom: f64,
+    /// The offset from the centered scene, in pixels.
+    pan: (f64, f64),
+    drag_start: (f64, f64),
+    /// The size of the drawing area when it was last drawn.
+    pub(super) area: (f64, f64),
+    pointer: Option<(f64, f64)>,
+}
+
+impl Default for View {
+    fn default() -> Self {
+        Self {
+            zoom: 1.0,
+            pan: (0.0, 0.0),
+            drag_start: (0.0, 0.0),
+            area: (0.0, 0.0),
+            pointer: None,
+        }
+    }
+}
+
+impl View {
+    /// The size of a cell in pixels, and where the origin of a scene of `width` by `height` cells
+    /// is drawn.
+    pub(super) fn transform(&self, (width, height): (f64, f64)) -> (f64, (f64, f64)) {
+        let (area_width, area_height) = self.area;
+        let scale = (area_width / width).min(area_height / height) * self.zoom;
+        let origin = (
+            (area_width - width * scale) / 2.0 + self.pan.0,
+            (area_height - height * scale) / 2.0 + self.pan.1,
+        );
+        (scale, origin)
+    }
+
+    /// The cell under a point in the drawing area, as `(row, col)`.
+    fn cell_at(&self, (width, height): (f64, f64), (x, y): (f64, f64)) -> Option<(usize, usize)> {
+        let (scale, (x0, y0)) = self.transform((width, height));
+        let (col, row) = ((x - x0) / scale, (y - y0) / scale);
+        ((0.0..width).contains(&col) && (0.0..height).contains(&row))
+            .then_some((row as usize, col as usize))
+    }
+
+    /// Zoom by `factor`, keeping the point under the pointer where it is.
+    fn zoom_by(&mut self, size: (f64, f64), factor: f64) {
+        let (scale, (x0, y0)) = self.transform(size);
+        let (x, y) = self
+            .pointer
+            .unwrap_or((self.area.0 / 2.0, self.area.1 / 2.0));
+        let (col, row) = ((x - x0) / scale, (y - y0) / scale);
+
+        self.zoom = (self.zoom * factor).clamp(1.0, MAX_ZOOM);
+
+        let (scale, (x0, y0)) = self.transform(size);
+        self.pan.0 += x - (x0 + col * scale);
+        self.pan.1 += y - (y0 + row * scale);
+    }
+}
+
+/// The state shared between whatever runs the window and its callbacks.
+#[derive(Default)]
+pub(super) struct Shared {
+    pub(super) scene: Option<Scene>,
+    /// The size of what's drawn, in cells, if it can be zoomed and inspected.
+    pub(super) size: Option<(f64, f64)>,
+    pub(super) view: View,
+    pub(super) events: VecDeque<WindowEvent>,
+}
+
+impl Shared {
+    /// The cell under the pointer, as `(row, col)`.
+    pub(super) fn inspected(&self) -> Option<(usize, usize)> {
+        self.view
+            .pointer
+            .and_then(|pointer| self.view.cell_at(self.size?, pointer))
+    }
+}
+
+pub(super) struct Window {
+    pub(super) window: gtk::Window,
+    pub(super) drawing_area: gtk::DrawingArea,
+    pub(super) text: gtk::Label,
+    pub(super) inspector: gtk::Label,
+    pub(super) status: gtk::Label,
+}
+
+impl Window {
+    /// Open a window with a drawing area of `width` by `height` pixels, drawn by `draw`.
+    pub(super) fn open(
+        title: &str,
+        (width, height): (i32, i32),
+        shared: &Rc<RefCell<Shared>>,
+        draw: impl Fn(&cairo::Context, &Shared) -> Result<()> + 'static,
+    ) -> Self {
+        let drawing_area = gtk::DrawingArea::builder()
+            .content_width(width)
+            .content_height(height)
+            .hexpand(true)
+            .vexpand(true)
+            .build();
+        let text = gtk::Label::builder()
+            .xalign(0.0)
+            .yalign(0.0)
+            .vexpand(true)
+            .visible(false)
+            .build();
+        text.add_css_class("monospace");
+        let inspector = gtk::Label::builder().xalign(0.0).build();
+        let status = gtk::Label::builder().xalign(0.0).build();
+
+        let layout = gtk::Box::builder()
+            .orientation(gtk::Orientation::Vertical)
+            .build();
+        layout.append(&drawing_area);
+        layout.append(&text);
+        layout.append(&inspector);
+        layout.append(&status);
+
+        let window = gtk::Window::builder()
+            .title(title)
+            .default_width(width)
+            .default_height(height)
+            .child(&layout)
+            .build();
+
+        drawing_area.set_draw_func({
+            let shared = Rc::clone(shared);
+            move |_, ctx, width, height| {
+                let mut shared = shared.borrow_mut();
+                shared.view.area = (width.into(), height.into());
+                ctx.set_source_rgb(0.0, 0.0, 0.0);
+                let drawn = ctx
+                    .paint()
+                    .map_err(Into::into)
+                    .and_then(|()| draw(ctx, &shared));
+                if let Err(err) = drawn {
+                    glib::g_warning!("viz", "failed to draw: {err}");
+                }
+            }
+        });
+
+        let keys = gtk::EventControllerKey::new();
+        keys.connect_key_pressed({
+            let shared = Rc::clone(shared);
+            move |_, key, _, modifiers| match key_event(key, modifiers) {
+                Some(key) => {
+                    shared.borrow_mut().events.push_back(WindowEvent::Key(key));
+                    glib::Propagation::Stop
+                }
+                None => glib::Propagation::Proceed,
+            }
+        });
+        window.add_controller(keys);
+
+        let motion = gtk::EventControllerMotion::new();
+        motion.connect_motion({
+            let shared = Rc::clone(shared);
+            move |_, x, y| {
+                let mut shared = shared.borrow_mut();
+                shared.view.pointer = Some((x, y));
+                shared.events.push_back(WindowEvent::Pointer);
+            }
+        });
+        motion.connect_leave({
+            let shared = Rc::clone(shared);
+            move |_| {
+                let mut shared = shared.borrow_mut();
+                shared.view.pointer = None;
+                shared.events.push_back(WindowEvent::Pointer);
+            }
+        });
+        drawing_area.add_controller(motion);
+
+        let scroll = gtk::EventControllerScroll::new(gtk::EventControllerScrollFlags::VERTICAL);
+        scroll.connect_scroll({
+            let shared = Rc::clone(shared);
+            move |_, _, dy| {
+                let mut shared = shared.borrow_mut();
+                if let Some(size) = shared.size {
+                    shared.view.zoom_by(size, ZOOM_STEP.powf(-dy));
+                }
+                shared.events.push_back(WindowEvent::Pointer);
+                glib::Propagation::Stop
+            }
+        });
+        drawing_area.add_controller(scroll);
+
+        let drag = gtk::GestureDrag::new();
+        drag.connect_drag_begin({
+            let shared = Rc::clone(shared);
+            move |_, _, _| {
+                let mut shared = shared.borrow_mut();
+                shared.view.drag_start = shared.view.pan;
+            }
+        });
+        drag.connect_drag_update({
+            let shared = Rc::clone(shared);
+            move |_, dx, dy| {
+                let mut shared = shared.borrow_mut();
+                let (x, y) = shared.view.drag_start;
+                shared.view.pan = (x + dx, y + dy);
+                shared.events.push_back(WindowEvent::Pointer);
+            }
+        });
+        drawing_area.add_controller(drag);
+
+        window.connect_close_request({
+            let shared = Rc::clone(shared);
+            move |_| {
+                shared.borrow_mut().events.push_back(WindowEvent::Closed);
+                glib::Propagation::Stop
+            }
+        });
+
+        window.present();
+
+        Self {
+            window,
+            drawing_area,
+            text,
+            inspector,
+            status,
+        }
+    }
+}
+
+impl<V: Visualizer> Driver<V> {
+    /// Show the visualization in a window until it's quit or the window is closed.
+    pub(super) fn run_desktop(
+        &mut self,
+        options: &DesktopOptions,
+        mut recorder: Option<&mut Recorder>,
+    ) -> Result<Option<Answer>> {
+        gtk::init().context("failed to initialize GTK")?;
+        let context = glib::MainContext::default();
+        let shared = Rc::new(RefCell::new(Shared::default()));
+        let size = (options.size.width.into(), options.size.height.into());
+        let window = Window::open(
+            "Advent of Code",
+            size,
+            &shared,
+            |ctx, shared| match &shared.scene {
+                Some(scene) => Ok(draw_scene(ctx, scene, &shared.view)?),
+                None => Ok(()),
+            },
+        );
+
+        let size = Size::default();
+        let mut terminal = Terminal::new(TestBackend::new(size.width, size.height))?;
+
+        let answer = 'run: loop {
+            let completed = terminal.draw(|frame| self.draw(frame))?;
+            if let Some(recorder) = recorder.as_mut() {
+                recorder.capture(self.step, completed.buffer)?;
+            }
+            let scene = self.visualizer.scene().filter(|_| !self.show_help);
+            self.show_in(&window, &mut shared.borrow_mut(), scene, completed.buffer);
+
+            let timeout = if self.playing {
+                FRAME_INTERVAL.saturating_sub(self.last_advance.elapsed())
+            } else {
+                IDLE_INTERVAL
+            };
+            let deadline = Instant::now() + timeout;
+            let events = loop {
+                while context.iteration(false) {}
+                let events = std::mem::take(&mut shared.borrow_mut().events);
+                if !events.is_empty() || Instant::now() >= deadline {
+                    break events;
+                }
+                thread::sleep(POLL_INTERVAL);
+            };
+
+            for event in events {
+                match event {
+                    WindowEvent::Key(key) => {
+                        if !self.visualizer.handle_event(&Event::Key(key))
+                            && let ControlFlow::Break(answer) = self.handle_key(key)
+                        {
+                            break 'run answer;
+                        }
+                    }
+                    WindowEvent::Pointer => {}
+                    WindowEvent::Closed => break 'run None,
+                }
+            }
+
+            if self.playing {
+                let now = Instant::now();
+                self.advance_playback(now.duration_since(self.last_advance));
+                self.last_advance = now;
+            }
+        };
+
+        window.window.destroy();
+        while context.iteration(false) {}
+
+        Ok(answer)
+    }
+
+    fn show_in(&self, window: &Window, shared: &mut Shared, scene: Option<Scene>, text: &Buffer) {
+        window.status.set_text(&self.status_line().to_string());
+
+        let Some(scene) = scene else {
+            shared.scene = None;
+            shared.size = None;
+            window.drawing_area.set_visible(false);
+            window.inspector.set_visible(false);
+            window.text.set_visible(true);
+            window.text.set_text(&headless::frame_text(text));
+            return;
+        };
+
+        shared.size = Some((scene.width, scene.height));
+        let inspector = match shared.inspected() {
+            Some((row, col)) => {
+                let mut line = format!("row {row}, col {col}");
+                let names = scene.layers_at(row, col);
+                if !names.is_empty() {
+                    line.push_str("  ");
+                    line.push_str(&names.join(", "));
+                }
+                if let Some(info) = self.visualizer.inspect(row, col) {
+                    line.push_str("  ");
+                    line.push_str(&info);
+                }
+                line
+            }
+            None => String::new(),
+        };
+
+        shared.scene = Some(scene);
+        window.text.set_visible(false);
+        window.drawing_area.set_visible(true);
+        window.drawing_area.queue_draw();
+        window.inspector.set_visible(true);
+        window.inspector.set_text(&inspector);
+    }
+}
+
+fn draw_scene(ctx: &cairo::Context, scene: &Scene, view: &View) -> Result<(), cairo::Error> {
+    let size = (scene.width, scene.height);
+    let (scale, (x0, y0)) = view.transform(size);
+
+    ctx.translate(x0, y0);
+    ctx.scale(scale, scale);
+
+    set_color(ctx, scene.background);
+    ctx.rectangle(0.0, 0.0, scene.width, scene.height);
+    ctx.fill()?;
+
+    for layer in &scene.layers {
+        set_color(ctx, layer.color);
+        match &layer.shape {
+            Shape::Cells(cells) => {
+                for &(row, col) in cells {
+                    ctx.rectangle(col as f64, row as f64, 1.0, 1.0);
+                }
+                ctx.fill()?;
+            }
+            Shape::Path { cells, width } => {
+                ctx.set_line_width(*width);
+                ctx.set_line_cap(cairo::LineCap::Square);
+                ctx.set_line_join(cairo::LineJoin::Miter);
+                for (i, &(row, col)) in cells.iter().enumerate() {
+                    let (x, y) = (col as f64 + 0.5, row as f64 + 0.5);
+                    if i == 0 {
+                        ctx.move_to(x, y);
+                    } else {
+                        ctx.line_to(x, y);
+                    }
+                }
+                ctx.stroke()?;
+            }
+            Shape::Rects(rects) => {
+                for &[x, y, width, height] in rects {
+                    ctx.rectangle(x, y, width, height);
+                }
+                ctx.fill()?;
+            }
+            Shape::Labels(labels) => {
+                ctx.select_font_face(
+                    "monospace",
+                    cairo::FontSlant::Normal,
+                    cairo::FontWeight::Bold,
+                );
+                ctx.set_font_size(LABEL_SIZE);
+                for ((row, col), text) in labels {
+                    let extents = ctx.text_extents(text)?;
+                    ctx.move_to(
+                        *col as f64 + 0.5 - extents.width() / 2.0 - extents.x_bearing(),
+                        *row as f64 + 0.5 - extents.height() / 2.0 - extents.y_bearing(),
+                    );
+                    ctx.show_text(text)?;
+                }
+            }
+        }
+    }
+
+    if let Some((row, col)) = view.pointer.and_then(|pointer| view.cell_at(size, pointer)) {
+        set_color(ctx, Rgba::WHITE);
+        ctx.set_line_width(INSPECTOR_WIDTH / scale);
+        ctx.rectangle(col as f64, row as f64, 1.0, 1.0);
+        ctx.stroke()?;
+    }
+
+    Ok(())
+}
+
+fn set_color(ctx: &cairo::Context, color: Rgba) {
+    ctx.set_source_rgba(
+        color.r as f64 / 255.0,
+        color.g as f64 / 255.0,
+        color.b as f64 / 255.0,
+        color.a.into(),
+    );
+}
+
+/// The key as the terminal would report it, if the driver or visualizers could use it.
+fn key_event(key: gdk::Key, state: gdk::ModifierType) -> Option<KeyEvent> {
+    let code = match key {
+        gdk::Key::Return | gdk::Key::KP_Enter => KeyCode::Enter,
+        gdk::Key::Escape => KeyCode::Esc,
+        gdk::Key::BackSpace => KeyCode::Backspace,
+        gdk::Key::Tab => KeyCode::Tab,
+        gdk::Key::Left => KeyCode::Left,
+        gdk::Key::Right => KeyCode::Right,
+        gdk::Key::Up => KeyCode::Up,
+        gdk::Key::Down => KeyCode::Down,
+        gdk::Key::Home => KeyCode::Home,
+        gdk::Key::End => KeyCode::End,
+        gdk::Key::Page_Up => KeyCode::PageUp,
+        gdk::Key::Page_Down => KeyCode::PageDown,
+        _ => KeyCode::Char(key.to_unicode().filter(|c| !c.is_control())?),
+    };
+
+    let mut modifiers = KeyModifiers::NONE;
+    if state.contains(gdk::ModifierType::SHIFT_MASK) {
+        modifiers |= KeyModifiers::SHIFT;
+    }
+    if state.contains(gdk::ModifierType::CONTROL_MASK) {
+        modifiers |= KeyModifiers::CONTROL;
+    }
+    if state.contains(gdk::ModifierType::ALT_MASK) {
+        modifiers |= KeyModifiers::ALT;
+    }
+
+    Some(KeyEvent::new(code, modifiers))
+}
@@ -0,0 +1,507 @@
+//! A visualizer for puzzles that play out on a grid of cells, like most puzzle input maps.
+//!
+//! A [`GridPuzzle`] only describes its grid and the overlays on it. The [`GridViz`] wrapping it
+//! draws them in the terminal, with panning, zooming and a cell inspector, and as a [`Scene`] for
+//! the other backends.
+
+use std::{cell::RefCell, collections::BTreeMap, rc::Rc};
+
+use crossterm::event::{Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::Line,
+};
+
+use crate::answer::Answer;
+
+use super::{
+    Visualizer,
+    scene::{Rgba, Scene, Shape, path_cells},
+};
+
+/// Cell sizes in the terminal, as `(width, height)` in characters.
+const ZOOM_LEVELS: [(u16, u16); 4] = [(1, 1), (2, 1), (4, 2), (6, 3)];
+
+const PAN_LARGE: usize = 10;
+
+/// The width of paths in the graphical backends, in cells.
+const PATH_WIDTH: f64 = 0.5;
+
+const HELP: &[(&str, &str)] = &[
+    ("w a s d", "pan, shift for 10"),
+    ("z x", "zoom in / out"),
+    ("i", "toggle the cell inspector"),
+    ("h j k l", "move the inspector, shift for 10"),
+];
+
+/// A puzzle on a grid of cells, stepped through by a [`GridViz`].
+pub(crate) trait GridPuzzle {
+    /// Advance the algorithm by one step, returning `false` if it's finished.
+    fn step(&mut self) -> bool;
+
+    /// Whether the puzzle has reached its solution.
+    fn is_solved(&self) -> bool {
+        false
+    }
+
+    /// The number of rows and columns.
+    fn size(&self) -> (usize, usize);
+
+    /// The cell at `(row, col)`, as it appears in the puzzle input.
+    fn cell(&self, row: usize, col: usize) -> u8;
+
+    /// The color of cells of a kind, or `None` to leave them as background.
+    fn cell_color(&self, _cell: u8) -> Option<Rgba> {
+        None
+    }
+
+    /// Overlays drawn over the cells, in order.
+    fn overlays(&self) -> Vec<Overlay>;
+
+    /// Anything else worth knowing about the cell under the inspector.
+    fn inspect(&self, _row: usize, _col: usize) -> Option<String> {
+        None
+    }
+
+    /// Labelled values shown in the status line.
+    fn status(&self) -> Vec<(&'static str, String)> {
+        Vec::new()
+    }
+
+    fn answer(&self) -> Option<Answer>;
+}
+
+pub(crate) enum OverlayKind {
+    /// A line through cells, as `(row, col)`, e.g. the route taken so far.
+    Path(Vec<(usize, usize)>),
+    /// Shaded cells, e.g. the cells visited so far.
+    Fill(Vec<(usize, usize)>),
+    /// Cells that stand out over everything else, e.g. the current position.
+    Highlight(Vec<(usize, usize)>),
+    /// Text on cells, e.g. a score.
+    Labels(Vec<((usize, usize), String)>),
+}
+
+/// A named layer drawn over the cells of a grid.
+pub(crate) struct Overlay {
+    name: &'static str,
+    color: Rgba,
+    kind: OverlayKind,
+}
+
+impl Overlay {
+    pub(crate) fn path(name: &'static str, color: Rgba, cells: Vec<(usize, usize)>) -> Self {
+        Self {
+            name,
+            color,
+            kind: OverlayKind::Path(cells),
+        }
+    }
+
+    pub(crate) fn fill(name: &'static str, color: Rgba, cells: Vec<(usize, usize)>) -> Self {
+        Self {
+            name,
+            color,
+            kind: OverlayKind::Fill(cells),
+        }
+    }
+
+    pub(crate) fn highlight(name: &'static str, color: Rgba, cells: Vec<(usize, usize)>) -> Self {
+        Self {
+            name,
+            color,
+            kind: OverlayKind::Highlight(cells),
+        }
+    }
+
+    pub(crate) fn labels(
+        name: &'static str,
+        color: Rgba,
+        labels: Vec<((usize, usize), String)>,
+    ) -> Self {
+        Self {
+            name,
+            color,
+            kind: OverlayKind::Labels(labels),
+        }
+    }
+
+    fn shape(self) -> Shape {
+        match self.kind {
+            OverlayKind::Path(cells) => Shape::Path {
+                cells,
+                width: PATH_WIDTH,
+            },
+            OverlayKind::Fill(cells) | OverlayKind::Highlight(cells) => Shape::Cells(cells),
+            OverlayKind::Labels(labels) => Shape::Labels(labels),
+        }
+    }
+}
+
+/// How a cell is drawn in the terminal.
+#[derive(Clone)]
+struct TermCell {
+    text: String,
+    fg: Option<Color>,
+    bg: Rgba,
+    bold: bool,
+}
+
+/// Which part of the grid is shown in the terminal.
+#[derive(Debug, Default)]
+struct View {
+    zoom: usize,
+    /// The top left cell shown, as `(row, col)`.
+    offset: (usize, usize),
+    /// The number of rows and columns that fit in the last frame.
+    visible: (usize, usize),
+    inspector: Option<(usize, usize)>,
+}
+
+impl View {
+    fn pan(&mut self, (rows, cols): (usize, usize), d_row: isize, d_col: isize) {
+        self.offset = (
+            self.offset
+                .0
+                .saturating_add_signed(d_row)
+                .min(rows.saturating_sub(self.visible.0)),
+            self.offset
+                .1
+                .saturating_add_signed(d_col)
+                .min(cols.saturating_sub(self.visible.1)),
+        );
+
+        // Take the inspector along so it stays in view.
+        if let Some((row, col)) = &mut self.inspector {
+            *row = (*row).clamp(self.offset.0, self.offset.0 + self.visible.0.max(1) - 1);
+            *col = (*col).clamp(self.offset.1, self.offset.1 + self.visible.1.max(1) - 1);
+        }
+    }
+
+    fn move_inspector(&mut self, (rows, cols): (usize, usize), d_row: isize, d_col: isize) {
+        let Some((row, col)) = self.inspector else {
+            return;
+        };
+        let inspector = (
+            row.saturating_add_signed(d_row).min(rows.saturating_sub(1)),
+            col.saturating_add_signed(d_col).min(cols.saturating_sub(1)),
+        );
+        self.inspector = Some(inspector);
+        self.scroll_to(inspector);
+    }
+
+    /// Pan the least needed for the cell to be in view.
+    fn scroll_to(&mut self, (row, col): (usize, usize)) {
+        let scroll = |offset: usize, visible: usize, pos: usize| {
+            offset.clamp((pos + 1).saturating_sub(visible.max(1)), pos)
+        };
+        self.offset = (
+            scroll(self.offset.0, self.visible.0, row),
+            scroll(self.offset.1, self.visible.1, col),
+        );
+    }
+}
+
+/// Steps through a [`GridPuzzle`], drawing its grid and overlays.
+#[derive(Clone)]
+pub(crate) struct GridViz<P> {
+    puzzle: P,
+    /// Shared between clones, so rewinding to a snapshot doesn't move the view.
+    view: Rc<RefCell<View>>,
+}
+
+impl<P: GridPuzzle> GridViz<P> {
+    pub(crate) fn new(puzzle: P) -> Self {
+        Self {
+            puzzle,
+            view: Rc::default(),
+        }
+    }
+
+    fn inspector_line(&self, (row, col): (usize, usize)) -> Line<'static> {
+        let cell = self.puzzle.cell(row, col) as char;
+        let mut line = format!(" row {row}, col {col}: {cell:?}");
+
+        let names = self
+            .puzzle
+            .overlays()
+            .into_iter()
+            .filter_map(|overlay| {
+                let name = overlay.name;
+                overlay.shape().contains(row, col).then_some(name)
+            })
+            .collect::<Vec<_>>();
+        if !names.is_empty() {
+            line.push_str("  ");
+            line.push_str(&names.join(", "));
+        }
+
+        if let Some(info) = self.puzzle.inspect(row, col) {
+            line.push_str("  ");
+            line.push_str(&info);
+        }
+
+        Line::styled(line, Style::new().add_modifier(Modifier::REVERSED))
+    }
+}
+
+impl<P: GridPuzzle> Visualizer for GridViz<P> {
+    fn step(&mut self) -> bool {
+        self.puzzle.step()
+    }
+
+    fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let size @ (rows, cols) = self.puzzle.size();
+        let mut view = self.view.borrow_mut();
+        let inspector_height = view.inspector.is_some() as u16;
+        let [grid_area, inspector_area] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(inspector_height)])
+                .areas(area);
+
+        let (cell_width, cell_height) = ZOOM_LEVELS[view.zoom];
+        view.visible = (
+            (grid_area.height / cell_height) as usize,
+            (grid_area.width / cell_width) as usize,
+        );
+        // Keep the view on the grid after zooming or resizing.
+        view.pan(size, 0, 0);
+        if let Some(inspector) = view.inspector {
+            view.scroll_to(inspector);
+        }
+
+        let (row0, col0) = view.offset;
+        let shown_rows = view.visible.0.min(rows - row0.min(rows));
+        let shown_cols = view.visible.1.min(cols - col0.min(cols));
+
+        let mut cells = Vec::with_capacity(shown_rows * shown_cols);
+        for row in row0..row0 + shown_rows {
+            for col in col0..col0 + shown_cols {
+                let cell = self.puzzle.cell(row, col);
+                cells.push(TermCell {
+                    text: (cell as char).to_string(),
+                    fg: self.puzzle.cell_color(cell).map(Color::from),
+                    bg: Rgba::BLACK,
+                    bold: false,
+                });
+            }
+        }
+        let index = |(row, col): (usize, usize)| {
+            let visible = (row0..row0 + shown_rows).contains(&row)
+                && (col0..col0 + shown_cols).contains(&col);
+            visible.then(|| (row - row0) * shown_cols + (col - col0))
+        };
+
+        for overlay in self.puzzle.overlays() {
+            let color = overlay.color;
+            match overlay.kind {
+                OverlayKind::Fill(positions) => {
+                    for i in positions.into_iter().filter_map(index) {
+                        cells[i].bg = color.over(cells[i].bg);
+                    }
+                }
+                OverlayKind::Path(points) => {
+                    for i in path_cells(&points).into_iter().filter_map(index) {
+                        cells[i].bg = color.over(cells[i].bg);
+                    }
+                }
+                OverlayKind::Highlight(positions) => {
+                    for i in positions.into_iter().filter_map(index) {
+                        let cell = &mut cells[i];
+                        cell.bg = color.over(cell.bg);
+                        cell.fg = Some(Color::Black);
+                        cell.bold = true;
+                    }
+                }
+                OverlayKind::Labels(labels) => {
+                    for (pos, text) in labels {
+                        if let Some(i) = index(pos) {
+                            let cell = &mut cells[i];
+                            cell.text = text;
+                            cell.fg = Some(color.into());
+                            cell.bold = true;
+                        }
+                    }
+                }
+            }
+        }
+
+        let inspector = view.inspector;
+        let buf = frame.buffer_mut();
+        for (i, cell) in cells.iter().enumerate() {
+            let (row, col) = (row0 + i / shown_cols, col0 + i % shown_cols);
+            let mut style = Style::new().bg(cell.bg.into());
+            if let Some(fg) = cell.fg {
+                style = style.fg(fg);
+            }
+            if cell.bold {
+                style = style.add_modifier(Modifier::BOLD);
+            }
+            if inspector == Some((row, col)) {
+                style = style.add_modifier(Modifier::REVERSED);
+            }
+
+            let x = grid_area.x + (i % shown_cols) as u16 * cell_width;
+            let y = grid_area.y + (i / shown_cols) as u16 * cell_height;
+            let text = cell
+                .text
+                .chars()
+                .take(cell_width as usize)
+                .collect::<String>();
+            let text_x = x + (cell_width - text.chars().count() as u16) / 2;
+            for dy in 0..cell_height {
+                buf.set_string(x, y + dy, " ".repeat(cell_width as usize), style);
+            }
+            buf.set_string(text_x, y + cell_height / 2, text, style);
+        }
+
+        if let Some(inspector) = inspector {
+            frame.render_widget(self.inspector_line(inspector), inspector_area);
+        }
+    }
+
+    fn scene(&self) -> Option<Scene> {
+        let (rows, cols) = self.puzzle.size();
+        let mut scene = Scene::new(cols as f64, rows as f64);
+
+        let mut cells_by_kind = BTreeMap::<u8, Vec<(usize, usize)>>::new();
+        for row in 0..rows {
+            for col in 0..cols {
+                let cell = self.puzzle.cell(row, col);
+                cells_by_kind.entry(cell).or_default().push((row, col));
+            }
+        }
+        for (cell, cells) in cells_by_kind {
+            if let Some(color) = self.puzzle.cell_color(cell) {
+                scene.layer("grid", color, Shape::Cells(cells));
+            }
+        }
+
+        for overlay in self.puzzle.overlays() {
+            let (name, color) = (overlay.name, overlay.color);
+            scene.layer(name, color, overlay.shape());
+        }
+
+        Some(scene)
+    }
+
+    fn inspect(&self, row: usize, col: usize) -> Option<String> {
+        self.puzzle.inspect(row, col)
+    }
+
+    fn is_solved(&self) -> bool {
+        self.puzzle.is_solved()
+    }
+
+    fn status(&self) -> Vec<(&'static str, String)> {
+        self.puzzle.status()
+    }
+
+    fn help(&self) -> &'static [(&'static str, &'static str)] {
+        HELP
+    }
+
+    fn handle_event(&mut self, event: &Event) -> bool {
+        let Event::Key(key) = event else {
+            return false;
+        };
+        if key.kind != KeyEventKind::Press || key.modifiers.contains(KeyModifiers::CONTROL) {
+            return false;
+        }
+
+        let KeyCode::Char(c) = key.code else {
+            return false;
+        };
+        let amount = if c.is_ascii_uppercase() {
+            PAN_LARGE as isize
+        } else {
+            1
+        };
+        let size = self.puzzle.size();
+        let mut view = self.view.borrow_mut();
+
+        match c.to_ascii_lowercase() {
+            'w' => view.pan(size, -amount, 0),
+            'a' => view.pan(size, 0, -amount),
+            's' => view.pan(size, amount, 0),
+            'd' => view.pan(size, 0, amount),
+            'z' if c == 'z' => view.zoom = (view.zoom + 1).min(ZOOM_LEVELS.len() - 1),
+            'x' if c == 'x' => view.zoom = view.zoom.saturating_sub(1),
+            'i' if c == 'i' => {
+                view.inspector = match view.inspector {
+                    Some(_) => None,
+                    None => Some((
+                        view.offset.0 + view.visible.0.min(size.0) / 2,
+                        view.offset.1 + view.visible.1.min(size.1) / 2,
+                    )),
+                }
+            }
+            'h' if view.inspector.is_some() => view.move_inspector(size, 0, -amount),
+            'j' if view.inspector.is_some() => view.move_inspector(size, amount, 0),
+            'k' if view.inspector.is_some() => view.move_inspector(size, -amount, 0),
+            'l' if view.inspector.is_some() => view.move_inspector(size, 0, amount),
+            _ => return false,
+        }
+
+        true
+    }
+
+    fn answer(&self, _step: u64) -> Option<Answer> {
+        self.puzzle.answer()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        answer::Answer,
+        viz::{Driver, VizContext, headless},
+    };
+
+    use super::{GridPuzzle, GridViz, Overlay, Rgba};
+
+    struct Room;
+
+    impl GridPuzzle for Room {
+        fn step(&mut self) -> bool {
+            false
+        }
+
+        fn size(&self) -> (usize, usize) {
+            (3, 4)
+        }
+
+        fn cell(&self, row: usize, col: usize) -> u8 {
+            b"#..#....#..#"[row * 4 + col]
+        }
+
+        fn overlays(&self) -> Vec<Overlay> {
+            vec![
+                Overlay::fill("visited", Rgba::WHITE.with_alpha(0.5), vec![(1, 0), (1, 1)]),
+                Overlay::labels("scores", Rgba::WHITE, vec![((0, 1), "7".to_owned())]),
+            ]
+        }
+
+        fn answer(&self) -> Option<Answer> {
+            None
+        }
+    }
+
+    #[test]
+    fn inspect_cells() {
+        let mut driver = Driver::new(GridViz::new(Room), &VizContext::default());
+        let size = headless::Size {
+            width: 30,
+            height: 6,
+        };
+        let (_, _, frame) = driver
+            .run_script(&"i,h".parse().unwrap(), size, |_, _, _| Ok(()))
+            .unwrap();
+
+        let text = headless::frame_text(&frame);
+        let lines = text.lines().collect::<Vec<_>>();
+        assert_eq!(["#7.#", "....", "#..#", ""], lines[..4]);
+        assert_eq!(" row 1, col 1: '.'  visited", lines[4]);
+    }
+}
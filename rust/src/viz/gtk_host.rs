@@ -0,0 +1,170 @@
+//! A host for visualizers that draw themselves with cairo in a GTK window, rather than through a
+//! [`Scene`](super::scene::Scene).
+//!
+//! Each window owns its visualizer, so they hold their state as plain fields instead of statics,
+//! and a process can open as many as it likes. The windows are the [`desktop`](super::desktop)
+//! window, so visualizers that draw a grid can be zoomed, panned and inspected the same way. The
+//! driver's quit keys close a window, and other keys are passed on.
+
+use std::{cell::RefCell, rc::Rc};
+
+use anyhow::{Context, Result};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use gtk::{cairo, glib, prelude::*};
+
+use super::desktop::{Shared, Window, WindowEvent};
+
+pub(crate) trait GtkVisualizer: 'static {
+    /// The initial size of the drawing area, in pixels.
+    fn size(&self) -> (i32, i32);
+
+    /// The number of rows and columns, for visualizers that draw a grid of square cells from the
+    /// top left of the canvas, so it can be zoomed and the pointer can be mapped to a cell.
+    fn grid(&self) -> Option<(usize, usize)> {
+        None
+    }
+
+    fn draw(&self, ctx: &cairo::Context, canvas: &Canvas) -> Result<()>;
+
+    /// Handle a key press, returning whether the drawing changed.
+    fn key_pressed(&mut self, _key: KeyEvent) -> bool {
+        false
+    }
+}
+
+/// What a visualizer draws on.
+pub(crate) struct Canvas {
+    pub(crate) width: f64,
+    pub(crate) height: f64,
+    /// The size of a grid cell in pixels, or 1 if the visualizer doesn't draw a grid.
+    pub(crate) cell_size: f64,
+    /// The cell under the pointer, as `(row, col)`.
+    pub(crate) pointer: Option<(usize, usize)>,
+}
+
+impl Canvas {
+    /// The canvas for the window's view, moving the origin of `ctx` to the grid's top left.
+    fn new(ctx: &cairo::Context, shared: &Shared) -> Self {
+        let Some((cols, rows)) = shared.size else {
+            let (width, height) = shared.view.area;
+            return Self {
+                width,
+                height,
+                cell_size: 1.0,
+                pointer: None,
+            };
+        };
+
+        let (cell_size, (x0, y0)) = shared.view.transform((cols, rows));
+        ctx.translate(x0, y0);
+        Self {
+            width: cols * cell_size,
+            height: rows * cell_size,
+            cell_size,
+            pointer: shared.inspected(),
+        }
+    }
+}
+
+/// The windows to open.
+#[derive(Default)]
+pub(crate) struct GtkHost {
+    windows: Vec<(String, Box<dyn GtkVisualizer>)>,
+}
+
+impl GtkHost {
+    pub(crate) fn window(mut self, title: &str, visualizer: impl GtkVisualizer) -> Self {
+        self.windows.push((title.to_owned(), Box::new(visualizer)));
+        self
+    }
+
+    /// Open the windows and run until they're all closed.
+    pub(crate) fn run(self) -> Result<()> {
+        gtk::init().context("failed to initialize GTK")?;
+        let context = glib::MainContext::default();
+
+        let mut windows = self
+            .windows
+            .into_iter()
+            .map(|(title, visualizer)| HostedWindow::open(&title, visualizer))
+            .collect::<Vec<_>>();
+        while !windows.is_empty() {
+            context.iteration(true);
+            windows.retain(HostedWindow::handle_events);
+        }
+
+        Ok(())
+    }
+}
+
+struct HostedWindow {
+    visualizer: Rc<RefCell<Box<dyn GtkVisualizer>>>,
+    shared: Rc<RefCell<Shared>>,
+    window: Window,
+}
+
+impl HostedWindow {
+    fn open(title: &str, visualizer: Box<dyn GtkVisualizer>) -> Self {
+        let size = visualizer.size();
+        let shared = Rc::new(RefCell::new(Shared {
+            size: visualizer
+                .grid()
+                .map(|(rows, cols)| (cols as f64, rows as f64)),
+            ..Shared::default()
+        }));
+        let visualizer = Rc::new(RefCell::new(visualizer));
+
+        let window = Window::open(title, size, &shared, {
+            let visualizer = Rc::clone(&visualizer);
+            move |ctx, shared| visualizer.borrow().draw(ctx, &Canvas::new(ctx, shared))
+        });
+        window.status.set_visible(false);
+        window.inspector.set_visible(shared.borrow().size.is_some());
+
+        Self {
+            visualizer,
+            shared,
+            window,
+        }
+    }
+
+    /// Handle the events since the last call, returning whether the window is still open.
+    fn handle_events(&self) -> bool {
+        let events = std::mem::take(&mut self.shared.borrow_mut().events);
+        for event in events {
+            match event {
+                WindowEvent::Key(key) if is_quit(key) => {
+                    self.window.window.destroy();
+                    return false;
+                }
+                WindowEvent::Key(key) => {
+                    if self.visualizer.borrow_mut().key_pressed(key) {
+                        self.window.drawing_area.queue_draw();
+                    }
+                }
+                WindowEvent::Pointer => {
+                    let inspector = match self.shared.borrow().inspected() {
+                        Some((row, col)) => format!("row {row}, col {col}"),
+                        None => String::new(),
+                    };
+                    self.window.inspector.set_text(&inspector);
+                    self.window.drawing_area.queue_draw();
+                }
+                WindowEvent::Closed => {
+                    self.window.window.destroy();
+                    return false;
+                }
+            }
+        }
+        true
+    }
+}
+
+/// Whether the driver would quit on `key`.
+fn is_quit(key: KeyEvent) -> bool {
+    match key.code {
+        KeyCode::Char('q') | KeyCode::Esc => true,
+        KeyCode::Char('c') => key.modifiers.contains(KeyModifiers::CONTROL),
+        _ => false,
+    }
+}
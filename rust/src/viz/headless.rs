@@ -0,0 +1,225 @@
+//! Run a visualization without a terminal, driven by a script of key presses.
+//!
+//! Frames are rendered into an in-memory buffer and can be dumped as text, so visualizations can be
+//! checked in tests and CI.
+
+use std::{fmt, str::FromStr, sync::OnceLock};
+
+use anyhow::{Result, bail};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::buffer::Buffer;
+
+static OPTIONS: OnceLock<HeadlessOptions> = OnceLock::new();
+
+pub(crate) struct HeadlessOptions {
+    pub(crate) script: Script,
+    pub(crate) size: Size,
+    /// Also dump every this many frames, not just the last.
+    pub(crate) dump_every: Option<u64>,
+}
+
+/// Run the next visualization headless.
+pub(crate) fn enable(options: HeadlessOptions) -> Result<()> {
+    if OPTIONS.set(options).is_err() {
+        bail!("headless mode is already enabled");
+    }
+    Ok(())
+}
+
+pub(super) fn options() -> Option<&'static HeadlessOptions> {
+    OPTIONS.get()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ScriptEvent {
+    Key(KeyEvent),
+    /// Let one frame of playback time pass.
+    Tick,
+}
+
+/// A comma-separated list of keys, each optionally repeated with `*N`, e.g. `space*100,enter`.
+///
+/// Keys are single characters or one of `space`, `enter`, `esc`, `bksp`, `tab`, `left`, `right`,
+/// `up`, `down`, `pgup`, `pgdn`, `home` and `end`, optionally prefixed with `shift+` or `ctrl+`.
+/// `tick` lets one frame of playback time pass.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Script(Vec<(ScriptEvent, u64)>);
+
+impl Script {
+    pub(crate) fn events(&self) -> impl Iterator<Item = ScriptEvent> + '_ {
+        self.0
+            .iter()
+            .flat_map(|&(event, n)| std::iter::repeat_n(event, n as usize))
+    }
+}
+
+impl FromStr for Script {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(|item| {
+                // Split on the last `*` so the key itself can be `*`.
+                let (key, n) = match item.rsplit_once('*') {
+                    Some((key, n)) if !key.is_empty() => {
+                        let n = n
+                            .parse::<u64>()
+                            .map_err(|err| format!("invalid repeat count in `{item}`: {err}"))?;
+                        (key, n)
+                    }
+                    _ => (item, 1),
+                };
+                Ok((parse_event(key)?, n))
+            })
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
+pub(super) fn parse_event(value: &str) -> Result<ScriptEvent, String> {
+    if value == "tick" {
+        return Ok(ScriptEvent::Tick);
+    }
+
+    let (modifiers, key) = if let Some(key) = value.strip_prefix("shift+") {
+        (KeyModifiers::SHIFT, key)
+    } else if let Some(key) = value.strip_prefix("ctrl+") {
+        (KeyModifiers::CONTROL, key)
+    } else {
+        (KeyModifiers::NONE, value)
+    };
+
+    let code = match key {
+        "space" => KeyCode::Char(' '),
+        "enter" => KeyCode::Enter,
+        "esc" => KeyCode::Esc,
+        "bksp" => KeyCode::Backspace,
+        "tab" => KeyCode::Tab,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "pgup" => KeyCode::PageUp,
+        "pgdn" => KeyCode::PageDown,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        _ => {
+            let mut chars = key.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => KeyCode::Char(c),
+                _ => return Err(format!("unknown key in script: {value}")),
+            }
+        }
+    };
+
+    Ok(ScriptEvent::Key(KeyEvent::new(code, modifiers)))
+}
+
+/// The size of the headless terminal, e.g. `120x40`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Size {
+    pub(crate) width: u16,
+    pub(crate) height: u16,
+}
+
+impl Default for Size {
+    fn default() -> Self {
+        Self {
+            width: 120,
+            height: 40,
+        }
+    }
+}
+
+impl fmt::Display for Size {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}x{}", self.width, self.height)
+    }
+}
+
+impl FromStr for Size {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid size, expected WIDTHxHEIGHT: {value}");
+        let (width, height) = value.split_once('x').ok_or_else(invalid)?;
+        let width = width.parse::<u16>().map_err(|_| invalid())?;
+        let height = height.parse::<u16>().map_err(|_| invalid())?;
+        if width == 0 || height == 0 {
+            return Err(invalid());
+        }
+        Ok(Self { width, height })
+    }
+}
+
+/// The text of a frame, one line per row with trailing whitespace removed.
+pub(crate) fn frame_text(frame: &Buffer) -> String {
+    let area = frame.area;
+    let mut text = String::new();
+    for y in area.top()..area.bottom() {
+        let row = (area.left()..area.right())
+            .filter_map(|x| frame.cell((x, y)))
+            .map(|cell| cell.symbol())
+            .collect::<String>();
+        text.push_str(row.trim_end());
+        text.push('\n');
+    }
+    text
+}
+
+/// Run `visualizer` to the end in a terminal of `size` and quit, returning the answer it quit with
+/// and the last frame. Panics if it takes more than one large jump to finish.
+#[cfg(test)]
+pub(crate) fn run_to_end<V: super::Visualizer>(
+    visualizer: V,
+    size: Size,
+) -> (Option<crate::answer::Answer>, Buffer) {
+    let mut driver = super::Driver::new(visualizer, &super::VizContext::default());
+    let (answer, _, frame) = driver
+        .run_script(&"N,enter".parse().unwrap(), size, |_, _, _| Ok(()))
+        .unwrap();
+    assert!(
+        driver.finished,
+        "the visualization didn't finish in {} steps",
+        super::LARGE_JUMP
+    );
+    (answer, frame)
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    use super::{Script, ScriptEvent};
+
+    #[test]
+    fn parse_script() {
+        let key = |code| ScriptEvent::Key(KeyEvent::new(code, KeyModifiers::NONE));
+
+        let script = "space*3, 5,*,**2,tick*2,shift+pgup,enter"
+            .parse::<Script>()
+            .unwrap();
+        assert_eq!(
+            vec![
+                (key(KeyCode::Char(' ')), 3),
+                (key(KeyCode::Char('5')), 1),
+                (key(KeyCode::Char('*')), 1),
+                (key(KeyCode::Char('*')), 2),
+                (ScriptEvent::Tick, 2),
+                (
+                    ScriptEvent::Key(KeyEvent::new(KeyCode::PageUp, KeyModifiers::SHIFT)),
+                    1
+                ),
+                (key(KeyCode::Enter), 1),
+            ],
+            script.0
+        );
+        assert_eq!(11, script.events().count());
+
+        assert!("space*x".parse::<Script>().is_err());
+        assert!("spaces".parse::<Script>().is_err());
+    }
+}
@@ -0,0 +1,296 @@
+//! Record the frames of a visualization to an asciicast or an animated GIF.
+
+use std::{
+    fmt::Write as _,
+    fs,
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::OnceLock,
+    time::SystemTime,
+};
+
+use anyhow::{Context, Result, bail};
+use ratatui::{
+    buffer::Buffer,
+    style::{Color, Modifier},
+};
+
+use super::json_string;
+
+mod font;
+mod gif;
+
+static OPTIONS: OnceLock<RecordOptions> = OnceLock::new();
+
+pub(crate) struct RecordOptions {
+    pub(crate) path: PathBuf,
+    /// Frames per second of the recording. Each recorded frame is shown for the same amount of
+    /// time, regardless of how long it was on screen.
+    pub(crate) fps: u32,
+    /// Record a frame at most every `skip` steps.
+    pub(crate) skip: u64,
+}
+
+#[derive(Clone, Copy)]
+enum Format {
+    Asciicast,
+    Gif,
+}
+
+impl Format {
+    fn from_path(path: &Path) -> Result<Self> {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("cast") => Ok(Self::Asciicast),
+            Some("gif") => Ok(Self::Gif),
+            _ => bail!(
+                "unsupported recording format, expected a .cast or .gif file: {}",
+                path.display()
+            ),
+        }
+    }
+}
+
+/// Record the next visualization that runs.
+pub(crate) fn enable(options: RecordOptions) -> Result<()> {
+    Format::from_path(&options.path)?;
+    if OPTIONS.set(options).is_err() {
+        bail!("recording is already enabled");
+    }
+    Ok(())
+}
+
+pub(super) fn options() -> Option<&'static RecordOptions> {
+    OPTIONS.get()
+}
+
+enum FrameWriter {
+    Asciicast(CastWriter<BufWriter<fs::File>>),
+    Gif(gif::GifWriter<BufWriter<fs::File>>),
+}
+
+pub(super) struct Recorder {
+    options: &'static RecordOptions,
+    writer: FrameWriter,
+    n_frames: usize,
+    /// The last recorded frame and the step it was at.
+    last: Option<(u64, Buffer)>,
+    /// The latest frame that was skipped, so the recording can end on it.
+    skipped: Option<(u64, Buffer)>,
+}
+
+impl Recorder {
+    pub(super) fn create(options: &'static RecordOptions) -> Result<Self> {
+        let file = fs::File::create(&options.path).with_context(|| {
+            format!(
+                "failed to create recording file: {}",
+                options.path.display()
+            )
+        })?;
+        let out = BufWriter::new(file);
+        let writer = match Format::from_path(&options.path)? {
+            Format::Asciicast => FrameWriter::Asciicast(CastWriter::new(out, options.fps)),
+            Format::Gif => FrameWriter::Gif(gif::GifWriter::new(out, options.fps)),
+        };
+        Ok(Self {
+            options,
+            writer,
+            n_frames: 0,
+            last: None,
+            skipped: None,
+        })
+    }
+
+    pub(super) fn path(&self) -> &Path {
+        &self.options.path
+    }
+
+    /// Record `frame` if it's different from the last recorded frame and at least `skip` steps
+    /// away from it. Frames at the same step, e.g. after moving a cursor, are always recorded.
+    pub(super) fn capture(&mut self, step: u64, frame: &Buffer) -> Result<()> {
+        if let Some((last_step, last_frame)) = &self.last {
+            if last_frame == frame {
+                return Ok(());
+            }
+            if step != *last_step && step.abs_diff(*last_step) < self.options.skip {
+                self.skipped = Some((step, frame.clone()));
+                return Ok(());
+            }
+        }
+        self.write(step, frame.clone())
+    }
+
+    /// Write the end of the recording, returning the number of frames recorded.
+    pub(super) fn finish(mut self) -> Result<usize> {
+        if let Some((step, frame)) = self.skipped.take() {
+            self.write(step, frame)?;
+        }
+
+        match self.writer {
+            FrameWriter::Asciicast(writer) => writer.finish(),
+            FrameWriter::Gif(writer) => writer.finish(),
+        }
+        .with_context(|| format!("failed to write recording: {}", self.options.path.display()))?;
+
+        Ok(self.n_frames)
+    }
+
+    fn write(&mut self, step: u64, frame: Buffer) -> Result<()> {
+        let prev = self.last.as_ref().map(|(_, prev)| prev);
+        match &mut self.writer {
+            FrameWriter::Asciicast(writer) => writer.frame(&frame, prev),
+            FrameWriter::Gif(writer) => writer.frame(&frame, prev),
+        }
+        .with_context(|| format!("failed to write recording: {}", self.options.path.display()))?;
+
+        self.n_frames += 1;
+        self.skipped = None;
+        self.last = Some((step, frame));
+        Ok(())
+    }
+}
+
+/// Writes frames as an [asciicast v2](https://docs.asciinema.org/manual/asciicast/v2/) file,
+/// redrawing only the rows that changed.
+struct CastWriter<W> {
+    out: W,
+    fps: u32,
+    n_frames: u64,
+}
+
+impl<W: Write> CastWriter<W> {
+    fn new(out: W, fps: u32) -> Self {
+        Self {
+            out,
+            fps,
+            n_frames: 0,
+        }
+    }
+
+    fn frame(&mut self, frame: &Buffer, prev: Option<&Buffer>) -> io::Result<()> {
+        let area = frame.area;
+        let mut data = String::new();
+
+        if prev.is_none() {
+            let timestamp = SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default();
+            writeln!(
+                self.out,
+                r#"{{"version": 2, "width": {}, "height": {}, "timestamp": {timestamp}, "env": {{"TERM": "xterm-256color"}}}}"#,
+                area.width, area.height
+            )?;
+            data.push_str("\x1b[?25l\x1b[2J");
+        }
+
+        for y in area.top()..area.bottom() {
+            let row = |buffer: &Buffer| {
+                (area.left()..area.right())
+                    .map(|x| buffer.cell((x, y)).cloned())
+                    .collect::<Vec<_>>()
+            };
+            if prev.is_some_and(|prev| prev.area == area && row(prev) == row(frame)) {
+                continue;
+            }
+
+            write!(data, "\x1b[{};1H", y - area.top() + 1).unwrap();
+            let mut style = None;
+            for x in area.left()..area.right() {
+                let Some(cell) = frame.cell((x, y)) else {
+                    continue;
+                };
+                let cell_style = (cell.fg, cell.bg, cell.modifier);
+                if style != Some(cell_style) {
+                    data.push_str(&sgr(cell.fg, cell.bg, cell.modifier));
+                    style = Some(cell_style);
+                }
+                data.push_str(cell.symbol());
+            }
+            data.push_str("\x1b[0m");
+        }
+
+        let time = self.n_frames as f64 / self.fps as f64;
+        self.n_frames += 1;
+        writeln!(self.out, "[{time:.3}, \"o\", {}]", json_string(&data))
+    }
+
+    fn finish(mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+/// The escape sequence that sets the colors and modifiers of a cell.
+fn sgr(fg: Color, bg: Color, modifier: Modifier) -> String {
+    let mut params = vec!["0".to_owned()];
+
+    for (flag, param) in [
+        (Modifier::BOLD, "1"),
+        (Modifier::DIM, "2"),
+        (Modifier::ITALIC, "3"),
+        (Modifier::UNDERLINED, "4"),
+        (Modifier::REVERSED, "7"),
+        (Modifier::CROSSED_OUT, "9"),
+    ] {
+        if modifier.contains(flag) {
+            params.push(param.to_owned());
+        }
+    }
+
+    for (color, base) in [(fg, 30), (bg, 40)] {
+        let param = match color {
+            Color::Reset => continue,
+            Color::Black => base.to_string(),
+            Color::Red => (base + 1).to_string(),
+            Color::Green => (base + 2).to_string(),
+            Color::Yellow => (base + 3).to_string(),
+            Color::Blue => (base + 4).to_string(),
+            Color::Magenta => (base + 5).to_string(),
+            Color::Cyan => (base + 6).to_string(),
+            Color::Gray => (base + 7).to_string(),
+            Color::DarkGray => (base + 60).to_string(),
+            Color::LightRed => (base + 61).to_string(),
+            Color::LightGreen => (base + 62).to_string(),
+            Color::LightYellow => (base + 63).to_string(),
+            Color::LightBlue => (base + 64).to_string(),
+            Color::LightMagenta => (base + 65).to_string(),
+            Color::LightCyan => (base + 66).to_string(),
+            Color::White => (base + 67).to_string(),
+            Color::Indexed(i) => format!("{};5;{i}", base + 8),
+            Color::Rgb(r, g, b) => format!("{};2;{r};{g};{b}", base + 8),
+        };
+        params.push(param);
+    }
+
+    format!("\x1b[{}m", params.join(";"))
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::{buffer::Buffer, layout::Rect, style::Stylize};
+
+    #[test]
+    fn cast_frames() {
+        let mut frame = Buffer::empty(Rect::new(0, 0, 4, 2));
+        frame.set_string(0, 0, "ab", ratatui::style::Style::new().red());
+        let mut next = frame.clone();
+        next.set_string(0, 1, "\"c\"", ratatui::style::Style::new());
+
+        let mut out = Vec::new();
+        let mut writer = super::CastWriter::new(&mut out, 4);
+        writer.frame(&frame, None).unwrap();
+        writer.frame(&next, Some(&frame)).unwrap();
+        writer.finish().unwrap();
+
+        let out = String::from_utf8(out).unwrap();
+        let lines = out.lines().collect::<Vec<_>>();
+        assert_eq!(3, lines.len());
+        assert!(lines[0].starts_with(r#"{"version": 2, "width": 4, "height": 2, "#));
+        assert!(
+            lines[1].starts_with(r#"[0.000, "o", "\u001b[?25l\u001b[2J\u001b[1;1H\u001b[0;31mab"#)
+        );
+        assert_eq!(
+            r#"[0.250, "o", "\u001b[2;1H\u001b[0m\"c\" \u001b[0m"]"#,
+            lines[2]
+        );
+    }
+}
@@ -0,0 +1,111 @@
+//! A 5x9 bitmap font for printable ASCII. The last two rows are below the baseline, for
+//! descenders.
+
+pub(super) const WIDTH: usize = 5;
+pub(super) const HEIGHT: usize = 9;
+
+#[rustfmt::skip]
+const GLYPHS: &[(char, [&str; HEIGHT])] = &[
+    ('!', ["..#..", "..#..", "..#..", "..#..", "..#..", ".....", "..#..", ".....", "....."]),
+    ('"', [".#.#.", ".#.#.", ".....", ".....", ".....", ".....", ".....", ".....", "....."]),
+    ('#', [".#.#.", ".#.#.", "#####", ".#.#.", "#####", ".#.#.", ".#.#.", ".....", "....."]),
+    ('$', ["..#..", ".####", "#.#..", ".###.", "..#.#", "####.", "..#..", ".....", "....."]),
+    ('%', ["##...", "##..#", "...#.", "..#..", ".#...", "#..##", "...##", ".....", "....."]),
+    ('&', [".##..", "#..#.", "#.#..", ".#...", "#.#.#", "#..#.", ".##.#", ".....", "....."]),
+    ('\'', ["..#..", "..#..", ".....", ".....", ".....", ".....", ".....", ".....", "....."]),
+    ('(', ["...#.", "..#..", ".#...", ".#...", ".#...", "..#..", "...#.", ".....", "....."]),
+    (')', [".#...", "..#..", "...#.", "...#.", "...#.", "..#..", ".#...", ".....", "....."]),
+    ('*', [".....", "..#..", "#.#.#", ".###.", "#.#.#", "..#..", ".....", ".....", "....."]),
+    ('+', [".....", "..#..", "..#..", "#####", "..#..", "..#..", ".....", ".....", "....."]),
+    (',', [".....", ".....", ".....", ".....", ".....", ".##..", ".##..", "..#..", ".#..."]),
+    ('-', [".....", ".....", ".....", "#####", ".....", ".....", ".....", ".....", "....."]),
+    ('.', [".....", ".....", ".....", ".....", ".....", ".##..", ".##..", ".....", "....."]),
+    ('/', [".....", "....#", "...#.", "..#..", ".#...", "#....", ".....", ".....", "....."]),
+    ('0', [".###.", "#...#", "#..##", "#.#.#", "##..#", "#...#", ".###.", ".....", "....."]),
+    ('1', ["..#..", ".##..", "..#..", "..#..", "..#..", "..#..", ".###.", ".....", "....."]),
+    ('2', [".###.", "#...#", "....#", "...#.", "..#..", ".#...", "#####", ".....", "....."]),
+    ('3', ["#####", "...#.", "..#..", "...#.", "....#", "#...#", ".###.", ".....", "....."]),
+    ('4', ["...#.", "..##.", ".#.#.", "#..#.", "#####", "...#.", "...#.", ".....", "....."]),
+    ('5', ["#####", "#....", "####.", "....#", "....#", "#...#", ".###.", ".....", "....."]),
+    ('6', ["..##.", ".#...", "#....", "####.", "#...#", "#...#", ".###.", ".....", "....."]),
+    ('7', ["#####", "....#", "...#.", "..#..", ".#...", ".#...", ".#...", ".....", "....."]),
+    ('8', [".###.", "#...#", "#...#", ".###.", "#...#", "#...#", ".###.", ".....", "....."]),
+    ('9', [".###.", "#...#", "#...#", ".####", "....#", "...#.", ".##..", ".....", "....."]),
+    (':', [".....", ".##..", ".##..", ".....", ".##..", ".##..", ".....", ".....", "....."]),
+    (';', [".....", ".##..", ".##..", ".....", ".##..", ".##..", "..#..", ".#...", "....."]),
+    ('<', ["...#.", "..#..", ".#...", "#....", ".#...", "..#..", "...#.", ".....", "....."]),
+    ('=', [".....", ".....", "#####", ".....", "#####", ".....", ".....", ".....", "....."]),
+    ('>', [".#...", "..#..", "...#.", "....#", "...#.", "..#..", ".#...", ".....", "....."]),
+    ('?', [".###.", "#...#", "....#", "...#.", "..#..", ".....", "..#..", ".....", "....."]),
+    ('@', [".###.", "#...#", "....#", ".##.#", "#.#.#", "#.#.#", ".###.", ".....", "....."]),
+    ('A', [".###.", "#...#", "#...#", "#####", "#...#", "#...#", "#...#", ".....", "....."]),
+    ('B', ["####.", "#...#", "#...#", "####.", "#...#", "#...#", "####.", ".....", "....."]),
+    ('C', [".###.", "#...#", "#....", "#....", "#....", "#...#", ".###.", ".....", "....."]),
+    ('D', ["###..", "#..#.", "#...#", "#...#", "#...#", "#..#.", "###..", ".....", "....."]),
+    ('E', ["#####", "#....", "#....", "####.", "#....", "#....", "#####", ".....", "....."]),
+    ('F', ["#####", "#....", "#....", "####.", "#....", "#....", "#....", ".....", "....."]),
+    ('G', [".###.", "#...#", "#....", "#.###", "#...#", "#...#", ".####", ".....", "....."]),
+    ('H', ["#...#", "#...#", "#...#", "#####", "#...#", "#...#", "#...#", ".....", "....."]),
+    ('I', [".###.", "..#..", "..#..", "..#..", "..#..", "..#..", ".###.", ".....", "....."]),
+    ('J', ["..###", "...#.", "...#.", "...#.", "...#.", "#..#.", ".##..", ".....", "....."]),
+    ('K', ["#...#", "#..#.", "#.#..", "##...", "#.#..", "#..#.", "#...#", ".....", "....."]),
+    ('L', ["#....", "#....", "#....", "#....", "#....", "#....", "#####", ".....", "....."]),
+    ('M', ["#...#", "##.##", "#.#.#", "#.#.#", "#...#", "#...#", "#...#", ".....", "....."]),
+    ('N', ["#...#", "#...#", "##..#", "#.#.#", "#..##", "#...#", "#...#", ".....", "....."]),
+    ('O', [".###.", "#...#", "#...#", "#...#", "#...#", "#...#", ".###.", ".....", "....."]),
+    ('P', ["####.", "#...#", "#...#", "####.", "#....", "#....", "#....", ".....", "....."]),
+    ('Q', [".###.", "#...#", "#...#", "#...#", "#.#.#", "#..#.", ".##.#", ".....", "....."]),
+    ('R', ["####.", "#...#", "#...#", "####.", "#.#..", "#..#.", "#...#", ".....", "....."]),
+    ('S', [".####", "#....", "#....", ".###.", "....#", "....#", "####.", ".....", "....."]),
+    ('T', ["#####", "..#..", "..#..", "..#..", "..#..", "..#..", "..#..", ".....", "....."]),
+    ('U', ["#...#", "#...#", "#...#", "#...#", "#...#", "#...#", ".###.", ".....", "....."]),
+    ('V', ["#...#", "#...#", "#...#", "#...#", "#...#", ".#.#.", "..#..", ".....", "....."]),
+    ('W', ["#...#", "#...#", "#...#", "#.#.#", "#.#.#", "#.#.#", ".#.#.", ".....", "....."]),
+    ('X', ["#...#", "#...#", ".#.#.", "..#..", ".#.#.", "#...#", "#...#", ".....", "....."]),
+    ('Y', ["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#..", "..#..", ".....", "....."]),
+    ('Z', ["#####", "....#", "...#.", "..#..", ".#...", "#....", "#####", ".....", "....."]),
+    ('[', [".###.", ".#...", ".#...", ".#...", ".#...", ".#...", ".###.", ".....", "....."]),
+    ('\\', [".....", "#....", ".#...", "..#..", "...#.", "....#", ".....", ".....", "....."]),
+    (']', [".###.", "...#.", "...#.", "...#.", "...#.", "...#.", ".###.", ".....", "....."]),
+    ('^', ["..#..", ".#.#.", "#...#", ".....", ".....", ".....", ".....", ".....", "....."]),
+    ('_', [".....", ".....", ".....", ".....", ".....", ".....", ".....", "#####", "....."]),
+    ('`', [".#...", "..#..", ".....", ".....", ".....", ".....", ".....", ".....", "....."]),
+    ('a', [".....", ".....", ".###.", "....#", ".####", "#...#", ".####", ".....", "....."]),
+    ('b', ["#....", "#....", "#.##.", "##..#", "#...#", "#...#", "####.", ".....", "....."]),
+    ('c', [".....", ".....", ".###.", "#....", "#....", "#...#", ".###.", ".....", "....."]),
+    ('d', ["....#", "....#", ".##.#", "#..##", "#...#", "#...#", ".####", ".....", "....."]),
+    ('e', [".....", ".....", ".###.", "#...#", "#####", "#....", ".###.", ".....", "....."]),
+    ('f', ["..##.", ".#..#", ".#...", "###..", ".#...", ".#...", ".#...", ".....", "....."]),
+    ('g', [".....", ".....", ".####", "#...#", "#...#", "#...#", ".####", "....#", ".###."]),
+    ('h', ["#....", "#....", "#.##.", "##..#", "#...#", "#...#", "#...#", ".....", "....."]),
+    ('i', ["..#..", ".....", ".##..", "..#..", "..#..", "..#..", ".###.", ".....", "....."]),
+    ('j', ["...#.", ".....", "..##.", "...#.", "...#.", "...#.", "...#.", "#..#.", ".##.."]),
+    ('k', ["#....", "#....", "#..#.", "#.#..", "##...", "#.#..", "#..#.", ".....", "....."]),
+    ('l', [".##..", "..#..", "..#..", "..#..", "..#..", "..#..", ".###.", ".....", "....."]),
+    ('m', [".....", ".....", "##.#.", "#.#.#", "#.#.#", "#.#.#", "#.#.#", ".....", "....."]),
+    ('n', [".....", ".....", "#.##.", "##..#", "#...#", "#...#", "#...#", ".....", "....."]),
+    ('o', [".....", ".....", ".###.", "#...#", "#...#", "#...#", ".###.", ".....", "....."]),
+    ('p', [".....", ".....", "####.", "#...#", "#...#", "#...#", "####.", "#....", "#...."]),
+    ('q', [".....", ".....", ".####", "#...#", "#...#", "#...#", ".####", "....#", "....#"]),
+    ('r', [".....", ".....", "#.##.", "##..#", "#....", "#....", "#....", ".....", "....."]),
+    ('s', [".....", ".....", ".###.", "#....", ".###.", "....#", "####.", ".....", "....."]),
+    ('t', [".#...", ".#...", "###..", ".#...", ".#...", ".#..#", "..##.", ".....", "....."]),
+    ('u', [".....", ".....", "#...#", "#...#", "#...#", "#..##", ".##.#", ".....", "....."]),
+    ('v', [".....", ".....", "#...#", "#...#", "#...#", ".#.#.", "..#..", ".....", "....."]),
+    ('w', [".....", ".....", "#...#", "#...#", "#.#.#", "#.#.#", ".#.#.", ".....", "....."]),
+    ('x', [".....", ".....", "#...#", ".#.#.", "..#..", ".#.#.", "#...#", ".....", "....."]),
+    ('y', [".....", ".....", "#...#", "#...#", "#...#", "#...#", ".####", "....#", ".###."]),
+    ('z', [".....", ".....", "#####", "...#.", "..#..", ".#...", "#####", ".....", "....."]),
+    ('{', ["...#.", "..#..", "..#..", ".#...", "..#..", "..#..", "...#.", ".....", "....."]),
+    ('|', ["..#..", "..#..", "..#..", "..#..", "..#..", "..#..", "..#..", ".....", "....."]),
+    ('}', [".#...", "..#..", "..#..", "...#.", "..#..", "..#..", ".#...", ".....", "....."]),
+    ('~', [".....", ".....", ".#...", "#.#.#", "...#.", ".....", ".....", ".....", "....."]),
+];
+
+/// The rows of the glyph for `c`, where `#` is lit, or `None` if it isn't printable ASCII.
+pub(super) fn glyph(c: char) -> Option<&'static [&'static str; HEIGHT]> {
+    GLYPHS
+        .binary_search_by_key(&c, |&(g, _)| g)
+        .ok()
+        .map(|i| &GLYPHS[i].1)
+}
@@ -0,0 +1,396 @@
+//! An animated GIF encoder for terminal frames, drawing each cell with the built-in font.
+
+use std::{
+    collections::HashMap,
+    io::{self, Write},
+};
+
+use ratatui::{
+    buffer::{Buffer, Cell},
+    layout::Rect,
+    style::{Color, Modifier},
+};
+
+use super::font;
+
+const CELL_WIDTH: usize = font::WIDTH + 1;
+const CELL_HEIGHT: usize = 12;
+/// Rows above the glyph, so the glyph is centered with its descender rows.
+const GLYPH_TOP: usize = 1;
+
+const DEFAULT_FG: u8 = 7;
+const DEFAULT_BG: u8 = 0;
+
+pub(super) struct GifWriter<W> {
+    out: W,
+    /// The size of the first frame, which all later frames are clipped to.
+    area: Option<Rect>,
+    /// Frame delay in hundredths of a second.
+    delay: u16,
+}
+
+impl<W: Write> GifWriter<W> {
+    pub(super) fn new(out: W, fps: u32) -> Self {
+        Self {
+            out,
+            area: None,
+            delay: (100 / fps).max(2) as u16,
+        }
+    }
+
+    /// Add `frame`, only encoding the cells that changed since `prev`.
+    pub(super) fn frame(&mut self, frame: &Buffer, prev: Option<&Buffer>) -> io::Result<()> {
+        let area = match self.area {
+            Some(area) => area,
+            None => {
+                let area = frame.area;
+                self.write_header(area)?;
+                self.area = Some(area);
+                area
+            }
+        };
+
+        let changed = match prev {
+            Some(prev) => changed_cells(area, frame, prev),
+            None => Some(area),
+        };
+        // Still add a frame so it's shown for as long as in the recording.
+        let changed = changed.unwrap_or(Rect::new(area.x, area.y, 1, 1));
+
+        let width = changed.width as usize * CELL_WIDTH;
+        let height = changed.height as usize * CELL_HEIGHT;
+        let mut pixels = vec![DEFAULT_BG; width * height];
+        for y in 0..changed.height {
+            for x in 0..changed.width {
+                let cell = frame.cell((changed.x + x, changed.y + y));
+                let origin = y as usize * CELL_HEIGHT * width + x as usize * CELL_WIDTH;
+                draw_cell(&mut pixels[origin..], width, cell);
+            }
+        }
+
+        let [delay_lo, delay_hi] = self.delay.to_le_bytes();
+        // Graphic control extension: leave the previous frame in place and set the delay.
+        self.out
+            .write_all(&[0x21, 0xF9, 4, 0b100, delay_lo, delay_hi, 0, 0])?;
+
+        self.out.write_all(&[0x2C])?;
+        for v in [
+            (changed.x - area.x) as usize * CELL_WIDTH,
+            (changed.y - area.y) as usize * CELL_HEIGHT,
+            width,
+            height,
+        ] {
+            self.out.write_all(&(v as u16).to_le_bytes())?;
+        }
+        self.out.write_all(&[0])?;
+
+        self.out.write_all(&[8])?;
+        for block in lzw_encode(&pixels).chunks(255) {
+            self.out.write_all(&[block.len() as u8])?;
+            self.out.write_all(block)?;
+        }
+        self.out.write_all(&[0])
+    }
+
+    pub(super) fn finish(mut self) -> io::Result<()> {
+        self.out.write_all(&[0x3B])?;
+        self.out.flush()
+    }
+
+    fn write_header(&mut self, area: Rect) -> io::Result<()> {
+        self.out.write_all(b"GIF89a")?;
+        let width = area.width as usize * CELL_WIDTH;
+        let height = area.height as usize * CELL_HEIGHT;
+        self.out.write_all(&(width as u16).to_le_bytes())?;
+        self.out.write_all(&(height as u16).to_le_bytes())?;
+        // A global color table with 256 entries.
+        self.out.write_all(&[0xF7, DEFAULT_BG, 0])?;
+        for i in 0..=255 {
+            self.out.write_all(&palette_rgb(i))?;
+        }
+        // Loop forever.
+        self.out
+            .write_all(b"\x21\xFF\x0BNETSCAPE2.0\x03\x01\x00\x00\x00")
+    }
+}
+
+/// The bounding box of the cells of `area` that differ between the frames.
+fn changed_cells(area: Rect, frame: &Buffer, prev: &Buffer) -> Option<Rect> {
+    let mut bounds: Option<(u16, u16, u16, u16)> = None;
+    for y in area.top()..area.bottom() {
+        for x in area.left()..area.right() {
+            if frame.cell((x, y)) != prev.cell((x, y)) {
+                bounds = Some(match bounds {
+                    None => (x, y, x, y),
+                    Some((x0, y0, x1, y1)) => (x0.min(x), y0.min(y), x1.max(x), y1.max(y)),
+                });
+            }
+        }
+    }
+    bounds.map(|(x0, y0, x1, y1)| Rect::new(x0, y0, x1 - x0 + 1, y1 - y0 + 1))
+}
+
+fn draw_cell(pixels: &mut [u8], stride: usize, cell: Option<&Cell>) {
+    let (mut fg, mut bg) = (DEFAULT_FG, DEFAULT_BG);
+    let mut symbol = " ";
+    if let Some(cell) = cell {
+        fg = palette_index(cell.fg).unwrap_or(DEFAULT_FG);
+        bg = palette_index(cell.bg).unwrap_or(DEFAULT_BG);
+        if cell.modifier.contains(Modifier::BOLD) && fg < 8 {
+            fg += 8;
+        }
+        if cell.modifier.contains(Modifier::REVERSED) {
+            (fg, bg) = (bg, fg);
+        }
+        symbol = cell.symbol();
+    }
+
+    let c = symbol.chars().next().unwrap_or(' ');
+    for y in 0..CELL_HEIGHT {
+        for x in 0..CELL_WIDTH {
+            pixels[y * stride + x] = if is_lit(c, x, y) { fg } else { bg };
+        }
+    }
+}
+
+/// Whether pixel (`x`, `y`) of a cell showing `c` is drawn in the foreground color.
+fn is_lit(c: char, x: usize, y: usize) -> bool {
+    let (mid_x, mid_y) = (CELL_WIDTH / 2, CELL_HEIGHT / 2);
+
+    // Which of the up, down, left and right arms of a box drawing character are drawn.
+    let arms = match c {
+        '█' => return true,
+        '▀' => return y < mid_y,
+        '▄' => return y >= mid_y,
+        '▌' => return x < mid_x,
+        '▐' => return x >= mid_x,
+        '─' | '━' | '═' => (false, false, true, true),
+        '│' | '┃' | '║' => (true, true, false, false),
+        '┌' | '╭' | '┏' | '╔' => (false, true, false, true),
+        '┐' | '╮' | '┓' | '╗' => (false, true, true, false),
+        '└' | '╰' | '┗' | '╚' => (true, false, false, true),
+        '┘' | '╯' | '┛' | '╝' => (true, false, true, false),
+        '├' => (true, true, false, true),
+        '┤' => (true, true, true, false),
+        '┬' => (false, true, true, true),
+        '┴' => (true, false, true, true),
+        '┼' => (true, true, true, true),
+        _ => {
+            let c = match c {
+                '→' | '▶' => '>',
+                '←' => '<',
+                '×' => 'x',
+                '⏸' => '"',
+                c => c,
+            };
+            if c == ' ' || x >= font::WIDTH || !(GLYPH_TOP..GLYPH_TOP + font::HEIGHT).contains(&y) {
+                return false;
+            }
+            let glyph = font::glyph(c).or(font::glyph('?'));
+            return glyph.is_some_and(|glyph| glyph[y - GLYPH_TOP].as_bytes()[x] == b'#');
+        }
+    };
+
+    let (up, down, left, right) = arms;
+    (x == mid_x && ((up && y <= mid_y) || (down && y >= mid_y)))
+        || (y == mid_y && ((left && x <= mid_x) || (right && x >= mid_x)))
+}
+
+/// The xterm 256 color palette index of `color`, or `None` for the default color.
+fn palette_index(color: Color) -> Option<u8> {
+    Some(match color {
+        Color::Reset => return None,
+        Color::Black => 0,
+        Color::Red => 1,
+        Color::Green => 2,
+        Color::Yellow => 3,
+        Color::Blue => 4,
+        Color::Magenta => 5,
+        Color::Cyan => 6,
+        Color::Gray => 7,
+        Color::DarkGray => 8,
+        Color::LightRed => 9,
+        Color::LightGreen => 10,
+        Color::LightYellow => 11,
+        Color::LightBlue => 12,
+        Color::LightMagenta => 13,
+        Color::LightCyan => 14,
+        Color::White => 15,
+        Color::Indexed(i) => i,
+        Color::Rgb(r, g, b) => {
+            let level = |v: u8| {
+                (0..CUBE_LEVELS.len())
+                    .min_by_key(|&i| CUBE_LEVELS[i].abs_diff(v))
+                    .unwrap_or_default() as u8
+            };
+            16 + 36 * level(r) + 6 * level(g) + level(b)
+        }
+    })
+}
+
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+fn palette_rgb(i: u8) -> [u8; 3] {
+    const ANSI: [[u8; 3]; 16] = [
+        [0x00, 0x00, 0x00],
+        [0xcd, 0x00, 0x00],
+        [0x00, 0xcd, 0x00],
+        [0xcd, 0xcd, 0x00],
+        [0x00, 0x00, 0xee],
+        [0xcd, 0x00, 0xcd],
+        [0x00, 0xcd, 0xcd],
+        [0xe5, 0xe5, 0xe5],
+        [0x7f, 0x7f, 0x7f],
+        [0xff, 0x00, 0x00],
+        [0x00, 0xff, 0x00],
+        [0xff, 0xff, 0x00],
+        [0x5c, 0x5c, 0xff],
+        [0xff, 0x00, 0xff],
+        [0x00, 0xff, 0xff],
+        [0xff, 0xff, 0xff],
+    ];
+    match i {
+        0..16 => ANSI[i as usize],
+        16..232 => {
+            let i = i - 16;
+            [i / 36, i / 6 % 6, i % 6].map(|l| CUBE_LEVELS[l as usize])
+        }
+        232.. => [8 + 10 * (i - 232); 3],
+    }
+}
+
+/// Compress palette indices with GIF's variable-length LZW, starting with 9-bit codes.
+fn lzw_encode(indices: &[u8]) -> Vec<u8> {
+    const CLEAR: u16 = 256;
+    const END: u16 = 257;
+    const FIRST_CODE: u16 = 258;
+    const MAX_CODE: u16 = 4096;
+
+    let mut bits = BitWriter::default();
+    let mut table = HashMap::<(u16, u8), u16>::new();
+    let mut code_size = 9;
+    let mut next_code = FIRST_CODE;
+
+    bits.write(CLEAR, code_size);
+
+    let mut indices = indices.iter().copied();
+    let Some(first) = indices.next() else {
+        bits.write(END, code_size);
+        return bits.finish();
+    };
+
+    let mut prefix = first as u16;
+    for index in indices {
+        if let Some(&code) = table.get(&(prefix, index)) {
+            prefix = code;
+            continue;
+        }
+
+        bits.write(prefix, code_size);
+        if next_code < MAX_CODE {
+            if next_code == 1 << code_size {
+                code_size += 1;
+            }
+            table.insert((prefix, index), next_code);
+            next_code += 1;
+        } else {
+            bits.write(CLEAR, code_size);
+            table.clear();
+            code_size = 9;
+            next_code = FIRST_CODE;
+        }
+        prefix = index as u16;
+    }
+
+    bits.write(prefix, code_size);
+    bits.write(END, code_size);
+    bits.finish()
+}
+
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    acc: u32,
+    n_bits: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, code: u16, size: u32) {
+        self.acc |= (code as u32) << self.n_bits;
+        self.n_bits += size;
+        while self.n_bits >= 8 {
+            self.bytes.push(self.acc as u8);
+            self.acc >>= 8;
+            self.n_bits -= 8;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.n_bits > 0 {
+            self.bytes.push(self.acc as u8);
+        }
+        self.bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    /// Decode GIF LZW data, the inverse of [`super::lzw_encode`].
+    fn lzw_decode(data: &[u8]) -> Vec<u8> {
+        let mut bit_pos = 0;
+        let mut read = |size: usize| {
+            let code = (0..size)
+                .map(|i| ((data[(bit_pos + i) / 8] >> ((bit_pos + i) % 8)) & 1) as usize)
+                .enumerate()
+                .map(|(i, bit)| bit << i)
+                .sum::<usize>();
+            bit_pos += size;
+            code
+        };
+
+        let mut out = Vec::new();
+        let mut table = Vec::<Vec<u8>>::new();
+        let mut code_size = 9;
+        let mut prev: Option<Vec<u8>> = None;
+        loop {
+            let code = read(code_size);
+            match code {
+                256 => {
+                    table = (0..=255).map(|i| vec![i as u8]).collect();
+                    table.extend([vec![], vec![]]);
+                    code_size = 9;
+                    prev = None;
+                    continue;
+                }
+                257 => return out,
+                _ => {}
+            }
+            let entry = match (table.get(code), &prev) {
+                (Some(entry), _) => entry.clone(),
+                (None, Some(prev)) => [prev.as_slice(), &prev[..1]].concat(),
+                (None, None) => panic!("invalid code {code}"),
+            };
+            out.extend_from_slice(&entry);
+            if let Some(prev) = prev {
+                table.push([prev.as_slice(), &entry[..1]].concat());
+            }
+            if table.len() == 1 << code_size && code_size < 12 {
+                code_size += 1;
+            }
+            prev = Some(entry);
+        }
+    }
+
+    #[test]
+    fn lzw_roundtrip() {
+        let short = b"TOBEORNOTTOBEORTOBEORNOT".to_vec();
+        let repetitive = vec![7; 50_000];
+        let varied = (0..100_000_u32)
+            .map(|i| (i.wrapping_mul(2_654_435_761) >> 24) as u8)
+            .collect::<Vec<_>>();
+
+        for indices in [vec![], short, repetitive, varied] {
+            assert_eq!(indices, lzw_decode(&super::lzw_encode(&indices)));
+        }
+    }
+}
@@ -0,0 +1,77 @@
+//! Snapshots of a visualizer's state, so visualizers that can only step forward can be rewound
+//! by restoring the nearest snapshot before a step and replaying from there.
+
+use std::collections::BTreeMap;
+
+/// The most snapshots kept. Past this, every other one is dropped and they're taken half as
+/// often, so memory stays bounded while replaying stays cheap relative to the length of the run.
+const MAX_SNAPSHOTS: usize = 100;
+
+const INITIAL_INTERVAL: u64 = 16;
+
+pub(super) struct Rewind<V> {
+    clone: fn(&V) -> V,
+    snapshots: BTreeMap<u64, V>,
+    /// Snapshots are taken at steps that are multiples of this.
+    interval: u64,
+}
+
+impl<V> Rewind<V> {
+    /// Start from the state at step 0.
+    pub(super) fn new(visualizer: &V, clone: fn(&V) -> V) -> Self {
+        Self {
+            clone,
+            snapshots: BTreeMap::from([(0, clone(visualizer))]),
+            interval: INITIAL_INTERVAL,
+        }
+    }
+
+    /// How many steps can be taken from `step` before the next snapshot is due.
+    pub(super) fn steps_to_next(&self, step: u64) -> u64 {
+        self.interval - step % self.interval
+    }
+
+    /// Take a snapshot if one is due at `step`.
+    pub(super) fn save(&mut self, step: u64, visualizer: &V) {
+        if step % self.interval != 0 || self.snapshots.contains_key(&step) {
+            return;
+        }
+        self.snapshots.insert(step, (self.clone)(visualizer));
+
+        if self.snapshots.len() > MAX_SNAPSHOTS {
+            self.interval *= 2;
+            let interval = self.interval;
+            self.snapshots.retain(|step, _| step % interval == 0);
+        }
+    }
+
+    /// A copy of the latest state at or before `step`, and its step.
+    pub(super) fn restore(&self, step: u64) -> (u64, V) {
+        let (&at, snapshot) = self
+            .snapshots
+            .range(..=step)
+            .next_back()
+            .expect("step 0 is always kept");
+        (at, (self.clone)(snapshot))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{INITIAL_INTERVAL, MAX_SNAPSHOTS, Rewind};
+
+    #[test]
+    fn thin_out_snapshots() {
+        let mut rewind = Rewind::new(&0_u64, Clone::clone);
+        let last = INITIAL_INTERVAL * MAX_SNAPSHOTS as u64;
+        for step in 1..=last {
+            rewind.save(step, &step);
+        }
+
+        assert_eq!(2 * INITIAL_INTERVAL, rewind.interval);
+        assert_eq!(MAX_SNAPSHOTS / 2 + 1, rewind.snapshots.len());
+        assert_eq!((0, 0), rewind.restore(2 * INITIAL_INTERVAL - 1));
+        assert_eq!((last, last), rewind.restore(last + 5));
+        assert_eq!(2 * INITIAL_INTERVAL, rewind.steps_to_next(last));
+    }
+}
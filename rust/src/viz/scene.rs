@@ -0,0 +1,330 @@
+//! A resolution-independent description of what a visualizer draws, so it can be rendered in the
+//! browser as well as in the terminal.
+
+use std::fmt::Write;
+
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::Color,
+    symbols::Marker,
+    text::Span,
+    widgets::canvas::{Canvas, Line, Points, Rectangle},
+};
+
+use super::json_string;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Rgba {
+    pub(super) r: u8,
+    pub(super) g: u8,
+    pub(super) b: u8,
+    pub(super) a: f32,
+}
+
+impl Rgba {
+    pub(crate) const BLACK: Self = Self::rgb(0, 0, 0);
+    pub(crate) const WHITE: Self = Self::rgb(255, 255, 255);
+
+    pub(crate) const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b, a: 1.0 }
+    }
+
+    pub(crate) const fn with_alpha(self, a: f32) -> Self {
+        Self { a, ..self }
+    }
+
+    /// Composite this color over an opaque one.
+    pub(crate) fn over(self, below: Self) -> Self {
+        let mix = |above: u8, below: u8| {
+            (above as f32 * self.a + below as f32 * (1.0 - self.a)).round() as u8
+        };
+        Self::rgb(
+            mix(self.r, below.r),
+            mix(self.g, below.g),
+            mix(self.b, below.b),
+        )
+    }
+
+    fn css(self) -> String {
+        format!("rgba({}, {}, {}, {})", self.r, self.g, self.b, self.a)
+    }
+}
+
+impl From<Rgba> for Color {
+    fn from(color: Rgba) -> Self {
+        Self::Rgb(color.r, color.g, color.b)
+    }
+}
+
+pub(crate) enum Shape {
+    /// Unit cells, as `(row, col)`.
+    Cells(Vec<(usize, usize)>),
+    /// A line through the centers of cells, as `(row, col)`, with a width in cells.
+    Path {
+        cells: Vec<(usize, usize)>,
+        width: f64,
+    },
+    /// Filled rectangles, as `[x, y, width, height]`.
+    Rects(Vec<[f64; 4]>),
+    /// Text centered on cells, as `(row, col)`.
+    Labels(Vec<((usize, usize), String)>),
+}
+
+impl Shape {
+    pub(super) fn contains(&self, row: usize, col: usize) -> bool {
+        match self {
+            Self::Cells(cells) => cells.contains(&(row, col)),
+            Self::Path { cells, .. } => path_cells(cells).contains(&(row, col)),
+            Self::Rects(rects) => {
+                let (x, y) = (col as f64 + 0.5, row as f64 + 0.5);
+                rects
+                    .iter()
+                    .any(|&[rx, ry, w, h]| (rx..rx + w).contains(&x) && (ry..ry + h).contains(&y))
+            }
+            Self::Labels(labels) => labels.iter().any(|(cell, _)| *cell == (row, col)),
+        }
+    }
+}
+
+pub(crate) struct Layer {
+    pub(super) name: &'static str,
+    pub(super) color: Rgba,
+    pub(super) shape: Shape,
+}
+
+/// Layers of shapes drawn in order over a background, in a coordinate space where a cell is one
+/// unit and the y axis points down.
+pub(crate) struct Scene {
+    pub(super) width: f64,
+    pub(super) height: f64,
+    pub(super) background: Rgba,
+    pub(super) layers: Vec<Layer>,
+}
+
+impl Scene {
+    pub(crate) fn new(width: f64, height: f64) -> Self {
+        Self {
+            width,
+            height,
+            background: Rgba::BLACK,
+            layers: Vec::new(),
+        }
+    }
+
+    pub(crate) fn with_background(mut self, background: Rgba) -> Self {
+        self.background = background;
+        self
+    }
+
+    pub(crate) fn layer(&mut self, name: &'static str, color: Rgba, shape: Shape) {
+        self.layers.push(Layer { name, color, shape });
+    }
+
+    /// The names of the layers drawn on a cell.
+    pub(crate) fn layers_at(&self, row: usize, col: usize) -> Vec<&'static str> {
+        let mut names = Vec::new();
+        for layer in &self.layers {
+            if layer.shape.contains(row, col) && !names.contains(&layer.name) {
+                names.push(layer.name);
+            }
+        }
+        names
+    }
+
+    pub(crate) fn to_json(&self) -> String {
+        let mut json = format!(
+            r#"{{"width": {}, "height": {}, "background": {}, "layers": ["#,
+            self.width,
+            self.height,
+            json_string(&self.background.css())
+        );
+
+        for (i, layer) in self.layers.iter().enumerate() {
+            if i > 0 {
+                json.push_str(", ");
+            }
+            write!(
+                json,
+                r#"{{"name": {}, "color": {}, "#,
+                json_string(layer.name),
+                json_string(&layer.color.css())
+            )
+            .unwrap();
+
+            let cells_json = |cells: &[(usize, usize)]| {
+                let cells = cells
+                    .iter()
+                    .map(|(row, col)| format!("[{row}, {col}]"))
+                    .collect::<Vec<_>>();
+                format!("[{}]", cells.join(", "))
+            };
+            match &layer.shape {
+                Shape::Cells(cells) => {
+                    write!(json, r#""kind": "cells", "cells": {}}}"#, cells_json(cells))
+                }
+                Shape::Path { cells, width } => write!(
+                    json,
+                    r#""kind": "path", "width": {width}, "cells": {}}}"#,
+                    cells_json(cells)
+                ),
+                Shape::Rects(rects) => {
+                    let rects = rects
+                        .iter()
+                        .map(|[x, y, w, h]| format!("[{x}, {y}, {w}, {h}]"))
+                        .collect::<Vec<_>>();
+                    write!(
+                        json,
+                        r#""kind": "rects", "rects": [{}]}}"#,
+                        rects.join(", ")
+                    )
+                }
+                Shape::Labels(labels) => {
+                    let labels = labels
+                        .iter()
+                        .map(|((row, col), text)| format!("[{row}, {col}, {}]", json_string(text)))
+                        .collect::<Vec<_>>();
+                    write!(
+                        json,
+                        r#""kind": "labels", "labels": [{}]}}"#,
+                        labels.join(", ")
+                    )
+                }
+            }
+            .unwrap();
+        }
+
+        json.push_str("]}");
+        json
+    }
+
+    /// Draw the scene on a braille canvas, stretched to fill `area`. Rectangles are outlined.
+    pub(crate) fn render(&self, frame: &mut Frame, area: Rect) {
+        // The canvas y axis points up.
+        let height = self.height;
+        let center =
+            move |&(row, col): &(usize, usize)| (col as f64 + 0.5, height - row as f64 - 0.5);
+
+        let canvas = Canvas::default()
+            .marker(Marker::Braille)
+            .background_color(self.background.into())
+            .x_bounds([0.0, self.width])
+            .y_bounds([0.0, self.height])
+            .paint(|ctx| {
+                for layer in &self.layers {
+                    let color = layer.color.into();
+                    match &layer.shape {
+                        Shape::Cells(cells) => {
+                            let coords = cells.iter().map(center).collect::<Vec<_>>();
+                            ctx.draw(&Points {
+                                coords: &coords,
+                                color,
+                            });
+                        }
+                        Shape::Path { cells, .. } => {
+                            for (from, to) in cells
+                                .iter()
+                                .map(center)
+                                .zip(cells.iter().skip(1).map(center))
+                            {
+                                ctx.draw(&Line::new(from.0, from.1, to.0, to.1, color));
+                            }
+                        }
+                        Shape::Rects(rects) => {
+                            for &[x, y, width, height] in rects {
+                                ctx.draw(&Rectangle {
+                                    x,
+                                    y: self.height - y - height,
+                                    width,
+                                    height,
+                                    color,
+                                });
+                            }
+                        }
+                        Shape::Labels(labels) => {
+                            for (cell, text) in labels {
+                                let (x, y) = center(cell);
+                                ctx.print(x, y, Span::styled(text.clone(), color));
+                            }
+                        }
+                    }
+                    ctx.layer();
+                }
+            });
+
+        frame.render_widget(canvas, area);
+    }
+}
+
+/// Every cell on a path through `points`, filling in the straight lines between them.
+pub(crate) fn path_cells(points: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    let mut cells = points.first().copied().into_iter().collect::<Vec<_>>();
+    for (&(r0, c0), &(r1, c1)) in points.iter().zip(points.iter().skip(1)) {
+        let (dr, dc) = (r1 as f64 - r0 as f64, c1 as f64 - c0 as f64);
+        let n = dr.abs().max(dc.abs()) as usize;
+        for i in 1..=n {
+            let t = i as f64 / n as f64;
+            let cell = (
+                (r0 as f64 + dr * t).round() as usize,
+                (c0 as f64 + dc * t).round() as usize,
+            );
+            if cells.last() != Some(&cell) {
+                cells.push(cell);
+            }
+        }
+    }
+    cells
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Rgba, Scene, Shape, path_cells};
+
+    #[test]
+    fn to_json() {
+        let mut scene = Scene::new(3.0, 2.0);
+        scene.layer("walls", Rgba::rgb(255, 0, 0), Shape::Cells(vec![(0, 1)]));
+        scene.layer(
+            "path",
+            Rgba::WHITE.with_alpha(0.5),
+            Shape::Path {
+                cells: vec![(1, 0), (1, 2)],
+                width: 0.8,
+            },
+        );
+        scene.layer(
+            "bars",
+            Rgba::BLACK,
+            Shape::Rects(vec![[0.5, 0.0, 2.0, 1.0]]),
+        );
+        scene.layer(
+            "labels",
+            Rgba::WHITE,
+            Shape::Labels(vec![((1, 2), "\"9\"".to_owned())]),
+        );
+
+        assert_eq!(
+            concat!(
+                r#"{"width": 3, "height": 2, "background": "rgba(0, 0, 0, 1)", "layers": ["#,
+                r#"{"name": "walls", "color": "rgba(255, 0, 0, 1)", "kind": "cells", "cells": [[0, 1]]}, "#,
+                r#"{"name": "path", "color": "rgba(255, 255, 255, 0.5)", "kind": "path", "width": 0.8, "cells": [[1, 0], [1, 2]]}, "#,
+                r#"{"name": "bars", "color": "rgba(0, 0, 0, 1)", "kind": "rects", "rects": [[0.5, 0, 2, 1]]}, "#,
+                r#"{"name": "labels", "color": "rgba(255, 255, 255, 1)", "kind": "labels", "labels": [[1, 2, "\"9\""]]}"#,
+                "]}"
+            ),
+            scene.to_json()
+        );
+
+        assert_eq!(vec!["walls", "bars"], scene.layers_at(0, 1));
+        assert_eq!(vec!["path"], scene.layers_at(1, 1));
+        assert_eq!(vec!["path", "labels"], scene.layers_at(1, 2));
+    }
+
+    #[test]
+    fn fill_in_paths() {
+        assert_eq!(
+            vec![(0, 0), (0, 1), (0, 2), (1, 2), (2, 2), (3, 3)],
+            path_cells(&[(0, 0), (0, 2), (2, 2), (3, 3)])
+        );
+    }
+}
@@ -0,0 +1,273 @@
+//! Serve a visualization to the browser, for when there's no desktop session, e.g. over SSH port
+//! forwarding.
+//!
+//! The page draws the frames it receives as server-sent events from `/events`, and posts key
+//! presses to `/key`, named as they are in a headless script. Only requests addressed to the
+//! server's own address are answered, so other sites can't send keys from the browser.
+
+use std::{
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    sync::{Arc, Mutex, OnceLock, mpsc},
+    thread,
+    time::Duration,
+};
+
+use anyhow::{Context, Result, bail};
+use crossterm::event::KeyEvent;
+
+use super::headless::{self, ScriptEvent};
+
+static OPTIONS: OnceLock<WebOptions> = OnceLock::new();
+
+const INDEX_HTML: &str = include_str!("web/index.html");
+
+/// The largest request body accepted, which only ever holds a key name.
+const MAX_BODY_LEN: usize = 64;
+
+const WRITE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The number of events a client can fall behind by before it's dropped.
+const MAX_PENDING_EVENTS: usize = 16;
+
+pub(crate) struct WebOptions {
+    pub(crate) addr: SocketAddr,
+}
+
+/// Serve the next visualization that runs.
+pub(crate) fn enable(options: WebOptions) -> Result<()> {
+    if OPTIONS.set(options).is_err() {
+        bail!("web mode is already enabled");
+    }
+    Ok(())
+}
+
+pub(super) fn options() -> Option<&'static WebOptions> {
+    OPTIONS.get()
+}
+
+#[derive(Default)]
+struct Clients {
+    clients: Vec<Client>,
+    /// The last published frame, sent to clients as soon as they connect.
+    latest: Option<String>,
+}
+
+/// A client of `/events`, which its connection's thread writes the events to.
+struct Client {
+    events: mpsc::SyncSender<String>,
+    /// Disconnected once the thread has stopped writing.
+    done: mpsc::Receiver<()>,
+}
+
+pub(super) struct Server {
+    addr: SocketAddr,
+    keys: mpsc::Receiver<KeyEvent>,
+    clients: Arc<Mutex<Clients>>,
+}
+
+impl Server {
+    pub(super) fn start(addr: SocketAddr) -> Result<Self> {
+        let listener =
+            TcpListener::bind(addr).with_context(|| format!("failed to listen on {addr}"))?;
+        let addr = listener.local_addr()?;
+        let (keys_tx, keys) = mpsc::channel();
+        let clients = Arc::<Mutex<Clients>>::default();
+
+        let server_clients = Arc::clone(&clients);
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let keys_tx = keys_tx.clone();
+                let clients = Arc::clone(&server_clients);
+                thread::spawn(move || {
+                    // Errors are the client's problem, e.g. it went away mid-request.
+                    _ = handle_connection(stream, addr, &keys_tx, &clients);
+                });
+            }
+        });
+
+        Ok(Self {
+            addr,
+            keys,
+            clients,
+        })
+    }
+
+    pub(super) fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Send a frame to every client, unless it's the same as the last one. Clients that have gone
+    /// away or fallen too far behind are dropped.
+    pub(super) fn publish(&self, frame: String) {
+        let mut clients = self.clients.lock().unwrap();
+        if clients.latest.as_ref() == Some(&frame) {
+            return;
+        }
+        let event = format!("data: {frame}\n\n");
+        clients
+            .clients
+            .retain(|client| client.events.try_send(event.clone()).is_ok());
+        clients.latest = Some(frame);
+    }
+
+    /// Wait up to `timeout` for a key press from any client.
+    pub(super) fn next_key(&self, timeout: Duration) -> Result<Option<KeyEvent>> {
+        match self.keys.recv_timeout(timeout) {
+            Ok(key) => Ok(Some(key)),
+            Err(mpsc::RecvTimeoutError::Timeout) => Ok(None),
+            Err(mpsc::RecvTimeoutError::Disconnected) => bail!("the web server stopped"),
+        }
+    }
+
+    /// Tell the clients the visualization has ended, waiting until they've been told.
+    pub(super) fn close(self) {
+        let clients = std::mem::take(&mut self.clients.lock().unwrap().clients);
+        for client in &clients {
+            _ = client.events.try_send("event: end\ndata: \n\n".to_owned());
+        }
+        for Client { events, done } in clients {
+            drop(events);
+            // Writes time out, so this doesn't wait for long.
+            _ = done.recv();
+        }
+    }
+}
+
+fn handle_connection(
+    mut stream: TcpStream,
+    addr: SocketAddr,
+    keys: &mpsc::Sender<KeyEvent>,
+    clients: &Mutex<Clients>,
+) -> io::Result<()> {
+    stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+
+    let mut content_length = 0;
+    let mut host = None;
+    let mut origin = None;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+            break;
+        }
+        let Some((name, value)) = header.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        if name.eq_ignore_ascii_case("content-length") {
+            content_length = value.parse().unwrap_or_default();
+        } else if name.eq_ignore_ascii_case("host") {
+            host = Some(value.to_owned());
+        } else if name.eq_ignore_ascii_case("origin") {
+            origin = Some(value.to_owned());
+        }
+    }
+
+    // Guard against DNS rebinding, where another site's name resolves to this server.
+    let Some(host) = host.filter(|host| is_own_host(host, addr)) else {
+        return respond(&mut stream, "403 Forbidden", "text/plain", "unknown host");
+    };
+
+    let mut parts = request_line.split_whitespace();
+    match (parts.next(), parts.next()) {
+        (Some("GET"), Some("/")) => respond(
+            &mut stream,
+            "200 OK",
+            "text/html; charset=utf-8",
+            INDEX_HTML,
+        ),
+        (Some("GET"), Some("/events")) => {
+            stream.write_all(
+                b"HTTP/1.1 200 OK\r\n\
+                Content-Type: text/event-stream\r\n\
+                Cache-Control: no-cache\r\n\r\n",
+            )?;
+
+            let (events_tx, events) = mpsc::sync_channel(MAX_PENDING_EVENTS);
+            let (_done, done) = mpsc::channel();
+            {
+                let mut clients = clients.lock().unwrap();
+                if let Some(frame) = &clients.latest {
+                    _ = events_tx.try_send(format!("data: {frame}\n\n"));
+                }
+                clients.clients.push(Client {
+                    events: events_tx,
+                    done,
+                });
+            }
+
+            // Write on this connection's thread, so a slow client doesn't hold up the others.
+            for event in events {
+                stream.write_all(event.as_bytes())?;
+            }
+            Ok(())
+        }
+        (Some("POST"), Some("/key")) => {
+            // Browsers send an origin with cross-site posts, which mustn't press keys.
+            if origin.is_some_and(|origin| origin != format!("http://{host}")) {
+                return respond(&mut stream, "403 Forbidden", "text/plain", "unknown origin");
+            }
+            if content_length > MAX_BODY_LEN {
+                return respond(&mut stream, "413 Content Too Large", "text/plain", "");
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body)?;
+
+            let key = String::from_utf8_lossy(&body);
+            match headless::parse_event(key.trim()) {
+                Ok(ScriptEvent::Key(key)) => {
+                    // The visualization may have just ended.
+                    _ = keys.send(key);
+                    respond(&mut stream, "204 No Content", "text/plain", "")
+                }
+                Ok(ScriptEvent::Tick) | Err(_) => {
+                    respond(&mut stream, "400 Bad Request", "text/plain", "unknown key")
+                }
+            }
+        }
+        _ => respond(&mut stream, "404 Not Found", "text/plain", "not found"),
+    }
+}
+
+/// Whether a `Host` header names the server's address, by IP or as `localhost`.
+fn is_own_host(host: &str, addr: SocketAddr) -> bool {
+    if let Some(port) = host.strip_prefix("localhost:") {
+        return port.parse() == Ok(addr.port());
+    }
+    host.parse::<SocketAddr>().is_ok_and(|host| {
+        host.port() == addr.port() && (host.ip() == addr.ip() || addr.ip().is_unspecified())
+    })
+}
+
+fn respond(stream: &mut TcpStream, status: &str, content_type: &str, body: &str) -> io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\n\
+        Content-Type: {content_type}\r\n\
+        Content-Length: {}\r\n\
+        Connection: close\r\n\r\n\
+        {body}",
+        body.len()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn is_own_host() {
+        let addr = "127.0.0.1:8000".parse().unwrap();
+        assert!(super::is_own_host("127.0.0.1:8000", addr));
+        assert!(super::is_own_host("localhost:8000", addr));
+        assert!(!super::is_own_host("127.0.0.1:8001", addr));
+        assert!(!super::is_own_host("localhost", addr));
+        assert!(!super::is_own_host("example.com:8000", addr));
+
+        let any = "0.0.0.0:8000".parse().unwrap();
+        assert!(super::is_own_host("192.168.1.2:8000", any));
+        assert!(!super::is_own_host("example.com:8000", any));
+    }
+}